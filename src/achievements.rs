@@ -0,0 +1,213 @@
+// Badges for milestones in the history. Like `scoring`, this module is kept free of
+// `seed` and `web_sys` so that it can be unit tested natively.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::TextKey;
+use crate::model::{Data, WorkSleep, WorkSleepData, WorkSleepGoals};
+
+const PERFECT_SCORE: i64 = 100;
+const STREAK_DAYS: i64 = 7;
+const TOTAL_WORK_PERIODS: f64 = 50.0;
+const STEADY_BEDTIME_NIGHTS: i64 = 5;
+const STEADY_BEDTIME_MINUTES: i64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Badge {
+    PerfectDay,
+    WeekStreak,
+    FiftyWorkPeriods,
+    SteadyBedtimes,
+}
+
+impl Badge {
+    pub const ALL: [Badge; 4] = [
+        Badge::PerfectDay,
+        Badge::WeekStreak,
+        Badge::FiftyWorkPeriods,
+        Badge::SteadyBedtimes,
+    ];
+
+    pub fn name(self) -> TextKey {
+        match self {
+            Badge::PerfectDay => TextKey::BadgePerfectDay,
+            Badge::WeekStreak => TextKey::BadgeWeekStreak,
+            Badge::FiftyWorkPeriods => TextKey::BadgeFiftyWorkPeriods,
+            Badge::SteadyBedtimes => TextKey::BadgeSteadyBedtimes,
+        }
+    }
+
+    pub fn criteria(self) -> TextKey {
+        match self {
+            Badge::PerfectDay => TextKey::BadgePerfectDayCriteria,
+            Badge::WeekStreak => TextKey::BadgeWeekStreakCriteria,
+            Badge::FiftyWorkPeriods => TextKey::BadgeFiftyWorkPeriodsCriteria,
+            Badge::SteadyBedtimes => TextKey::BadgeSteadyBedtimesCriteria,
+        }
+    }
+
+    // The first date on which the days on record met the badge's criteria.
+    fn earned_on(self, data: &WorkSleepData, defaults: &WorkSleepGoals) -> Option<NaiveDate> {
+        match self {
+            Badge::PerfectDay => data
+                .days()
+                .iter()
+                .find(|(date, ws)| data.score(**date, ws, defaults) >= PERFECT_SCORE)
+                .map(|(date, _)| *date),
+            Badge::WeekStreak => end_of_first_run(data, STREAK_DAYS, |ws| ws.met_targets(defaults)),
+            Badge::FiftyWorkPeriods => {
+                let mut total = 0.0;
+                data.days()
+                    .iter()
+                    .find(|(_, ws)| {
+                        total += ws.weighted_work_count();
+                        total >= TOTAL_WORK_PERIODS
+                    })
+                    .map(|(date, _)| *date)
+            }
+            Badge::SteadyBedtimes => end_of_first_run(data, STEADY_BEDTIME_NIGHTS, |ws| {
+                ws.bedtime_deviation(defaults)
+                    .is_some_and(|deviation| deviation.abs() <= STEADY_BEDTIME_MINUTES)
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Achievement {
+    pub badge: Badge,
+    pub earned_on: NaiveDate,
+}
+
+// The badges that `data` has earned but doesn't hold yet. Once they're added to
+// `data.achievements`, checking again finds nothing new.
+pub fn check_achievements(data: &Data) -> Vec<Achievement> {
+    Badge::ALL
+        .iter()
+        .filter(|badge| !data.achievements.iter().any(|held| held.badge == **badge))
+        .filter_map(|badge| {
+            badge
+                .earned_on(&data.work_sleep_data, &data.default_work_sleep_goals)
+                .map(|earned_on| Achievement {
+                    badge: *badge,
+                    earned_on,
+                })
+        })
+        .collect()
+}
+
+// The last day of the first run of `days` consecutive dates that all pass `qualifies`.
+fn end_of_first_run(
+    data: &WorkSleepData,
+    days: i64,
+    qualifies: impl Fn(&WorkSleep) -> bool,
+) -> Option<NaiveDate> {
+    data.runs(qualifies)
+        .find(|(_, run)| *run == days)
+        .map(|(date, _)| date)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::model::{shift_days, Bedtime};
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn bedtime(h: u32, m: u32) -> Option<Bedtime> {
+        Some(Bedtime {
+            time: NaiveTime::from_hms_opt(h, m, 0).unwrap(),
+            next_day: false,
+        })
+    }
+
+    // Days from `start` on, each with the work count and bedtime given.
+    fn days(start: NaiveDate, days: &[(i64, Option<Bedtime>)]) -> Data {
+        let mut data = Data::new(start);
+        for (i, (work, bedtime)) in days.iter().enumerate() {
+            let ws = data
+                .work_sleep_data
+                .get_mut_or_create(&shift_days(start, i as i64));
+            ws.actual_work_count = *work;
+            ws.actual_bedtime = bedtime.clone();
+        }
+        data
+    }
+
+    fn earned_on(data: &Data, badge: Badge) -> Option<NaiveDate> {
+        badge.earned_on(&data.work_sleep_data, &data.default_work_sleep_goals)
+    }
+
+    #[test]
+    fn perfect_days_need_a_score_of_100() {
+        let data = days(
+            ymd(2021, 5, 1),
+            &[
+                (6, bedtime(23, 30)),
+                (6, bedtime(23, 0)),
+                (6, bedtime(23, 0)),
+            ],
+        );
+        assert_eq!(earned_on(&data, Badge::PerfectDay), Some(ymd(2021, 5, 2)));
+        let data = days(ymd(2021, 5, 1), &[(5, bedtime(23, 0))]);
+        assert_eq!(earned_on(&data, Badge::PerfectDay), None);
+    }
+
+    #[test]
+    fn streaks_and_steady_bedtimes_need_consecutive_days() {
+        let mut week: Vec<(i64, Option<Bedtime>)> = vec![(6, bedtime(23, 10)); 8];
+        week[2] = (6, bedtime(23, 11));
+        let data = days(ymd(2021, 5, 1), &week);
+        assert_eq!(earned_on(&data, Badge::WeekStreak), Some(ymd(2021, 5, 7)));
+        assert_eq!(
+            earned_on(&data, Badge::SteadyBedtimes),
+            Some(ymd(2021, 5, 8))
+        );
+
+        // A day without data breaks the run.
+        let mut data = days(ymd(2021, 5, 1), &week[..3]);
+        let rest = days(ymd(2021, 5, 5), &week[3..]);
+        data.work_sleep_data
+            .days_mut()
+            .extend(rest.work_sleep_data.days().clone());
+        assert_eq!(earned_on(&data, Badge::WeekStreak), None);
+    }
+
+    #[test]
+    fn work_periods_add_up_across_days() {
+        let data = days(
+            ymd(2021, 5, 1),
+            &[(30, None), (-5, None), (19, None), (1, None)],
+        );
+        assert_eq!(
+            earned_on(&data, Badge::FiftyWorkPeriods),
+            Some(ymd(2021, 5, 4))
+        );
+    }
+
+    #[test]
+    fn checking_again_finds_nothing_new() {
+        let mut data = days(ymd(2021, 5, 1), &[(60, bedtime(23, 0))]);
+        let earned = check_achievements(&data);
+        assert_eq!(
+            earned,
+            vec![
+                Achievement {
+                    badge: Badge::PerfectDay,
+                    earned_on: ymd(2021, 5, 1),
+                },
+                Achievement {
+                    badge: Badge::FiftyWorkPeriods,
+                    earned_on: ymd(2021, 5, 1),
+                },
+            ]
+        );
+        data.achievements.extend(earned);
+        assert!(check_achievements(&data).is_empty());
+    }
+}
@@ -0,0 +1,1162 @@
+// The changes that can be made to `Data`, as a state machine that needs no browser. The
+// Seed app turns its messages into `Action`s; everything only the page cares about, like
+// forms, timers and notices, stays in `update`. Like `scoring`, this module is kept free
+// of `seed` and `web_sys` so that it can be used and unit tested natively.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+use chrono::{NaiveDate, NaiveTime};
+use uuid::Uuid;
+
+use crate::i18n::{Lang, TextKey};
+use crate::model::{
+    find_task, group_containing, insert_tasks, remove_tasks, sort_by_priority, Bedtime,
+    ClockFormat, CompletedPeriod, Data, DateFormat, Period, Priority, RecurringTask, Theme,
+    WeekMode, WorkSleepGoals, MAX_BEDTIME_REMINDER_LEAD_MINUTES, MAX_BREAK_MINUTES,
+    MAX_CONSISTENCY_DAYS, MAX_DAY_CUTOFF_HOUR, MAX_POMODORO_MINUTES, MAX_SLEEP_MINUTES,
+    MAX_TASK_QUANTITY, MIN_CONSISTENCY_NIGHTS, MOOD_EMOJIS,
+};
+
+// Actions that depend on the time take it as `today` or `now`, so that applying the same
+// actions to the same data always gives the same result. Unless a date is given, tasks
+// are looked for in the plan of the current date.
+#[derive(Clone, Debug)]
+pub enum Action {
+    SetCurrentDate {
+        date: NaiveDate,
+        today: NaiveDate,
+    },
+    // Adds `quantity` copies of `task` to the plan of `date`, each with its own id.
+    AddTasks {
+        date: NaiveDate,
+        task: Period,
+        quantity: i64,
+        today: NaiveDate,
+    },
+    TogglePinQuickTask(String),
+    RemoveQuickTask(String),
+    DeleteTasks(NaiveDate, Vec<Uuid>),
+    // Puts deleted periods back into the plan of the date, from the index.
+    RestoreTasks(NaiveDate, usize, Vec<Period>),
+    PostponeTask {
+        id: Uuid,
+        now: NaiveTime,
+    },
+    // Editing a task looks for it in the plans of every date, so that upcoming tasks can
+    // be changed before their date.
+    RenameTask(Uuid, String),
+    SetTaskNote(Uuid, String),
+    MoveTaskToTop(Uuid),
+    MoveTaskUp(Uuid),
+    MoveTaskDown(Uuid),
+    MoveTaskToBottom(Uuid),
+    // Moving several tasks keeps them together, and past whole groups of repeated tasks.
+    MoveTasksToTop(Vec<Uuid>),
+    MoveTasksUp(Vec<Uuid>),
+    MoveTasksDown(Vec<Uuid>),
+    MoveTasksToBottom(Vec<Uuid>),
+    SetTaskPriority(Uuid, Priority),
+    SetTasksPriority(Vec<Uuid>, Priority),
+    // Stable, so tasks of the same priority keep their order.
+    SortTasksByPriority,
+    CopyUnfinishedTasksFromYesterday,
+    // Starts timing the task, stopping any other.
+    StartTask {
+        id: Uuid,
+        now: NaiveTime,
+    },
+    FinishTopTask {
+        now: NaiveTime,
+        today: NaiveDate,
+    },
+    AddRecurringTask(RecurringTask),
+    DeleteRecurringTask(Uuid),
+    // `None` clears the bedtime.
+    SetBedtime {
+        date: NaiveDate,
+        bedtime: Option<Bedtime>,
+        today: NaiveDate,
+    },
+    // The time woken up after the night of `night`.
+    SetWakeTime {
+        night: NaiveDate,
+        wake_time: Bedtime,
+        today: NaiveDate,
+    },
+    // Starts an empty day of data.
+    CreateDayEntry {
+        date: NaiveDate,
+        today: NaiveDate,
+    },
+    // Never takes the work count below zero.
+    AdjustWorkCount {
+        date: NaiveDate,
+        delta: i64,
+        today: NaiveDate,
+    },
+    // From 1 to the number of `MOOD_EMOJIS`. Setting the mood a date already has clears it.
+    SetMood {
+        date: NaiveDate,
+        mood: u8,
+        today: NaiveDate,
+    },
+    SetDayGoals(NaiveDate, WorkSleepGoals),
+    ResetDayGoals(NaiveDate),
+    // The review notes of the week starting on the date.
+    SetWeeklyNote(NaiveDate, String),
+    SetDefaultGoals(WorkSleepGoals),
+    CompleteOnboarding(WorkSleepGoals),
+    // Switches between weekend goals starting from the defaults and none.
+    ToggleWeekendGoals,
+    // Ignored while there are no weekend goals.
+    SetWeekendGoals(WorkSleepGoals),
+    ArchiveOldWeeks {
+        today: NaiveDate,
+        after_days: u32,
+    },
+    ToggleOneClickDelete,
+    ToggleTargetReachedSound,
+    ToggleGroupRepeatedTasks,
+    ToggleBreaks,
+    SetTheme(Theme),
+    SetDateFormat(DateFormat),
+    SetClockFormat(ClockFormat),
+    SetLang(Lang),
+    SetWeekMode(WeekMode),
+    SetPomodoroMinutes(i64),
+    SetBreakMinutes(i64),
+    SetBedtimeReminderLead(i64),
+    SetDayCutoffHour(u32),
+    SetConsistencyDays(u32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionError {
+    // The task isn't planned, say because another tab deleted or finished it.
+    MissingTask,
+    // The date, or the current date for the action, hasn't come yet.
+    NotYet(NaiveDate),
+    OutOfRange,
+    // The wake time is too long after the night's bedtime to be the same night's sleep.
+    SleepTooLong,
+    EmptyName,
+}
+
+impl ActionError {
+    pub fn describe(&self, lang: Lang) -> String {
+        match self {
+            ActionError::MissingTask => lang.tr(TextKey::MissingTaskError).to_owned(),
+            ActionError::NotYet(date) => lang.trf(TextKey::NotYetError, &[date]),
+            ActionError::OutOfRange => lang.tr(TextKey::OutOfRangeError).to_owned(),
+            ActionError::SleepTooLong => {
+                lang.trf(TextKey::SleepTooLongError, &[&(MAX_SLEEP_MINUTES / 60)])
+            }
+            ActionError::EmptyName => lang.tr(TextKey::EmptyNameError).to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.describe(Lang::English))
+    }
+}
+
+impl Error for ActionError {}
+
+// Applies `action` to `data`. When an error is returned, `data` was left as it was.
+pub fn apply(data: &mut Data, action: Action) -> Result<(), ActionError> {
+    match action {
+        Action::SetCurrentDate { date, today } => {
+            let last_date = data.current_date;
+            data.current_date = date;
+            if date == today {
+                data.carry_over_unfinished_tasks(last_date, today);
+            }
+            data.work_sleep_data.set_week_start(&date, data.week_mode);
+            data.add_recurring_tasks(today);
+        }
+        Action::AddTasks {
+            date,
+            task,
+            quantity,
+            today,
+        } => {
+            if task.name.trim().is_empty() {
+                return Err(ActionError::EmptyName);
+            }
+            if !(1..=MAX_TASK_QUANTITY).contains(&quantity) {
+                return Err(ActionError::OutOfRange);
+            }
+            data.record_task_use(&task.name, today);
+            let plan = data.plan_mut(date);
+            for _ in 0..quantity {
+                plan.push_back(Period {
+                    id: Uuid::new_v4(),
+                    ..task.clone()
+                });
+            }
+        }
+        Action::TogglePinQuickTask(name) => {
+            let pinned = &mut data.pinned_quick_tasks;
+            if !pinned.remove(&name) {
+                pinned.insert(name);
+            }
+        }
+        Action::RemoveQuickTask(name) => {
+            data.forget_quick_task(&name);
+        }
+        Action::DeleteTasks(date, ids) => {
+            remove_tasks(existing_plan(data, date)?, &ids).ok_or(ActionError::MissingTask)?;
+        }
+        Action::RestoreTasks(date, index, periods) => {
+            let plan = data.plan_mut(date);
+            // Undoing the deletion some other way may have restored them already.
+            let periods: Vec<Period> = periods
+                .into_iter()
+                .filter(|period| plan.iter().all(|wp| wp.id != period.id))
+                .collect();
+            let index = index.min(plan.len());
+            insert_tasks(plan, index, periods);
+        }
+        Action::PostponeTask { id, now } => {
+            data.postpone_task(id, now)
+                .ok_or(ActionError::MissingTask)?;
+        }
+        Action::RenameTask(id, name) => {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(ActionError::EmptyName);
+            }
+            let wp = data.planned_task_mut(id).ok_or(ActionError::MissingTask)?;
+            wp.name = name.to_owned();
+        }
+        Action::SetTaskNote(id, note) => {
+            let wp = data.planned_task_mut(id).ok_or(ActionError::MissingTask)?;
+            wp.note = note;
+        }
+        Action::MoveTaskToTop(id) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let i = find_task(periods, id).ok_or(ActionError::MissingTask)?;
+            let wp = periods.remove(i).ok_or(ActionError::MissingTask)?;
+            periods.push_front(wp);
+        }
+        Action::MoveTaskUp(id) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let i = find_task(periods, id).ok_or(ActionError::MissingTask)?;
+            if let Some(j) = i.checked_sub(1) {
+                periods.swap(i, j);
+            }
+        }
+        Action::MoveTaskDown(id) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let i = find_task(periods, id).ok_or(ActionError::MissingTask)?;
+            if i + 1 < periods.len() {
+                periods.swap(i, i + 1);
+            }
+        }
+        Action::MoveTaskToBottom(id) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let i = find_task(periods, id).ok_or(ActionError::MissingTask)?;
+            let wp = periods.remove(i).ok_or(ActionError::MissingTask)?;
+            periods.push_back(wp);
+        }
+        Action::MoveTasksToTop(ids) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let (_, tasks) = remove_tasks(periods, &ids).ok_or(ActionError::MissingTask)?;
+            insert_tasks(periods, 0, tasks);
+        }
+        Action::MoveTasksUp(ids) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let (start, tasks) = remove_tasks(periods, &ids).ok_or(ActionError::MissingTask)?;
+            let target = match start.checked_sub(1) {
+                Some(previous) => group_containing(periods, previous).start,
+                None => start,
+            };
+            insert_tasks(periods, target, tasks);
+        }
+        Action::MoveTasksDown(ids) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let (start, tasks) = remove_tasks(periods, &ids).ok_or(ActionError::MissingTask)?;
+            let target = if start < periods.len() {
+                group_containing(periods, start).end
+            } else {
+                start
+            };
+            insert_tasks(periods, target, tasks);
+        }
+        Action::MoveTasksToBottom(ids) => {
+            let periods = existing_plan(data, data.current_date)?;
+            let (_, tasks) = remove_tasks(periods, &ids).ok_or(ActionError::MissingTask)?;
+            let len = periods.len();
+            insert_tasks(periods, len, tasks);
+        }
+        Action::SetTaskPriority(id, priority) => {
+            let wp = data.planned_task_mut(id).ok_or(ActionError::MissingTask)?;
+            wp.priority = priority;
+        }
+        Action::SetTasksPriority(ids, priority) => {
+            let mut found = false;
+            for wp in data.planned_work_periods.values_mut().flatten() {
+                if ids.contains(&wp.id) {
+                    wp.priority = priority;
+                    found = true;
+                }
+            }
+            if !found {
+                return Err(ActionError::MissingTask);
+            }
+        }
+        Action::SortTasksByPriority => {
+            sort_by_priority(data.current_plan_mut());
+        }
+        Action::CopyUnfinishedTasksFromYesterday => {
+            let date = data.current_date;
+            let copies: Vec<Period> = date
+                .pred_opt()
+                .map(|yesterday| data.plan(yesterday).iter())
+                .into_iter()
+                .flatten()
+                .map(|wp| Period {
+                    category: wp.category.clone(),
+                    note: wp.note.clone(),
+                    priority: wp.priority,
+                    weight: wp.weight,
+                    ..Period::new(wp.name.clone())
+                })
+                .collect();
+            data.plan_mut(date).extend(copies);
+        }
+        Action::StartTask { id, now } => {
+            if !data.has_planned_task(id) {
+                return Err(ActionError::MissingTask);
+            }
+            for wp in data.planned_work_periods.values_mut().flatten() {
+                if wp.id == id {
+                    if wp.started_at.is_none() {
+                        wp.started_at = Some(now);
+                    }
+                } else {
+                    wp.stop_timer(now);
+                }
+            }
+        }
+        Action::FinishTopTask { now, today } => {
+            let date = data.current_date;
+            if date > today {
+                return Err(ActionError::NotYet(date));
+            }
+            if let Some(period) = data.current_plan_mut().pop_front() {
+                let defaults = data.default_work_sleep_goals.clone();
+                let ws = data.day_mut(date);
+                let before = ws.weighted_work_count();
+                ws.actual_work_count += 1;
+                ws.extra_work_weight += period.weight - 1.0;
+                let target = ws.effective_goals(&defaults).target_work_count as f64;
+                if before < target && ws.weighted_work_count() >= target {
+                    ws.target_celebrated = true;
+                }
+                ws.completed.push(CompletedPeriod {
+                    id: period.id,
+                    name: period.name.clone(),
+                    completed_at: now,
+                    duration_minutes: period.elapsed_minutes(now),
+                    category: period.category,
+                    note: period.note,
+                });
+                data.record_task_use(&period.name, today);
+            }
+        }
+        Action::AddRecurringTask(task) => {
+            if task.name.trim().is_empty() {
+                return Err(ActionError::EmptyName);
+            }
+            data.recurring_tasks.push(task);
+        }
+        Action::DeleteRecurringTask(id) => {
+            data.recurring_tasks.retain(|task| task.id != id);
+        }
+        Action::SetBedtime {
+            date,
+            bedtime,
+            today,
+        } => {
+            check_has_come(date, today)?;
+            match bedtime {
+                Some(bedtime) => data.day_mut(date).actual_bedtime = Some(bedtime),
+                None => {
+                    if let Some(ws) = data.work_sleep_data.get_mut(&date) {
+                        ws.actual_bedtime = None;
+                    }
+                }
+            }
+        }
+        Action::SetWakeTime {
+            night,
+            wake_time,
+            today,
+        } => {
+            // The night only ends on the day after.
+            if night >= today {
+                return Err(ActionError::NotYet(night));
+            }
+            let bedtime = data
+                .work_sleep_data
+                .get(&night)
+                .and_then(|ws| ws.actual_bedtime.as_ref());
+            if bedtime.is_some_and(|bedtime| bedtime.sleep_minutes_until(&wake_time).is_none()) {
+                return Err(ActionError::SleepTooLong);
+            }
+            data.day_mut(night).wake_time = Some(wake_time);
+        }
+        Action::CreateDayEntry { date, today } => {
+            check_has_come(date, today)?;
+            data.day_mut(date);
+        }
+        Action::AdjustWorkCount { date, delta, today } => {
+            check_has_come(date, today)?;
+            let ws = data.day_mut(date);
+            ws.actual_work_count = (ws.actual_work_count + delta).max(0);
+            if ws.actual_work_count == 0 {
+                ws.extra_work_weight = 0.0;
+            }
+            if date < today {
+                ws.work_count_edited = true;
+            }
+        }
+        Action::SetMood { date, mood, today } => {
+            check_has_come(date, today)?;
+            if !(1..=MOOD_EMOJIS.len() as u8).contains(&mood) {
+                return Err(ActionError::OutOfRange);
+            }
+            let ws = data.day_mut(date);
+            ws.mood = if ws.mood == Some(mood) {
+                None
+            } else {
+                Some(mood)
+            };
+        }
+        Action::SetDayGoals(date, goals) => {
+            let ws = data.work_sleep_data.get_mut_or_create(&date);
+            ws.goals = Some(goals.clamped());
+            ws.weekend_preset = false;
+        }
+        Action::ResetDayGoals(date) => {
+            if let Some(ws) = data.work_sleep_data.get_mut(&date) {
+                ws.goals = None;
+                ws.weekend_preset = false;
+            }
+        }
+        Action::SetWeeklyNote(week_start, note) => {
+            data.set_weekly_note(week_start, note);
+        }
+        Action::SetDefaultGoals(goals) => {
+            data.default_work_sleep_goals = goals.clamped();
+        }
+        Action::CompleteOnboarding(goals) => {
+            data.default_work_sleep_goals = goals.clamped();
+            data.onboarded = true;
+        }
+        Action::ToggleWeekendGoals => {
+            data.weekend_goals = match data.weekend_goals {
+                Some(_) => None,
+                None => Some(data.default_work_sleep_goals.clone()),
+            };
+        }
+        Action::SetWeekendGoals(goals) => {
+            if data.weekend_goals.is_some() {
+                data.weekend_goals = Some(goals.clamped());
+            }
+        }
+        Action::ArchiveOldWeeks { today, after_days } => {
+            data.archive_weeks(today, after_days);
+        }
+        Action::ToggleOneClickDelete => {
+            data.one_click_delete ^= true;
+        }
+        Action::ToggleTargetReachedSound => {
+            data.target_reached_sound ^= true;
+        }
+        Action::ToggleGroupRepeatedTasks => {
+            data.group_repeated_tasks ^= true;
+        }
+        Action::ToggleBreaks => {
+            data.breaks_enabled ^= true;
+        }
+        Action::SetTheme(theme) => {
+            data.theme = theme;
+        }
+        Action::SetDateFormat(format) => {
+            data.date_format = format;
+        }
+        Action::SetClockFormat(format) => {
+            data.clock_format = format;
+        }
+        Action::SetLang(lang) => {
+            data.lang = lang;
+        }
+        Action::SetWeekMode(mode) => {
+            data.week_mode = mode;
+            // Keep showing about the same days, aligned to the new mode.
+            let week_start = &mut data.work_sleep_data.week_start;
+            *week_start = mode.align(*week_start);
+        }
+        Action::SetPomodoroMinutes(minutes) => {
+            data.pomodoro_minutes = in_range(minutes, 1, MAX_POMODORO_MINUTES)?;
+        }
+        Action::SetBreakMinutes(minutes) => {
+            data.break_minutes = in_range(minutes, 1, MAX_BREAK_MINUTES)?;
+        }
+        Action::SetBedtimeReminderLead(minutes) => {
+            let max = MAX_BEDTIME_REMINDER_LEAD_MINUTES;
+            data.bedtime_reminder_lead_minutes = in_range(minutes, 1, max)?;
+        }
+        Action::SetDayCutoffHour(hour) => {
+            data.day_cutoff_hour = in_range(hour.into(), 0, MAX_DAY_CUTOFF_HOUR)? as u32;
+        }
+        Action::SetConsistencyDays(days) => {
+            let min = MIN_CONSISTENCY_NIGHTS as i64;
+            data.consistency_days = in_range(days.into(), min, MAX_CONSISTENCY_DAYS)? as u32;
+        }
+    }
+    Ok(())
+}
+
+// The plan of `date`, without making an empty one for a date that has none.
+fn existing_plan(data: &mut Data, date: NaiveDate) -> Result<&mut VecDeque<Period>, ActionError> {
+    data.planned_work_periods
+        .get_mut(&date)
+        .ok_or(ActionError::MissingTask)
+}
+
+fn check_has_come(date: NaiveDate, today: NaiveDate) -> Result<(), ActionError> {
+    if date > today {
+        Err(ActionError::NotYet(date))
+    } else {
+        Ok(())
+    }
+}
+
+fn in_range(value: i64, min: i64, max: i64) -> Result<i64, ActionError> {
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(ActionError::OutOfRange)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        ymd(2024, 3, 6)
+    }
+
+    fn data_with(names: &[&str]) -> (Data, Vec<Uuid>) {
+        let mut data = Data::new(today());
+        let mut ids = Vec::new();
+        for name in names {
+            let period = Period::new((*name).to_owned());
+            ids.push(period.id);
+            data.current_plan_mut().push_back(period);
+        }
+        (data, ids)
+    }
+
+    fn names(data: &Data) -> Vec<&str> {
+        data.current_plan()
+            .iter()
+            .map(|wp| wp.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn missing_tasks_leave_the_data_as_it_was() {
+        let (mut data, _) = data_with(&[]);
+        let before = serde_json::to_string(&data).unwrap();
+        let id = Uuid::new_v4();
+        let tomorrow = today().succ_opt().unwrap();
+        let actions = vec![
+            Action::DeleteTasks(tomorrow, vec![id]),
+            Action::PostponeTask { id, now: hm(10, 0) },
+            Action::RenameTask(id, "read".to_owned()),
+            Action::MoveTaskToTop(id),
+            Action::MoveTasksDown(vec![id]),
+            Action::SetTaskPriority(id, Priority::High),
+            Action::SetTasksPriority(vec![id], Priority::Low),
+            Action::StartTask { id, now: hm(10, 0) },
+        ];
+        for action in actions {
+            assert_eq!(apply(&mut data, action), Err(ActionError::MissingTask));
+            assert_eq!(serde_json::to_string(&data).unwrap(), before);
+        }
+    }
+
+    #[test]
+    fn added_copies_get_their_own_ids() {
+        let (mut data, _) = data_with(&[]);
+        let action = Action::AddTasks {
+            date: today(),
+            task: Period::new("write".to_owned()),
+            quantity: 3,
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        let plan = data.current_plan();
+        assert_eq!(names(&data), ["write", "write", "write"]);
+        assert!(plan[0].id != plan[1].id && plan[1].id != plan[2].id);
+
+        let action = Action::AddTasks {
+            date: today(),
+            task: Period::new("  ".to_owned()),
+            quantity: 1,
+            today: today(),
+        };
+        assert_eq!(apply(&mut data, action), Err(ActionError::EmptyName));
+    }
+
+    #[test]
+    fn tasks_are_moved_and_deleted_by_id() {
+        let (mut data, ids) = data_with(&["a", "b", "c", "d"]);
+        apply(&mut data, Action::MoveTaskToTop(ids[2])).unwrap();
+        assert_eq!(names(&data), ["c", "a", "b", "d"]);
+        apply(&mut data, Action::MoveTasksToBottom(vec![ids[0], ids[2]])).unwrap();
+        assert_eq!(names(&data), ["b", "d", "c", "a"]);
+        apply(&mut data, Action::DeleteTasks(today(), vec![ids[3]])).unwrap();
+        assert_eq!(names(&data), ["b", "c", "a"]);
+
+        assert_eq!(
+            apply(&mut data, Action::MoveTaskUp(ids[3])),
+            Err(ActionError::MissingTask)
+        );
+        assert_eq!(
+            apply(&mut data, Action::DeleteTasks(today(), vec![ids[3]])),
+            Err(ActionError::MissingTask)
+        );
+    }
+
+    #[test]
+    fn finishing_the_top_task_records_it() {
+        let (mut data, ids) = data_with(&["a", "b"]);
+        data.default_work_sleep_goals.target_work_count = 1;
+        let finish = Action::FinishTopTask {
+            now: hm(10, 0),
+            today: today(),
+        };
+        apply(&mut data, finish.clone()).unwrap();
+        assert_eq!(names(&data), ["b"]);
+        let ws = &data.work_sleep_data.days()[&today()];
+        assert_eq!(ws.actual_work_count, 1);
+        assert!(ws.target_celebrated);
+        assert_eq!(ws.completed[0].id, ids[0]);
+        assert_eq!(ws.completed[0].completed_at, hm(10, 0));
+
+        let tomorrow = today().succ_opt().unwrap();
+        let action = Action::SetCurrentDate {
+            date: tomorrow,
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        assert_eq!(apply(&mut data, finish), Err(ActionError::NotYet(tomorrow)));
+    }
+
+    #[test]
+    fn days_that_havent_come_are_left_alone() {
+        let (mut data, _) = data_with(&[]);
+        let tomorrow = today().succ_opt().unwrap();
+        let action = Action::AdjustWorkCount {
+            date: tomorrow,
+            delta: 1,
+            today: today(),
+        };
+        assert_eq!(apply(&mut data, action), Err(ActionError::NotYet(tomorrow)));
+        assert!(data.work_sleep_data.days().is_empty());
+
+        let set_mood = |mood| Action::SetMood {
+            date: today(),
+            mood,
+            today: today(),
+        };
+        assert_eq!(apply(&mut data, set_mood(0)), Err(ActionError::OutOfRange));
+        apply(&mut data, set_mood(4)).unwrap();
+        assert_eq!(data.work_sleep_data.days()[&today()].mood, Some(4));
+        apply(&mut data, set_mood(4)).unwrap();
+        assert_eq!(data.work_sleep_data.days()[&today()].mood, None);
+    }
+
+    #[test]
+    fn wake_times_must_follow_the_bedtime() {
+        let (mut data, _) = data_with(&[]);
+        let night = today().pred_opt().unwrap();
+        let action = Action::SetBedtime {
+            date: night,
+            bedtime: Some(Bedtime::from_time_of_day(hm(23, 0))),
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        let wake_up = |time| Action::SetWakeTime {
+            night,
+            wake_time: Bedtime {
+                time,
+                next_day: true,
+            },
+            today: today(),
+        };
+        assert_eq!(
+            apply(&mut data, wake_up(hm(22, 0))),
+            Err(ActionError::SleepTooLong)
+        );
+        apply(&mut data, wake_up(hm(7, 0))).unwrap();
+        let ws = &data.work_sleep_data.days()[&night];
+        assert_eq!(ws.wake_time.as_ref().map(|wake| wake.time), Some(hm(7, 0)));
+        assert_eq!(ws.sleep_minutes(), Some(480));
+
+        let action = Action::SetWakeTime {
+            night: today(),
+            wake_time: Bedtime::from_time_of_day(hm(7, 0)),
+            today: today(),
+        };
+        assert_eq!(apply(&mut data, action), Err(ActionError::NotYet(today())));
+    }
+
+    // The cached score of the day, which must be what scoring it afresh gives.
+    fn score(data: &Data, date: NaiveDate) -> i64 {
+        let ws = &data.work_sleep_data.days()[&date];
+        let defaults = &data.default_work_sleep_goals;
+        let score = data.work_sleep_data.score(date, ws, defaults);
+        assert_eq!(score, ws.calc_score(defaults));
+        score
+    }
+
+    #[test]
+    fn clearing_a_bedtime_rescores_the_day() {
+        let (mut data, _) = data_with(&[]);
+        let bedtime = |bedtime| Action::SetBedtime {
+            date: today(),
+            bedtime,
+            today: today(),
+        };
+        let target = data.default_work_sleep_goals.target_bedtime.clone();
+        apply(&mut data, bedtime(Some(target))).unwrap();
+        let with_bedtime = score(&data, today());
+        apply(&mut data, bedtime(None)).unwrap();
+        assert!(score(&data, today()) < with_bedtime);
+    }
+
+    #[test]
+    fn resetting_a_days_goals_rescores_the_day() {
+        let (mut data, _) = data_with(&[]);
+        let goals = WorkSleepGoals {
+            target_work_count: 1,
+            ..data.default_work_sleep_goals.clone()
+        };
+        apply(&mut data, Action::SetDayGoals(today(), goals)).unwrap();
+        let adjust = Action::AdjustWorkCount {
+            date: today(),
+            delta: 1,
+            today: today(),
+        };
+        apply(&mut data, adjust).unwrap();
+        let with_goals = score(&data, today());
+        apply(&mut data, Action::ResetDayGoals(today())).unwrap();
+        assert!(score(&data, today()) < with_goals);
+    }
+
+    #[test]
+    fn settings_outside_their_range_are_rejected() {
+        let (mut data, _) = data_with(&[]);
+        let minutes = data.pomodoro_minutes;
+        assert_eq!(
+            apply(&mut data, Action::SetPomodoroMinutes(0)),
+            Err(ActionError::OutOfRange)
+        );
+        assert_eq!(data.pomodoro_minutes, minutes);
+        apply(&mut data, Action::SetDayCutoffHour(4)).unwrap();
+        assert_eq!(data.day_cutoff_hour, 4);
+        assert_eq!(
+            apply(&mut data, Action::SetDayCutoffHour(7)),
+            Err(ActionError::OutOfRange)
+        );
+    }
+
+    fn add(data: &mut Data, name: &str, quantity: i64) {
+        let action = Action::AddTasks {
+            date: data.current_date,
+            task: Period::new(name.to_owned()),
+            quantity,
+            today: today(),
+        };
+        apply(data, action).unwrap();
+    }
+
+    fn finish(data: &mut Data) {
+        let action = Action::FinishTopTask {
+            now: hm(10, 0),
+            today: today(),
+        };
+        apply(data, action).unwrap();
+    }
+
+    #[test]
+    fn added_and_finished_names_rank_the_quick_add_chips() {
+        let (mut data, _) = data_with(&[]);
+        add(&mut data, "read", 2);
+        add(&mut data, "write", 1);
+        finish(&mut data);
+        assert_eq!(names(&data), ["read", "write"]);
+        assert_eq!(data.task_frequency["read"], 2);
+        assert_eq!(data.task_frequency["write"], 1);
+        assert_eq!(data.quick_add_tasks(today()), ["read", "write"]);
+    }
+
+    #[test]
+    fn quick_add_chips_can_be_pinned_and_removed() {
+        let (mut data, _) = data_with(&[]);
+        add(&mut data, "read", 1);
+        add(&mut data, "read", 1);
+        apply(&mut data, Action::TogglePinQuickTask("write".to_owned())).unwrap();
+        assert_eq!(data.quick_add_tasks(today()), ["write", "read"]);
+        apply(&mut data, Action::TogglePinQuickTask("write".to_owned())).unwrap();
+        apply(&mut data, Action::RemoveQuickTask("read".to_owned())).unwrap();
+        assert!(data.quick_add_tasks(today()).is_empty());
+        assert_eq!(names(&data), ["read", "read"]);
+    }
+
+    #[test]
+    fn postponed_tasks_show_up_the_next_day() {
+        let (mut data, ids) = data_with(&["write", "read"]);
+        let postpone = Action::PostponeTask {
+            id: ids[0],
+            now: hm(10, 0),
+        };
+        apply(&mut data, postpone).unwrap();
+        assert_eq!(names(&data), ["read"]);
+        assert_eq!(data.postponed_count(), 1);
+
+        let tomorrow = today().succ_opt().unwrap();
+        let action = Action::SetCurrentDate {
+            date: tomorrow,
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        assert_eq!(names(&data), ["write"]);
+        assert_eq!(data.current_plan()[0].postponed_from, Some(today()));
+    }
+
+    #[test]
+    fn unfinished_tasks_carry_over_when_the_day_rolls_over() {
+        let (mut data, _) = data_with(&["write", "read"]);
+        finish(&mut data);
+        let tomorrow = today().succ_opt().unwrap();
+        let rollover = Action::SetCurrentDate {
+            date: tomorrow,
+            today: tomorrow,
+        };
+        apply(&mut data, rollover).unwrap();
+        assert_eq!(names(&data), ["read"]);
+        assert!(data.plan(today()).is_empty());
+
+        // Looking back at a past date leaves the plans where they are.
+        let back = Action::SetCurrentDate {
+            date: today(),
+            today: tomorrow,
+        };
+        apply(&mut data, back).unwrap();
+        assert!(names(&data).is_empty());
+        assert_eq!(data.plan(tomorrow).len(), 1);
+    }
+
+    #[test]
+    fn setting_the_current_date_moves_the_week() {
+        let (mut data, _) = data_with(&[]);
+        let action = Action::SetCurrentDate {
+            date: ymd(2024, 2, 20),
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        assert_eq!(data.current_date, ymd(2024, 2, 20));
+        // Rolling weeks end on the date.
+        assert_eq!(data.work_sleep_data.week_start, ymd(2024, 2, 14));
+    }
+
+    #[test]
+    fn switching_to_calendar_weeks_aligns_the_week_shown() {
+        let (mut data, _) = data_with(&[]);
+        data.work_sleep_data.week_start = ymd(2024, 2, 15);
+        let calendar = WeekMode::Calendar(Weekday::Mon);
+        apply(&mut data, Action::SetWeekMode(calendar)).unwrap();
+        assert_eq!(data.work_sleep_data.week_start, ymd(2024, 2, 12));
+        apply(&mut data, Action::SetWeekMode(WeekMode::Rolling)).unwrap();
+        assert_eq!(data.work_sleep_data.week_start, ymd(2024, 2, 12));
+    }
+
+    #[test]
+    fn old_weeks_are_archived_with_their_summaries() {
+        let mut data = Data::new(ymd(2021, 5, 5));
+        for date in [ymd(2021, 3, 1), ymd(2021, 4, 1), ymd(2021, 5, 1)] {
+            let action = Action::AdjustWorkCount {
+                date,
+                delta: 2,
+                today: ymd(2021, 5, 5),
+            };
+            apply(&mut data, action).unwrap();
+        }
+        let action = Action::ArchiveOldWeeks {
+            today: ymd(2021, 5, 5),
+            after_days: 20,
+        };
+        apply(&mut data, action).unwrap();
+        let starts: Vec<_> = data.week_archives.iter().map(|a| a.start).collect();
+        // Rolling weeks end on 2021-05-05, so they start on Thursdays.
+        assert_eq!(starts, [ymd(2021, 2, 25), ymd(2021, 4, 1)]);
+        assert!(data.week_archives.iter().all(|a| a.work_done == 2.0));
+        assert_eq!(data.work_sleep_data.days().len(), 1);
+    }
+
+    #[test]
+    fn past_days_can_be_created_and_their_bedtime_cleared() {
+        let (mut data, _) = data_with(&[]);
+        let yesterday = today().pred_opt().unwrap();
+        let tomorrow = today().succ_opt().unwrap();
+        let create = |date| Action::CreateDayEntry {
+            date,
+            today: today(),
+        };
+        apply(&mut data, create(yesterday)).unwrap();
+        assert_eq!(
+            apply(&mut data, create(tomorrow)),
+            Err(ActionError::NotYet(tomorrow))
+        );
+        assert_eq!(data.work_sleep_data.days().len(), 1);
+
+        let bedtime = |bedtime| Action::SetBedtime {
+            date: yesterday,
+            bedtime,
+            today: today(),
+        };
+        let half_past_midnight = Bedtime::from_time_of_day(hm(0, 30));
+        assert!(half_past_midnight.next_day);
+        apply(&mut data, bedtime(Some(half_past_midnight))).unwrap();
+        assert!(data.work_sleep_data.days()[&yesterday]
+            .actual_bedtime
+            .is_some());
+        apply(&mut data, bedtime(None)).unwrap();
+        assert_eq!(data.work_sleep_data.days()[&yesterday].actual_bedtime, None);
+    }
+
+    #[test]
+    fn moods_are_only_set_in_range_and_on_days_that_have_come() {
+        let (mut data, _) = data_with(&[]);
+        let set_mood = |date, mood| Action::SetMood {
+            date,
+            mood,
+            today: today(),
+        };
+        let tomorrow = today().succ_opt().unwrap();
+        assert_eq!(
+            apply(&mut data, set_mood(tomorrow, 3)),
+            Err(ActionError::NotYet(tomorrow))
+        );
+        let too_high = MOOD_EMOJIS.len() as u8 + 1;
+        assert_eq!(
+            apply(&mut data, set_mood(today(), too_high)),
+            Err(ActionError::OutOfRange)
+        );
+        assert!(data.work_sleep_data.days().is_empty());
+    }
+
+    #[test]
+    fn completing_onboarding_sets_the_default_goals() {
+        let (mut data, _) = data_with(&[]);
+        assert!(!data.onboarded);
+        let mut goals = data.default_work_sleep_goals.clone();
+        goals.target_work_count = 4;
+        goals.target_bedtime.next_day = true;
+        apply(&mut data, Action::CompleteOnboarding(goals)).unwrap();
+        assert!(data.onboarded);
+        assert_eq!(data.default_work_sleep_goals.target_work_count, 4);
+        assert!(data.default_work_sleep_goals.target_bedtime.next_day);
+    }
+
+    #[test]
+    fn task_notes_follow_the_task_into_the_completed_log() {
+        let (mut data, _) = data_with(&[]);
+        let task = Period {
+            note: "pages 1-20".to_owned(),
+            ..Period::new("read".to_owned())
+        };
+        let action = Action::AddTasks {
+            date: today(),
+            task,
+            quantity: 1,
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        add(&mut data, "write", 1);
+        let write = data.current_plan()[1].id;
+        apply(
+            &mut data,
+            Action::SetTaskNote(write, "ch. 7: the proof".to_owned()),
+        )
+        .unwrap();
+        apply(&mut data, Action::MoveTaskToTop(write)).unwrap();
+        finish(&mut data);
+        finish(&mut data);
+        let ws = &data.work_sleep_data.days()[&today()];
+        let notes: Vec<&str> = ws.completed.iter().map(|cp| cp.note.as_str()).collect();
+        assert_eq!(notes, ["ch. 7: the proof", "pages 1-20"]);
+    }
+
+    #[test]
+    fn task_moves_on_an_empty_plan_change_nothing() {
+        let (mut data, _) = data_with(&[]);
+        let id = Uuid::new_v4();
+        for action in [
+            Action::DeleteTasks(today(), vec![id]),
+            Action::MoveTaskToTop(id),
+            Action::MoveTaskUp(id),
+            Action::MoveTaskDown(id),
+            Action::MoveTaskToBottom(id),
+        ] {
+            assert_eq!(apply(&mut data, action), Err(ActionError::MissingTask));
+        }
+        finish(&mut data);
+        assert!(names(&data).is_empty());
+        assert!(data.work_sleep_data.days().is_empty());
+    }
+
+    #[test]
+    fn a_single_task_stays_where_it_is() {
+        let (mut data, ids) = data_with(&["write"]);
+        for action in [
+            Action::MoveTaskToTop(ids[0]),
+            Action::MoveTaskUp(ids[0]),
+            Action::MoveTaskDown(ids[0]),
+            Action::MoveTaskToBottom(ids[0]),
+        ] {
+            apply(&mut data, action).unwrap();
+        }
+        assert_eq!(data.current_plan()[0].id, ids[0]);
+        assert_eq!(data.current_plan().len(), 1);
+    }
+
+    #[test]
+    fn tasks_move_up_and_to_the_top() {
+        let (mut data, ids) = data_with(&["a", "b", "c"]);
+        apply(&mut data, Action::MoveTaskUp(ids[2])).unwrap();
+        assert_eq!(names(&data), ["a", "c", "b"]);
+        apply(&mut data, Action::MoveTaskUp(ids[0])).unwrap();
+        assert_eq!(names(&data), ["a", "c", "b"]);
+        apply(&mut data, Action::MoveTaskToTop(ids[1])).unwrap();
+        assert_eq!(names(&data), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn tasks_keep_working_after_sorting_by_priority() {
+        let (mut data, ids) = data_with(&["filler", "urgent", "reply"]);
+        let high = vec![ids[1], ids[2]];
+        apply(&mut data, Action::SetTasksPriority(high, Priority::High)).unwrap();
+        apply(&mut data, Action::SortTasksByPriority).unwrap();
+        assert_eq!(names(&data), ["urgent", "reply", "filler"]);
+        apply(&mut data, Action::MoveTaskToTop(ids[0])).unwrap();
+        assert_eq!(names(&data), ["filler", "urgent", "reply"]);
+        finish(&mut data);
+        assert_eq!(names(&data), ["urgent", "reply"]);
+    }
+
+    #[test]
+    fn finished_tasks_count_by_their_weight() {
+        let (mut data, _) = data_with(&[]);
+        let action = Action::AddTasks {
+            date: today(),
+            task: Period {
+                weight: 2.5,
+                ..Period::new("deep work".to_owned())
+            },
+            quantity: 2,
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        add(&mut data, "chore", 1);
+        finish(&mut data);
+        finish(&mut data);
+        let ws = &data.work_sleep_data.days()[&today()];
+        assert_eq!(ws.actual_work_count, 2);
+        assert_eq!(ws.weighted_work_count(), 5.0);
+        assert!(!ws.target_celebrated);
+        finish(&mut data);
+        assert!(data.work_sleep_data.days()[&today()].target_celebrated);
+    }
+
+    #[test]
+    fn tasks_can_be_planned_edited_and_deleted_before_their_date() {
+        let (mut data, _) = data_with(&[]);
+        let later = ymd(2024, 3, 13);
+        let action = Action::AddTasks {
+            date: later,
+            task: Period::new("renew passport".to_owned()),
+            quantity: 2,
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        assert!(names(&data).is_empty());
+        assert_eq!(data.plan(later).len(), 2);
+
+        let id = data.plan(later)[1].id;
+        apply(&mut data, Action::RenameTask(id, "renew visa".to_owned())).unwrap();
+        apply(&mut data, Action::SetTaskPriority(id, Priority::High)).unwrap();
+        assert_eq!(data.plan(later)[1].name, "renew visa");
+        assert_eq!(data.plan(later)[1].priority, Priority::High);
+
+        let id = data.plan(later)[0].id;
+        apply(&mut data, Action::DeleteTasks(later, vec![id])).unwrap();
+        assert_eq!(data.plan(later).len(), 1);
+        let next_day = later.succ_opt().unwrap();
+        assert_eq!(
+            apply(&mut data, Action::DeleteTasks(next_day, vec![id])),
+            Err(ActionError::MissingTask)
+        );
+
+        let action = Action::SetCurrentDate {
+            date: later,
+            today: today(),
+        };
+        apply(&mut data, action).unwrap();
+        assert_eq!(names(&data), ["renew visa"]);
+    }
+
+    #[test]
+    fn scores_follow_the_actions_that_change_the_day() {
+        let (mut data, _) = data_with(&["write"]);
+        finish(&mut data);
+        let after_task = score(&data, today());
+        let bedtime = Action::SetBedtime {
+            date: today(),
+            bedtime: Some(Bedtime::from_time_of_day(hm(23, 0))),
+            today: today(),
+        };
+        apply(&mut data, bedtime).unwrap();
+        let after_bedtime = score(&data, today());
+        assert!(after_bedtime > after_task);
+
+        let goals = WorkSleepGoals {
+            target_work_count: 1,
+            ..data.default_work_sleep_goals.clone()
+        };
+        apply(&mut data, Action::SetDayGoals(today(), goals)).unwrap();
+        assert!(score(&data, today()) > after_bedtime);
+    }
+}
@@ -0,0 +1,126 @@
+// Tests that mount the whole app in a browser and drive it through the page, run with
+// `cargo make test_h firefox` (or chrome). The app starts on fresh data open on a fixed
+// date, with none of the timers, storage or tab syncing that `init` sets up.
+
+use chrono::NaiveDate;
+use seed::{prelude::*, *};
+use wasm_bindgen_test::*;
+
+use crate::app::update::{update, Msg};
+use crate::app::view::view;
+use crate::app::Model;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct TestApp {
+    app: App<Msg, Model, Node<Msg>>,
+    root: web_sys::Element,
+}
+
+impl TestApp {
+    fn on(today: NaiveDate) -> Self {
+        let root = document().create_element("div").unwrap();
+        body().append_child(&root).unwrap();
+        let app = App::start(root.clone(), move |_, _| Model::on(today), update, view);
+        Self { app, root }
+    }
+
+    fn find(&self, selector: &str) -> web_sys::Element {
+        self.root
+            .query_selector(selector)
+            .unwrap()
+            .unwrap_or_else(|| panic!("nothing matches {}", selector))
+    }
+
+    fn text(&self, selector: &str) -> String {
+        self.find(selector).text_content().unwrap_or_default()
+    }
+
+    async fn click(&self, selector: &str) {
+        self.find(selector)
+            .unchecked_into::<web_sys::HtmlElement>()
+            .click();
+        next_frame().await;
+    }
+
+    // Types `value` into the input matching `selector`, as if all at once.
+    async fn type_into(&self, selector: &str, value: &str) {
+        let input = self.find(selector);
+        input
+            .clone()
+            .unchecked_into::<web_sys::HtmlInputElement>()
+            .set_value(value);
+        input
+            .dispatch_event(&web_sys::Event::new("input").unwrap())
+            .unwrap();
+        next_frame().await;
+    }
+
+    async fn visit(&self, path: &[&str]) {
+        let url = Url::new().set_path(path);
+        self.app.update(Msg::UrlChanged(subs::UrlChanged(url)));
+        next_frame().await;
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        self.root.remove();
+    }
+}
+
+// Waits for the app to render the changes made so far.
+async fn next_frame() {
+    let frame = js_sys::Promise::new(&mut |resolve, _| {
+        window().request_animation_frame(&resolve).unwrap();
+    });
+    JsFuture::from(frame).await.unwrap();
+}
+
+fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn added_tasks_count_once_finished() {
+    let app = TestApp::on(ymd(2021, 5, 5));
+    app.type_into("#new-task-name", "write").await;
+    app.click("#add-new-task").await;
+    assert!(app.text("li[aria-current]").contains("CURRENT TASK: write"));
+
+    app.click("#finish-task").await;
+    assert!(app
+        .root
+        .query_selector("li[aria-current]")
+        .unwrap()
+        .is_none());
+    app.visit(&["history"]).await;
+    assert!(app
+        .text("td[aria-current=date]")
+        .contains("Work Completed: 1"));
+}
+
+#[wasm_bindgen_test]
+async fn weeks_can_be_paged_through() {
+    let app = TestApp::on(ymd(2021, 5, 5));
+    app.visit(&["history"]).await;
+    assert_eq!(app.text("#week-range"), "2021-04-29 – 2021-05-05");
+    assert!(app.find("#next-week").has_attribute("disabled"));
+
+    app.click("#previous-week").await;
+    assert_eq!(app.text("#week-range"), "2021-04-22 – 2021-04-28");
+    assert!(!app.find("#next-week").has_attribute("disabled"));
+    app.click("#next-week").await;
+    assert_eq!(app.text("#week-range"), "2021-04-29 – 2021-05-05");
+}
+
+#[wasm_bindgen_test]
+async fn typed_bedtimes_are_recorded() {
+    let app = TestApp::on(ymd(2021, 5, 5));
+    app.type_into("#bedtime", "11pm").await;
+    app.click("#record-bedtime").await;
+    app.visit(&["history"]).await;
+    assert!(app
+        .text("td[aria-current=date]")
+        .contains("Bedtime: 11:00 PM"));
+}
@@ -0,0 +1,691 @@
+// The Seed app: `Model`, `init` and the start-up wiring, with `update` and `view` below.
+// Only built with the `web` feature.
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod browser_tests;
+mod update;
+mod view;
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::mem::Discriminant;
+use std::rc::Rc;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use seed::{prelude::*, *};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, NotificationPermission};
+
+use crate::merge::{GoalsSource, MergeSummary};
+use crate::model::{
+    bedtime_countdown, Bedtime, BedtimeCountdown, Clock, Data, DataSnapshot, Priority,
+    WorkSleepGoals,
+};
+use update::{notification_permission, on_window_key_down, update, viewport_width, Msg};
+use view::view;
+
+const STORAGE_KEY: &str = "slorz";
+const SYNC_CHANNEL_NAME: &str = "slorz-sync";
+const REMOTE_SYNC_KEY: &str = "slorz-remote-sync";
+const DATE_ROLLOVER_CHECK_MS: u32 = 60_000;
+const MAX_UNDO_STEPS: usize = 50;
+const BEDTIME_REMINDER_CHECK_MS: u32 = 60_000;
+const CLOCK_TICK_MS: u32 = 60_000;
+const DEFAULT_DEMO_SEED: u64 = 1;
+const DEMO_DAYS: usize = 28;
+// Windows narrower than this get the layout for phones.
+const NARROW_VIEWPORT_PX: u32 = 600;
+const DEFAULT_WEEK_ARCHIVE_DAYS: u32 = 28;
+
+// ------ ------
+//     Init
+// ------ ------
+
+// `init` describes what should happen when your app started.
+fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
+    // `?demo` or `?demo=<seed>` shows made-up data instead of the saved data.
+    let demo_seed = url.search().get("demo").map(|values| {
+        values
+            .first()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or(DEFAULT_DEMO_SEED)
+    });
+    orders
+        .subscribe(Msg::UrlChanged)
+        .notify(subs::UrlChanged(url));
+    orders.stream(streams::window_event(Ev::KeyDown, on_window_key_down));
+    orders.stream(streams::interval(DATE_ROLLOVER_CHECK_MS, || {
+        Msg::CheckDateRollover
+    }));
+    orders.stream(streams::document_event(Ev::VisibilityChange, |_| {
+        Msg::CheckDateRollover
+    }));
+    orders.stream(streams::interval(BEDTIME_REMINDER_CHECK_MS, || {
+        Msg::CheckBedtimeReminder
+    }));
+    orders.stream(streams::interval(CLOCK_TICK_MS, || Msg::ClockTick));
+    orders.stream(streams::window_event(Ev::Resize, |_| {
+        Msg::ViewportResized(viewport_width())
+    }));
+
+    let clock = Clock::Real;
+    let color_scheme = ColorScheme::watch(orders);
+    let prefers_dark = color_scheme.as_ref().is_some_and(ColorScheme::prefers_dark);
+
+    if let Some(seed) = demo_seed {
+        return Model {
+            demo: true,
+            notification_permission: notification_permission(),
+            viewport_width: viewport_width(),
+            _color_scheme: color_scheme,
+            prefers_dark,
+            ..Model::new(
+                Data::demo(seed, DEMO_DAYS, clock.today(0)),
+                Uuid::new_v4(),
+                None,
+                clock,
+            )
+        };
+    }
+
+    let data = match LocalStorage::get::<Data>(STORAGE_KEY) {
+        Ok(mut data) => {
+            data.open_on(clock.today(data.day_cutoff_hour));
+            data
+        }
+        Err(_) => Data::new(clock.today(0)),
+    };
+    let tab_id = Uuid::new_v4();
+    let sync = TabSync::connect(tab_id, orders);
+    Model {
+        notification_permission: notification_permission(),
+        viewport_width: viewport_width(),
+        _color_scheme: color_scheme,
+        prefers_dark,
+        remote_sync: LocalStorage::get(REMOTE_SYNC_KEY).unwrap_or_default(),
+        ..Model::new(data, tab_id, sync, clock)
+    }
+}
+
+// ------ ------
+//     Model
+// ------ ------
+
+// `Model` describes our app state.
+struct Model {
+    // Distinguishes this tab's sync messages from those sent by other tabs.
+    tab_id: Uuid,
+    // `None` if the browser has no BroadcastChannel API.
+    sync: Option<TabSync>,
+    // `data` as last sent to or received from other tabs, to only send real changes.
+    last_synced: String,
+    remote_sync: RemoteSyncSettings,
+    // Changes made since the data was last synced with the server.
+    changes_since_remote_sync: u32,
+    remote_sync_in_flight: bool,
+    last_remote_sync: Option<NaiveDateTime>,
+    data: Data,
+    // Whether `data` changed since it was last saved to LocalStorage.
+    dirty: bool,
+    unload_warning: UnloadWarning,
+    // Showing made-up data, which is neither saved nor shared with other tabs so that
+    // it can't overwrite the real data.
+    demo: bool,
+    clock: Clock,
+    // The real local date as of the last rollover check.
+    today: NaiveDate,
+    // The local time as of the last clock tick, for previews that follow the clock.
+    now: NaiveDateTime,
+    page: Page,
+    refs: Refs,
+    import_error: Option<String>,
+    // Whether importing a data file merges it into the data here rather than replacing it.
+    import_merge: bool,
+    pending_import: Option<PendingImport>,
+    // Whether importing a sleep tracker's CSV replaces bedtimes that were already recorded.
+    sleep_csv_overwrite: bool,
+    // The date picked in settings for archiving the history before it.
+    archive_cutoff: Option<NaiveDate>,
+    // Weeks that ended more than this many days ago are compacted by "Archive old weeks".
+    week_archive_days: u32,
+    // Where the exported calendar starts laying out the plan, and how long each block is.
+    calendar_start: NaiveTime,
+    calendar_block_minutes: i64,
+    // Oldest first. Cleared when the data is replaced as a whole.
+    undo_stack: VecDeque<HistoryStep>,
+    // What undoing took away, most recently undone last. Cleared by any new change.
+    redo_stack: Vec<DataSnapshot>,
+    day_goals_editor_open: bool,
+    // The day of the week shown whose details are open under it.
+    expanded_day: Option<NaiveDate>,
+    show_shortcut_help: bool,
+    // Shows only the current task, as big as it gets, until left.
+    focus_mode: bool,
+    history_view: HistoryView,
+    // `None` if the browser has no Notification API.
+    notification_permission: Option<NotificationPermission>,
+    // Kept outside `Data` so that changing dates or weeks leaves it running.
+    pomodoro: Option<Pomodoro>,
+    pomodoro_finished: bool,
+    work_break: Option<WorkBreak>,
+    target_reached: bool,
+    // The browser tab title as last set, to only touch the document when it changes.
+    tab_title: String,
+    // Only kept to keep receiving changes. `None` if the browser has no `matchMedia`.
+    _color_scheme: Option<ColorScheme>,
+    prefers_dark: bool,
+    // The window's width in CSS pixels, or `None` if it couldn't be read.
+    viewport_width: Option<u32>,
+    // Whether the page was last switched to the dark theme.
+    dark_mode: bool,
+    week_report: Option<WeekReportStatus>,
+    // Whether the review of the week shown in the history is open.
+    week_review_open: bool,
+    // A task whose Delete button was clicked once and awaits confirmation.
+    pending_delete: Option<Uuid>,
+    // Whether the review shown on finishing the last planned task is open.
+    day_review_open: bool,
+    // The date and work target the review was last shown for, so that emptying the plan
+    // again that day only brings it back once the target has changed.
+    day_review_shown: Option<(NaiveDate, i64)>,
+    // A bedtime captured for the current date that would replace the one recorded,
+    // until replacing it is confirmed or cancelled.
+    bedtime_to_confirm: Option<Bedtime>,
+    // The task whose name is being edited, with the name typed so far.
+    editing_task: Option<(Uuid, String)>,
+    // The forms as filled in so far. They belong to this tab, so they're neither saved nor
+    // replaced by data from elsewhere.
+    new_task: NewTask,
+    new_recurring_task: NewRecurringTask,
+    current_date_bedtime: CurrentDateBedtime,
+    wake_time_input: WakeTimeInput,
+    // The lines typed or pasted to add several tasks at once, while that box is open.
+    bulk_add: Option<String>,
+    // What has been typed to confirm deleting all data, while the confirmation is shown.
+    reset_confirmation: Option<String>,
+    // The data from before deleting it all, and the fresh data as JSON to tell when it
+    // was first changed. Undoing is only offered until then.
+    reset_backup: Option<(Data, String)>,
+    // Messages about what just happened, oldest first.
+    notices: VecDeque<Notice>,
+    // What screen readers were last told about finishing a task.
+    announcement: String,
+    next_notice_id: usize,
+    // The first-run setup, shown instead of the app until it's completed or skipped.
+    onboarding: Option<Onboarding>,
+}
+
+#[derive(Clone, Debug)]
+struct NewRecurringTask {
+    name: String,
+    quantity: String,
+    quantity_parsed: Result<i64, String>,
+    weekdays: [bool; 7],
+}
+
+impl Default for NewRecurringTask {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            quantity: "1".to_owned(),
+            quantity_parsed: Ok(1),
+            weekdays: [false; 7],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct NewTask {
+    name: String,
+    category: String,
+    note: String,
+    priority: Priority,
+    quantity: String,
+    quantity_parsed: Result<i64, String>,
+    weight: String,
+    weight_parsed: Result<f64, String>,
+    // The date to plan the task for, if not the current date.
+    date: Option<NaiveDate>,
+}
+
+impl NewTask {
+    fn category(&self) -> Option<String> {
+        let category = self.category.trim();
+        if category.is_empty() {
+            None
+        } else {
+            Some(category.to_owned())
+        }
+    }
+}
+
+impl Default for NewTask {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            category: String::new(),
+            note: String::new(),
+            priority: Priority::Normal,
+            quantity: "1".to_owned(),
+            quantity_parsed: Ok(1),
+            weight: "1".to_owned(),
+            weight_parsed: Ok(1.0),
+            date: None,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+struct CurrentDateBedtime {
+    time: String,
+    is_next_day: bool,
+    error: Option<String>,
+}
+
+// The time of waking up this morning, which ends the night before the current date.
+#[derive(Clone, Default, Debug)]
+struct WakeTimeInput {
+    time: String,
+    error: Option<String>,
+}
+
+struct Onboarding {
+    step: OnboardingStep,
+    // The goals picked so far, starting from the defaults.
+    goals: WorkSleepGoals,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OnboardingStep {
+    TargetWorkCount,
+    TargetBedtime,
+    WorkSleepBalance,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+struct Notice {
+    id: usize,
+    severity: Severity,
+    message: String,
+    // A button label and what pressing it sends, such as undoing what the notice is about.
+    action: Option<(String, Msg)>,
+    // When the notice goes away by itself if it wasn't dismissed.
+    expires_at: NaiveDateTime,
+}
+
+enum WeekReportStatus {
+    Copied,
+    // The clipboard was unavailable, so the report is shown for copying by hand.
+    CopyFailed(String),
+}
+
+// A data file read for merging, waiting for the merge to be confirmed.
+struct PendingImport {
+    data: Data,
+    goals: GoalsSource,
+    // What merging would do, worked out on a copy of the data.
+    summary: MergeSummary,
+}
+
+// Keeps the data of every open tab in step through a `BroadcastChannel`.
+struct TabSync {
+    channel: web_sys::BroadcastChannel,
+    // Dropping the closure would detach the message handler.
+    _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SyncMessage {
+    tab_id: Uuid,
+    data: Data,
+}
+
+impl TabSync {
+    fn connect(tab_id: Uuid, orders: &mut impl Orders<Msg>) -> Option<Self> {
+        let channel = web_sys::BroadcastChannel::new(SYNC_CHANNEL_NAME).ok()?;
+        let msg_sender = orders.msg_sender();
+        let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+            let message = event
+                .data()
+                .as_string()
+                .and_then(|json| serde_json::from_str::<SyncMessage>(&json).ok());
+            match message {
+                Some(message) if message.tab_id != tab_id => {
+                    msg_sender(Some(Msg::RemoteStateReceived(message.data)));
+                }
+                _ => {}
+            }
+        }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+        channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        Some(Self {
+            channel,
+            _on_message: on_message,
+        })
+    }
+
+    fn publish(&self, tab_id: Uuid, data: &Data) {
+        let message = SyncMessage {
+            tab_id,
+            data: data.clone(),
+        };
+        match serde_json::to_string(&message) {
+            Ok(json) => {
+                if let Err(err) = self.channel.post_message(&JsValue::from_str(&json)) {
+                    log!("failed to sync data to other tabs", err);
+                }
+            }
+            Err(err) => log!("failed to serialize data for other tabs", err.to_string()),
+        }
+    }
+}
+
+// Where a copy of the data is kept over HTTP, read with GET and replaced with PUT. Saved
+// apart from `Data` so that the token never ends up in exports or in the copy itself.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct RemoteSyncSettings {
+    url: String,
+    token: String,
+    // Syncs by itself after this many changes, or only on demand if 0.
+    #[serde(default)]
+    sync_every: u32,
+}
+
+// Tells whether the browser prefers a dark color scheme, for the `System` theme.
+struct ColorScheme {
+    query: web_sys::MediaQueryList,
+    // Dropping the closure would detach the change handler.
+    _on_change: Closure<dyn FnMut(web_sys::MediaQueryListEvent)>,
+}
+
+impl ColorScheme {
+    fn watch(orders: &mut impl Orders<Msg>) -> Option<Self> {
+        let query = window()
+            .match_media("(prefers-color-scheme: dark)")
+            .ok()
+            .flatten()?;
+        let msg_sender = orders.msg_sender();
+        let on_change = Closure::wrap(Box::new(move |event: web_sys::MediaQueryListEvent| {
+            msg_sender(Some(Msg::PrefersDarkChanged(event.matches())));
+        }) as Box<dyn FnMut(web_sys::MediaQueryListEvent)>);
+        query.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+        Some(Self {
+            query,
+            _on_change: on_change,
+        })
+    }
+
+    fn prefers_dark(&self) -> bool {
+        self.query.matches()
+    }
+}
+
+// Asks before the tab is closed while there are unsaved changes.
+#[derive(Default)]
+struct UnloadWarning {
+    // The `beforeunload` handler, only attached while there is something to lose.
+    on_before_unload: Option<Closure<dyn FnMut(web_sys::BeforeUnloadEvent)>>,
+    // What older browsers show in the prompt, kept in the language last chosen.
+    message: Rc<Cell<&'static str>>,
+}
+
+impl UnloadWarning {
+    fn set(&mut self, dirty: bool, message: &'static str) {
+        self.message.set(message);
+        match (&self.on_before_unload, dirty) {
+            (None, true) => {
+                let message = Rc::clone(&self.message);
+                let on_before_unload =
+                    Closure::wrap(Box::new(move |event: web_sys::BeforeUnloadEvent| {
+                        event.prevent_default();
+                        // Older browsers only prompt when a return value is set.
+                        event.set_return_value(message.get());
+                    })
+                        as Box<dyn FnMut(web_sys::BeforeUnloadEvent)>);
+                if let Err(err) = window().add_event_listener_with_callback(
+                    "beforeunload",
+                    on_before_unload.as_ref().unchecked_ref(),
+                ) {
+                    log!("failed to watch for the tab closing", err);
+                }
+                self.on_before_unload = Some(on_before_unload);
+            }
+            (Some(on_before_unload), false) => {
+                if let Err(err) = window().remove_event_listener_with_callback(
+                    "beforeunload",
+                    on_before_unload.as_ref().unchecked_ref(),
+                ) {
+                    log!("failed to stop watching for the tab closing", err);
+                }
+                self.on_before_unload = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Page {
+    // `/`: the current date's tasks and bedtime.
+    Tracking,
+    // `/history`, optionally with the date of a week to show, as in `/history#2024-03-04`.
+    History,
+    // `/settings`: goals, recurring tasks and importing or exporting data.
+    Settings,
+    NotFound,
+}
+
+impl Page {
+    fn from_url(url: &Url) -> Self {
+        match url.path() {
+            [] => Page::Tracking,
+            [page] if page == "history" => Page::History,
+            [page] if page == "settings" => Page::Settings,
+            _ => Page::NotFound,
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            Page::Tracking | Page::NotFound => "/",
+            Page::History => "/history",
+            Page::Settings => "/settings",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum HistoryView {
+    Week,
+    // The month containing `WorkSleepData::week_start`.
+    Month,
+    // The 365 days ending at the current date.
+    Year,
+    // Statistics over every day on record.
+    AllTime,
+}
+
+// A countdown on the current task.
+struct Pomodoro {
+    task_id: Uuid,
+    remaining_secs: i64,
+    // Dropping the handle stops the ticks, so this is `None` while paused.
+    ticker: Option<StreamHandle>,
+}
+
+// A break after a finished task, during which the next one can't be finished.
+struct WorkBreak {
+    remaining_secs: i64,
+    // Dropping the handle stops the ticks.
+    _ticker: Option<StreamHandle>,
+}
+
+impl Model {
+    // A fresh app state around `data`, which should already be open on today.
+    fn new(data: Data, tab_id: Uuid, sync: Option<TabSync>, clock: Clock) -> Self {
+        let last_synced = serde_json::to_string(&data).unwrap_or_default();
+        let onboarding = (!data.onboarded).then(|| Onboarding {
+            step: OnboardingStep::TargetWorkCount,
+            goals: data.default_work_sleep_goals.clone(),
+        });
+        Self {
+            tab_id,
+            sync,
+            last_synced,
+            dirty: false,
+            unload_warning: UnloadWarning::default(),
+            remote_sync: RemoteSyncSettings::default(),
+            changes_since_remote_sync: 0,
+            remote_sync_in_flight: false,
+            last_remote_sync: None,
+            today: data.current_date,
+            calendar_block_minutes: data.pomodoro_minutes,
+            now: clock.now(),
+            clock,
+            data,
+            demo: false,
+            page: Page::Tracking,
+            refs: Refs::default(),
+            import_error: None,
+            import_merge: false,
+            pending_import: None,
+            sleep_csv_overwrite: false,
+            archive_cutoff: None,
+            week_archive_days: DEFAULT_WEEK_ARCHIVE_DAYS,
+            calendar_start: NaiveTime::from_hms_opt(9, 0, 0).expect("09:00 is a valid time"),
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            day_goals_editor_open: false,
+            expanded_day: None,
+            show_shortcut_help: false,
+            focus_mode: false,
+            history_view: HistoryView::Week,
+            notification_permission: None,
+            pomodoro: None,
+            pomodoro_finished: false,
+            work_break: None,
+            target_reached: false,
+            tab_title: String::new(),
+            _color_scheme: None,
+            prefers_dark: false,
+            viewport_width: None,
+            dark_mode: false,
+            week_report: None,
+            week_review_open: false,
+            pending_delete: None,
+            day_review_open: false,
+            day_review_shown: None,
+            bedtime_to_confirm: None,
+            editing_task: None,
+            new_task: NewTask::default(),
+            new_recurring_task: NewRecurringTask::default(),
+            current_date_bedtime: CurrentDateBedtime::default(),
+            wake_time_input: WakeTimeInput::default(),
+            bulk_add: None,
+            reset_confirmation: None,
+            reset_backup: None,
+            notices: VecDeque::new(),
+            announcement: String::new(),
+            next_notice_id: 0,
+            onboarding,
+        }
+    }
+
+    // How long until today's target bedtime, until a bedtime is recorded for today.
+    fn bedtime_countdown(&self) -> Option<BedtimeCountdown> {
+        let ws = self.data.work_sleep_data.get(&self.today);
+        if ws.is_some_and(|ws| ws.actual_bedtime.is_some()) {
+            return None;
+        }
+        let target = &self.data.goals_on(self.today).target_bedtime;
+        bedtime_countdown(self.now, target, self.today)
+    }
+
+    // Made-up data is never synced, and nothing is without a URL.
+    fn remote_sync_enabled(&self) -> bool {
+        !self.demo && !self.remote_sync.url.trim().is_empty()
+    }
+
+    fn remote_sync_due(&self) -> bool {
+        let every = self.remote_sync.sync_every;
+        self.remote_sync_enabled()
+            && !self.remote_sync_in_flight
+            && every > 0
+            && self.changes_since_remote_sync >= every
+    }
+
+    // Phones get the layout for narrow screens; a width that can't be read counts as wide.
+    fn is_narrow(&self) -> bool {
+        self.viewport_width
+            .is_some_and(|width| width < NARROW_VIEWPORT_PX)
+    }
+
+    // Future dates can be planned for, but nothing can be recorded on them yet.
+    fn is_planning(&self) -> bool {
+        self.data.current_date > self.today
+    }
+
+    fn push_history(&mut self, step: HistoryStep) {
+        self.undo_stack.push_back(step);
+        if self.undo_stack.len() > MAX_UNDO_STEPS {
+            self.undo_stack.pop_front();
+        }
+    }
+
+    // Records a change from `before`, unless it carries on the last change made by
+    // the same kind of message, such as a slider being dragged.
+    fn record_change(&mut self, before: DataSnapshot, merge_key: Option<Discriminant<Msg>>) {
+        let carries_on = merge_key.is_some()
+            && self.redo_stack.is_empty()
+            && self.undo_stack.back().is_some_and(|last| {
+                last.merge_key == merge_key && last.before.date() == before.date()
+            });
+        self.redo_stack.clear();
+        if !carries_on {
+            self.push_history(HistoryStep { before, merge_key });
+        }
+    }
+}
+
+#[cfg(test)]
+impl Model {
+    // Fresh data open on `today` past the first-run setup, with no other tabs to sync with
+    // and the clock stopped at noon.
+    fn on(today: NaiveDate) -> Self {
+        let mut data = Data::new(today);
+        data.onboarded = true;
+        Self::new(data, Uuid::new_v4(), None, Clock::at_noon(today))
+    }
+}
+
+// A change that can be undone, as the data it touched looked before it.
+struct HistoryStep {
+    before: DataSnapshot,
+    // The kind of message that made the change, if repeats of it are undone together.
+    merge_key: Option<Discriminant<Msg>>,
+}
+
+#[derive(Default)]
+struct Refs {
+    new_task_name: ElRef<HtmlInputElement>,
+    pomodoro_countdown: ElRef<web_sys::Element>,
+    break_countdown: ElRef<web_sys::Element>,
+    task_name_editor: ElRef<HtmlInputElement>,
+}
+
+// ------ ------
+//     Start
+// ------ ------
+
+// (This function is invoked by `init` function in `index.html`.)
+#[wasm_bindgen(start)]
+pub fn start() {
+    // Mount the `app` to the element with the `id` "app".
+    App::start("app", init, update, view);
+}
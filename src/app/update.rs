@@ -0,0 +1,3397 @@
+// `Msg` and `update`, plus the browser APIs that messages are handled with.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use seed::{prelude::*, *};
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, Notification, NotificationPermission};
+
+use crate::achievements::check_achievements;
+use crate::action::{self, Action, ActionError};
+use crate::app::{
+    HistoryStep, HistoryView, Model, NewRecurringTask, NewTask, Notice, OnboardingStep, Page,
+    PendingImport, Pomodoro, RemoteSyncSettings, Severity, WeekReportStatus, WorkBreak,
+    REMOTE_SYNC_KEY, STORAGE_KEY,
+};
+use crate::i18n::{Lang, TextKey};
+use crate::ics::plan_calendar;
+use crate::merge::{merge_data, GoalsSource};
+use crate::model::{
+    add_months, bedtime_now, find_task, format_bedtime, format_countdown, format_work_count,
+    normalize_task_name, parse_bulk_tasks, parse_in_range, parse_quantity, parse_time,
+    parse_weight, remove_tasks, tab_title, week_report, Bedtime, BedtimePenaltyMode, ClockFormat,
+    Data, DataSnapshot, DateFormat, Period, Priority, RecurringTask, Theme, WeekMode,
+    WorkSleepGoals, MAX_BEDTIME_PTS_HALFLIFE, MAX_CALENDAR_BLOCK_MINUTES, MAX_SLEEP_MINUTES,
+    MAX_TARGET_WORK_COUNT, MAX_TASK_QUANTITY, MIN_BEDTIME_PTS_HALFLIFE,
+};
+use crate::sleep_csv::parse_sleep_csv;
+
+const POMODORO_TICK_MS: u32 = 1_000;
+const BREAK_TICK_MS: u32 = 1_000;
+const POMODORO_FLASH_MS: u32 = 5_000;
+const TARGET_REACHED_FLASH_MS: u32 = 5_000;
+const COPIED_NOTICE_MS: u32 = 3_000;
+const PENDING_DELETE_TIMEOUT_MS: u32 = 5_000;
+const NOTICE_TIMEOUT_MS: u32 = 6_000;
+const MAX_NOTICES: usize = 5;
+const MAX_SYNC_EVERY: i64 = 1_000;
+const RESET_CONFIRMATION: &str = "DELETE";
+
+// Maps global keyboard shortcuts to messages. Shortcuts are ignored while typing
+// into a form field so that e.g. a task name containing "d" doesn't finish a task.
+pub fn on_window_key_down(event: web_sys::Event) -> Option<Msg> {
+    let event: web_sys::KeyboardEvent = event.unchecked_into();
+    let in_form_field = event.target().is_some_and(|target| {
+        target.has_type::<HtmlInputElement>()
+            || target.has_type::<web_sys::HtmlTextAreaElement>()
+            || target.has_type::<web_sys::HtmlSelectElement>()
+    });
+    if in_form_field || event.alt_key() {
+        return None;
+    }
+    let msg = if event.ctrl_key() || event.meta_key() {
+        match event.key().as_str() {
+            "z" => Msg::Undo,
+            "Z" | "y" => Msg::Redo,
+            _ => return None,
+        }
+    } else {
+        match event.key().as_str() {
+            "d" => Msg::FinishedTopTask,
+            "n" => Msg::FocusNewTaskName,
+            "[" => Msg::ViewPreviousWeek,
+            "]" => Msg::ViewNextWeek,
+            "?" => Msg::ToggleShortcutHelp,
+            "Escape" => Msg::ExitFocusMode,
+            _ => return None,
+        }
+    };
+    event.prevent_default();
+    Some(msg)
+}
+
+// (Remove the line below once any of your `Msg` variants doesn't implement `Copy`.)
+#[derive(Clone)]
+// `Msg` describes the different events you can modify state with.
+pub enum Msg {
+    UrlChanged(subs::UrlChanged),
+    SetCurrentDate(NaiveDate),
+    CheckDateRollover,
+    ClockTick,
+    // Adds the new task on the date picked for it.
+    AddNewTask,
+    AddNewTaskOn(NaiveDate),
+    NewTaskDateChanged(String),
+    // Empties the new task form, as after adding the task.
+    ResetNewTaskForm,
+    // Deletes a task from the plan of a date after the current one.
+    DeleteUpcomingTask(NaiveDate, Uuid),
+    ToggleBulkAdd,
+    BulkAddTextChanged(String),
+    AddBulkTasks,
+    QuickAddTask(String),
+    TogglePinQuickTask(String),
+    RemoveQuickTask(String),
+    DeleteTask(Uuid),
+    PostponeTask(Uuid),
+    RequestDeleteTask(Uuid),
+    CancelDeleteTask,
+    PendingDeleteTimedOut(Uuid),
+    ToggleOneClickDelete,
+    ToggleTargetReachedSound,
+    TargetReachedFlashEnded,
+    SetTheme(Theme),
+    // The browser's `prefers-color-scheme` changed, to dark if `true`.
+    PrefersDarkChanged(bool),
+    ViewportResized(Option<u32>),
+    DismissNotice(usize),
+    NoticeExpired(usize),
+    // Dismisses the notice and sends its action.
+    NoticeActionClicked(usize),
+    // Puts deleted periods back into the plan of the date, from the index.
+    RestoreTasks(NaiveDate, usize, Vec<Period>),
+    TaskNoteChanged(Uuid, String),
+    StartEditTask(Uuid),
+    EditTaskNameChanged(String),
+    CommitEditTask,
+    CancelEditTask,
+    MoveTaskToTop(Uuid),
+    MoveTaskUp(Uuid),
+    MoveTaskDown(Uuid),
+    MoveTaskToBottom(Uuid),
+    DeleteTasks(Vec<Uuid>),
+    MoveTasksToTop(Vec<Uuid>),
+    MoveTasksUp(Vec<Uuid>),
+    MoveTasksDown(Vec<Uuid>),
+    MoveTasksToBottom(Vec<Uuid>),
+    SetTaskPriority(Uuid, Priority),
+    SetTasksPriority(Vec<Uuid>, Priority),
+    // Stable, so tasks of the same priority keep their order.
+    SortTasksByPriority,
+    CopyUnfinishedTasksFromYesterday,
+    ToggleGroupRepeatedTasks,
+    StartTask(Uuid),
+    StartPomodoro(Uuid),
+    PomodoroTick,
+    PausePomodoro,
+    ResumePomodoro,
+    CancelPomodoro,
+    PomodoroFinishedDismissed,
+    PomodoroLengthChanged(String),
+    BreakTick,
+    SkipBreak,
+    ToggleBreaks,
+    BreakLengthChanged(String),
+    FinishedTopTask,
+    Undo,
+    Redo,
+    NewTaskNameChanged(String),
+    NewTaskCategoryChanged(String),
+    NewTaskNoteChanged(String),
+    NewTaskPriorityChanged(Priority),
+    FocusNewTaskName,
+    ToggleShortcutHelp,
+    EnterFocusMode,
+    ExitFocusMode,
+    NewTaskQuantityChanged(String),
+    NewTaskWeightChanged(String),
+    NewRecurringTaskNameChanged(String),
+    NewRecurringTaskQuantityChanged(String),
+    NewRecurringTaskWeekdayToggled(usize),
+    AddRecurringTask,
+    DeleteRecurringTask(Uuid),
+    BedtimeInputChanged(String),
+    BedtimeNextDayToggled(bool),
+    RecordBedtime,
+    // Records the time, read off the clock when going to bed, as the bedtime of the
+    // current date. One already recorded is only replaced once that's confirmed.
+    RecordBedtimeAt(NaiveDateTime),
+    ConfirmBedtimeOverwrite,
+    CancelBedtimeOverwrite,
+    DismissDayReview,
+    ToggleWeekReview,
+    // Saves the review notes of the week starting on the date as they're typed.
+    WeeklyNoteChanged(NaiveDate, String),
+    // Closes the day review to plan the day after the current date.
+    PlanTomorrow,
+    WakeTimeInputChanged(String),
+    RecordWakeTime,
+    ViewNextWeek,
+    ViewPreviousWeek,
+    ViewCurrentWeek,
+    ViewNextMonth,
+    ViewPreviousMonth,
+    ViewCurrentMonth,
+    SetHistoryView(HistoryView),
+    ConsistencyDaysChanged(String),
+    ToggleBedtimeReminder,
+    NotificationPermissionChanged(Option<NotificationPermission>),
+    BedtimeReminderLeadChanged(String),
+    CheckBedtimeReminder,
+    DayCutoffHourChanged(String),
+    WeekModeChanged(WeekMode),
+    DateFormatChanged(DateFormat),
+    ClockFormatChanged(ClockFormat),
+    LangChanged(Lang),
+    OnboardingGoalsChanged(WorkSleepGoals),
+    OnboardingStepChanged(OnboardingStep),
+    CompleteOnboarding(WorkSleepGoals),
+    GoalBalanceChanged(String),
+    GoalTargetWorkCountChanged(String),
+    GoalTargetBedtimeChanged(String),
+    GoalTargetBedtimeNextDayToggled(bool),
+    GoalBedtimePenaltyModeChanged(BedtimePenaltyMode),
+    GoalCapWorkScoreToggled,
+    GoalHalflifeChanged(String),
+    ToggleDayGoalsEditor,
+    // Opens the details of the day under the week, closing any other day's.
+    ToggleDayDetails(NaiveDate),
+    // Starts an empty day of data to fill in from its details.
+    CreateDayEntry(NaiveDate),
+    // A time of day, or an empty string to clear the bedtime.
+    SetDayBedtime(NaiveDate, String),
+    SetDayGoals(NaiveDate, WorkSleepGoals),
+    ResetDayGoals(NaiveDate),
+    // Switches between weekend goals starting from the defaults and none.
+    ToggleWeekendGoals,
+    SetWeekendGoals(WorkSleepGoals),
+    AdjustWorkCount(NaiveDate, i64),
+    // Picking the mood a date already has clears it.
+    SetMood(NaiveDate, u8),
+    ExportData,
+    CalendarStartChanged(String),
+    CalendarBlockMinutesChanged(String),
+    ExportCalendar,
+    ShowResetAllData,
+    ResetConfirmationChanged(String),
+    CancelResetAllData,
+    ResetAllData,
+    UndoResetAllData,
+    LeaveDemo,
+    ArchiveCutoffChanged(String),
+    ArchiveHistory,
+    WeekArchiveDaysChanged(String),
+    ArchiveOldWeeks,
+    ImportFileChosen(web_sys::File),
+    DataImported(Data),
+    ToggleImportMerge,
+    ImportGoalsChosen(GoalsSource),
+    ConfirmImport,
+    CancelImport,
+    ToggleSleepCsvOverwrite,
+    SleepCsvChosen(web_sys::File),
+    SleepCsvRead(String),
+    RemoteStateReceived(Data),
+    RemoteSyncUrlChanged(String),
+    RemoteSyncTokenChanged(String),
+    RemoteSyncEveryChanged(String),
+    SyncNow,
+    // The data stored on the sync server, or `None` if nothing was stored there yet.
+    RemoteDataFetched(Option<Data>),
+    RemoteDataPushed,
+    RemoteSyncFailed(String),
+    CopyWeekReport,
+    WeekReportCopied,
+    WeekReportCopyFailed(String),
+    WeekReportDismissed,
+    ImportFailed(String),
+}
+
+// `update` describes how to handle each `Msg`.
+impl Msg {
+    // Whether this message comes from a timer, another tab or a finished command
+    // rather than from something the user did.
+    fn is_background(&self) -> bool {
+        matches!(
+            self,
+            Msg::CheckDateRollover
+                | Msg::ClockTick
+                | Msg::PomodoroTick
+                | Msg::BreakTick
+                | Msg::PomodoroFinishedDismissed
+                | Msg::TargetReachedFlashEnded
+                | Msg::PrefersDarkChanged(_)
+                | Msg::ViewportResized(_)
+                | Msg::NotificationPermissionChanged(_)
+                | Msg::CheckBedtimeReminder
+                | Msg::DataImported(_)
+                | Msg::SleepCsvRead(_)
+                | Msg::ImportFailed(_)
+                | Msg::RemoteStateReceived(_)
+                | Msg::RemoteDataFetched(_)
+                | Msg::RemoteDataPushed
+                | Msg::RemoteSyncFailed(_)
+                | Msg::WeekReportCopied
+                | Msg::WeekReportCopyFailed(_)
+                | Msg::PendingDeleteTimedOut(_)
+                | Msg::NoticeExpired(_)
+        )
+    }
+}
+
+// How a message is kept in the undo history.
+enum History {
+    // It only changes what's shown or typed, or nothing the history covers.
+    Skip,
+    // It can be undone on its own, and only changes the data around the date.
+    Step(NaiveDate),
+    // Like `Step`, but repeats of it in a row are undone together, as when a slider
+    // is dragged.
+    Merge(NaiveDate),
+    // It changes so much that the history no longer applies.
+    Clear,
+}
+
+impl Msg {
+    // Whether the message can't change any day or goal, so that no badge can have been
+    // earned. Frequent messages belong here, as does anything else that only changes what's
+    // shown or typed.
+    fn keeps_days(&self) -> bool {
+        matches!(
+            self,
+            Msg::ClockTick
+                | Msg::PomodoroTick
+                | Msg::BreakTick
+                | Msg::SkipBreak
+                | Msg::ToggleBulkAdd
+                | Msg::BulkAddTextChanged(_)
+                | Msg::RequestDeleteTask(_)
+                | Msg::CancelDeleteTask
+                | Msg::PendingDeleteTimedOut(_)
+                | Msg::TargetReachedFlashEnded
+                | Msg::SetTheme(_)
+                | Msg::DateFormatChanged(_)
+                | Msg::ClockFormatChanged(_)
+                | Msg::LangChanged(_)
+                | Msg::ToggleImportMerge
+                | Msg::ImportGoalsChosen(_)
+                | Msg::CancelImport
+                | Msg::PrefersDarkChanged(_)
+                | Msg::ViewportResized(_)
+                | Msg::DismissNotice(_)
+                | Msg::NoticeExpired(_)
+                | Msg::NoticeActionClicked(_)
+                | Msg::RecordBedtimeAt(_)
+                | Msg::ConfirmBedtimeOverwrite
+                | Msg::CancelBedtimeOverwrite
+                | Msg::DismissDayReview
+                | Msg::ToggleWeekReview
+                | Msg::WeeklyNoteChanged(..)
+                | Msg::PlanTomorrow
+                | Msg::StartEditTask(_)
+                | Msg::EditTaskNameChanged(_)
+                | Msg::CancelEditTask
+                | Msg::NewTaskNameChanged(_)
+                | Msg::NewTaskCategoryChanged(_)
+                | Msg::NewTaskNoteChanged(_)
+                | Msg::NewTaskPriorityChanged(_)
+                | Msg::NewTaskQuantityChanged(_)
+                | Msg::NewTaskWeightChanged(_)
+                | Msg::NewTaskDateChanged(_)
+                | Msg::ResetNewTaskForm
+                | Msg::AddNewTask
+                | Msg::FocusNewTaskName
+                | Msg::ToggleShortcutHelp
+                | Msg::EnterFocusMode
+                | Msg::ExitFocusMode
+                | Msg::NewRecurringTaskNameChanged(_)
+                | Msg::NewRecurringTaskQuantityChanged(_)
+                | Msg::NewRecurringTaskWeekdayToggled(_)
+                | Msg::BedtimeInputChanged(_)
+                | Msg::BedtimeNextDayToggled(_)
+                | Msg::WakeTimeInputChanged(_)
+                | Msg::ViewNextWeek
+                | Msg::ViewPreviousWeek
+                | Msg::ViewCurrentWeek
+                | Msg::ViewNextMonth
+                | Msg::ViewPreviousMonth
+                | Msg::ViewCurrentMonth
+                | Msg::SetHistoryView(_)
+                | Msg::ConsistencyDaysChanged(_)
+                | Msg::ToggleDayGoalsEditor
+                | Msg::ToggleDayDetails(_)
+                | Msg::ToggleWeekendGoals
+                | Msg::SetWeekendGoals(_)
+                | Msg::ShowResetAllData
+                | Msg::ResetConfirmationChanged(_)
+                | Msg::CancelResetAllData
+                | Msg::ArchiveCutoffChanged(_)
+                | Msg::WeekArchiveDaysChanged(_)
+                | Msg::CalendarStartChanged(_)
+                | Msg::CalendarBlockMinutesChanged(_)
+                | Msg::ExportCalendar
+                | Msg::ToggleSleepCsvOverwrite
+                | Msg::CopyWeekReport
+                | Msg::WeekReportCopied
+                | Msg::WeekReportCopyFailed(_)
+                | Msg::WeekReportDismissed
+                | Msg::RemoteSyncUrlChanged(_)
+                | Msg::RemoteSyncTokenChanged(_)
+                | Msg::RemoteSyncEveryChanged(_)
+                | Msg::SyncNow
+                | Msg::RemoteDataPushed
+                | Msg::RemoteSyncFailed(_)
+        )
+    }
+
+    fn history(&self, current_date: NaiveDate) -> History {
+        match self {
+            Msg::AddNewTaskOn(date) => History::Merge(*date),
+            Msg::DeleteUpcomingTask(date, _) => History::Step(*date),
+            Msg::AddBulkTasks
+            | Msg::QuickAddTask(_)
+            | Msg::DeleteTask(_)
+            | Msg::PostponeTask(_)
+            | Msg::CommitEditTask
+            | Msg::MoveTaskToTop(_)
+            | Msg::MoveTaskUp(_)
+            | Msg::MoveTaskDown(_)
+            | Msg::MoveTaskToBottom(_)
+            | Msg::DeleteTasks(_)
+            | Msg::MoveTasksToTop(_)
+            | Msg::MoveTasksUp(_)
+            | Msg::MoveTasksDown(_)
+            | Msg::MoveTasksToBottom(_)
+            | Msg::SetTaskPriority(..)
+            | Msg::SetTasksPriority(..)
+            | Msg::SortTasksByPriority
+            | Msg::CopyUnfinishedTasksFromYesterday
+            | Msg::FinishedTopTask
+            | Msg::RecordBedtime
+            | Msg::RecordWakeTime
+            | Msg::GoalTargetBedtimeNextDayToggled(_)
+            | Msg::GoalBedtimePenaltyModeChanged(_)
+            | Msg::GoalCapWorkScoreToggled => History::Step(current_date),
+            Msg::SetDayGoals(date, _)
+            | Msg::ResetDayGoals(date)
+            | Msg::AdjustWorkCount(date, _)
+            | Msg::CreateDayEntry(date)
+            | Msg::SetDayBedtime(date, _)
+            | Msg::SetMood(date, _)
+            | Msg::RestoreTasks(date, ..) => History::Step(*date),
+            Msg::TaskNoteChanged(..)
+            | Msg::GoalBalanceChanged(_)
+            | Msg::GoalTargetWorkCountChanged(_)
+            | Msg::GoalTargetBedtimeChanged(_)
+            | Msg::GoalHalflifeChanged(_) => History::Merge(current_date),
+            Msg::DataImported(_)
+            | Msg::ConfirmImport
+            | Msg::RemoteStateReceived(_)
+            | Msg::SleepCsvRead(_)
+            | Msg::ArchiveHistory
+            | Msg::ArchiveOldWeeks
+            | Msg::ResetAllData
+            | Msg::UndoResetAllData => History::Clear,
+            _ => History::Skip,
+        }
+    }
+}
+
+// Something `apply` leaves for `update` to do through the browser or `Orders`.
+pub enum Effect {
+    // Nothing visible changed, so the page needn't be re-rendered.
+    Skip,
+    FocusNewTaskName,
+    FocusTaskNameEditor,
+    StartPomodoroTicker,
+    // Sets the pomodoro countdown text in place rather than re-rendering the page.
+    PatchCountdown(i64),
+    StartBreakTicker,
+    // Like `PatchCountdown`, for the break countdown.
+    PatchBreakCountdown(i64),
+    SendAfter(u32, Msg),
+    RequestNotificationPermission,
+    ShowBedtimeReminder(i64),
+    // Plays a chime if `true`.
+    CelebrateTargetReached(bool),
+    ExportData,
+    // Downloads the iCalendar text of the current date's plan.
+    DownloadCalendar(String),
+    // Reloads the app without `?demo`, back to the saved data.
+    LeaveDemo,
+    // Downloads the JSON of days archived before the date.
+    DownloadArchive(String, NaiveDate),
+    ReadImportFile(web_sys::File),
+    ReadSleepCsv(web_sys::File),
+    CopyWeekReport(String),
+    SaveRemoteSyncSettings,
+    // Fetches the data stored on the sync server.
+    PullRemoteData,
+    // Replaces the data stored on the sync server with `model.data`.
+    PushRemoteData,
+}
+
+pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    if let Msg::CheckBedtimeReminder = msg {
+        // Permission can be revoked from the browser settings at any time.
+        model.notification_permission = notification_permission();
+    }
+    for effect in apply(msg, model) {
+        run_effect(effect, model, orders);
+    }
+    let title = if model.target_reached {
+        model.data.lang.tr(TextKey::TargetReachedTitle).to_owned()
+    } else {
+        let today = model.today;
+        tab_title(model.data.plan(today).front().map(|wp| wp.name.as_str()))
+    };
+    if title != model.tab_title {
+        set_tab_title(&title);
+        model.tab_title = title;
+    }
+    let dark_mode = model.data.theme.is_dark(model.prefers_dark);
+    if dark_mode != model.dark_mode {
+        set_dark_mode(dark_mode);
+        model.dark_mode = dark_mode;
+    }
+    if model.demo {
+        return;
+    }
+    if sync_to_other_tabs(model) {
+        model.dirty = true;
+        model.changes_since_remote_sync += 1;
+        if model.remote_sync_due() {
+            run_effect(start_remote_sync(model), model, orders);
+        }
+    }
+    match LocalStorage::insert(STORAGE_KEY, &model.data) {
+        Ok(()) => model.dirty = false,
+        Err(err) => log!("failed to save data to LocalStorage", err),
+    }
+    model.unload_warning.set(
+        model.dirty,
+        model.data.lang.tr(TextKey::UnsavedChangesPrompt),
+    );
+}
+
+// Updates `model` for `msg` without touching the browser, so that it can be tested
+// natively, and returns what is left to do.
+pub fn apply(msg: Msg, model: &mut Model) -> Vec<Effect> {
+    let (before, merge_key) = match msg.history(model.data.current_date) {
+        History::Skip => (None, None),
+        History::Step(date) => (Some(DataSnapshot::take(&model.data, date)), None),
+        History::Merge(date) => (
+            Some(DataSnapshot::take(&model.data, date)),
+            Some(std::mem::discriminant(&msg)),
+        ),
+        History::Clear => {
+            model.undo_stack.clear();
+            model.redo_stack.clear();
+            (None, None)
+        }
+    };
+    let keeps_days = msg.keeps_days();
+    let mut effects = apply_msg(msg, model);
+    if !keeps_days {
+        effects.extend(award_achievements(model));
+    }
+    if let Some(before) = before {
+        if before != DataSnapshot::take(&model.data, before.date()) {
+            model.record_change(before, merge_key);
+        }
+    }
+    effects
+}
+
+fn apply_msg(msg: Msg, model: &mut Model) -> Vec<Effect> {
+    let mut effects = Vec::new();
+    // Any other action abandons a pending delete.
+    if !msg.is_background() {
+        model.pending_delete = None;
+    }
+    match msg {
+        Msg::UrlChanged(subs::UrlChanged(url)) => {
+            model.page = Page::from_url(&url);
+            let week = url
+                .hash()
+                .and_then(|hash| NaiveDate::parse_from_str(hash, "%Y-%m-%d").ok());
+            if let (Page::History, Some(date)) = (model.page, week) {
+                model
+                    .data
+                    .work_sleep_data
+                    .set_week_start(&date, model.data.week_mode);
+            }
+        }
+        Msg::SetCurrentDate(date) => {
+            let today = model.today;
+            apply_action(model, Action::SetCurrentDate { date, today }, &mut effects).ok();
+        }
+        Msg::CheckDateRollover => {
+            let today = model.clock.today(model.data.day_cutoff_hour);
+            if today != model.today {
+                model.today = today;
+                let action = Action::SetCurrentDate { date: today, today };
+                apply_action(model, action, &mut effects).ok();
+            } else {
+                effects.push(Effect::Skip);
+            }
+        }
+        Msg::ViewportResized(width) => {
+            let was_narrow = model.is_narrow();
+            model.viewport_width = width;
+            // Resizing fires often, and only crossing the breakpoint changes the page.
+            if model.is_narrow() == was_narrow {
+                effects.push(Effect::Skip);
+            }
+        }
+        Msg::ClockTick => {
+            model.now = model.clock.now();
+            // The bedtime countdown in the header follows the clock on every page.
+            if model.page != Page::Tracking && model.bedtime_countdown().is_none() {
+                effects.push(Effect::Skip);
+            }
+        }
+        Msg::AddNewTask => {
+            let date = model.new_task.date.unwrap_or(model.data.current_date);
+            effects.extend(apply(Msg::AddNewTaskOn(date), model));
+        }
+        Msg::AddNewTaskOn(date) => {
+            let new_task = &model.new_task;
+            let name = normalize_task_name(&new_task.name);
+            let parsed = new_task.quantity_parsed.clone().and_then(|quantity| {
+                let weight = new_task.weight_parsed.clone()?;
+                Ok((quantity, weight))
+            });
+            match parsed {
+                Err(err) if !name.is_empty() => {
+                    effects.push(push_notice(model, Severity::Error, err));
+                }
+                Ok((quantity, weight)) if !name.is_empty() => {
+                    let capped = new_task
+                        .quantity
+                        .trim()
+                        .parse::<i64>()
+                        .is_ok_and(|requested| requested > quantity);
+                    let task = Period {
+                        category: new_task.category(),
+                        note: new_task.note.trim().to_owned(),
+                        priority: new_task.priority,
+                        weight,
+                        ..Period::new(name)
+                    };
+                    let today = model.today;
+                    let action = Action::AddTasks {
+                        date,
+                        task,
+                        quantity,
+                        today,
+                    };
+                    apply_action(model, action, &mut effects).ok();
+                    let data = &model.data;
+                    effects.push(Effect::FocusNewTaskName);
+                    if date != data.current_date {
+                        let message = data
+                            .lang
+                            .trf(TextKey::AddedToPlanFor, &[&data.fmt_date(date)]);
+                        effects.push(push_notice(model, Severity::Info, message));
+                    }
+                    if capped {
+                        let message = model.data.lang.trf(TextKey::OnlySomeAdded, &[&quantity]);
+                        effects.push(push_notice(model, Severity::Info, message));
+                    }
+                    effects.extend(apply(Msg::ResetNewTaskForm, model));
+                }
+                _ => {}
+            }
+        }
+        Msg::ResetNewTaskForm => {
+            model.new_task = NewTask::default();
+        }
+        Msg::NewTaskDateChanged(s) => {
+            let date = NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok();
+            model.new_task.date =
+                date.filter(|date| *date >= model.today && *date != model.data.current_date);
+        }
+        Msg::DeleteUpcomingTask(date, id) => {
+            apply_action(model, Action::DeleteTasks(date, vec![id]), &mut effects).ok();
+        }
+        Msg::ToggleBulkAdd => {
+            model.bulk_add = match model.bulk_add {
+                Some(_) => None,
+                None => Some(String::new()),
+            };
+        }
+        Msg::BulkAddTextChanged(text) => {
+            model.bulk_add = Some(text);
+        }
+        Msg::AddBulkTasks => {
+            let tasks = parse_bulk_tasks(model.bulk_add.as_deref().unwrap_or_default());
+            if !tasks.is_empty() {
+                let (date, today) = (model.data.current_date, model.today);
+                for (name, quantity) in tasks {
+                    let action = Action::AddTasks {
+                        date,
+                        task: Period::new(name),
+                        quantity,
+                        today,
+                    };
+                    apply_action(model, action, &mut effects).ok();
+                }
+                model.bulk_add = None;
+            }
+        }
+        Msg::QuickAddTask(name) => {
+            let action = Action::AddTasks {
+                date: model.data.current_date,
+                task: Period::new(name),
+                quantity: 1,
+                today: model.today,
+            };
+            apply_action(model, action, &mut effects).ok();
+        }
+        Msg::TogglePinQuickTask(name) => {
+            apply_action(model, Action::TogglePinQuickTask(name), &mut effects).ok();
+        }
+        Msg::RemoveQuickTask(name) => {
+            apply_action(model, Action::RemoveQuickTask(name), &mut effects).ok();
+        }
+        Msg::DeleteTask(id) => {
+            effects.extend(delete_tasks(model, vec![id]));
+        }
+        Msg::PostponeTask(id) => {
+            let now = model.clock.now().time();
+            apply_action(model, Action::PostponeTask { id, now }, &mut effects).ok();
+        }
+        Msg::RequestDeleteTask(id) => {
+            if find_task(model.data.current_plan(), id).is_some() {
+                model.pending_delete = Some(id);
+                effects.push(Effect::SendAfter(
+                    PENDING_DELETE_TIMEOUT_MS,
+                    Msg::PendingDeleteTimedOut(id),
+                ));
+            } else {
+                effects.push(push_missing_task_notice(model));
+            }
+        }
+        Msg::CancelDeleteTask => {}
+        Msg::PendingDeleteTimedOut(id) => {
+            if model.pending_delete == Some(id) {
+                model.pending_delete = None;
+            } else {
+                effects.push(Effect::Skip);
+            }
+        }
+        Msg::DismissNotice(id) => {
+            model.notices.retain(|notice| notice.id != id);
+        }
+        Msg::NoticeExpired(id) => {
+            let now = model.clock.now();
+            model
+                .notices
+                .retain(|notice| notice.id != id && notice.expires_at > now);
+        }
+        Msg::NoticeActionClicked(id) => {
+            let position = model.notices.iter().position(|notice| notice.id == id);
+            if let Some(action) = position.and_then(|i| model.notices.remove(i)?.action) {
+                effects.extend(apply(action.1, model));
+            }
+        }
+        Msg::ToggleOneClickDelete => {
+            apply_action(model, Action::ToggleOneClickDelete, &mut effects).ok();
+        }
+        Msg::ToggleTargetReachedSound => {
+            apply_action(model, Action::ToggleTargetReachedSound, &mut effects).ok();
+        }
+        Msg::SetTheme(theme) => {
+            apply_action(model, Action::SetTheme(theme), &mut effects).ok();
+        }
+        Msg::PrefersDarkChanged(prefers_dark) => {
+            model.prefers_dark = prefers_dark;
+        }
+        Msg::TargetReachedFlashEnded => {
+            model.target_reached = false;
+        }
+        Msg::StartEditTask(id) => match model.data.planned_task(id) {
+            Some(wp) => {
+                model.editing_task = Some((id, wp.name.clone()));
+                effects.push(Effect::FocusTaskNameEditor);
+            }
+            None => effects.push(push_missing_task_notice(model)),
+        },
+        Msg::EditTaskNameChanged(s) => {
+            if let Some((_, name)) = &mut model.editing_task {
+                *name = s;
+            }
+        }
+        Msg::CommitEditTask => {
+            if let Some((id, name)) = model.editing_task.take() {
+                // An empty name leaves the task as it was.
+                apply_action(model, Action::RenameTask(id, name), &mut effects).ok();
+            }
+        }
+        Msg::CancelEditTask => {
+            model.editing_task = None;
+        }
+        Msg::MoveTaskToTop(id) => {
+            apply_action(model, Action::MoveTaskToTop(id), &mut effects).ok();
+        }
+        Msg::MoveTaskUp(id) => {
+            apply_action(model, Action::MoveTaskUp(id), &mut effects).ok();
+        }
+        Msg::MoveTaskDown(id) => {
+            apply_action(model, Action::MoveTaskDown(id), &mut effects).ok();
+        }
+        Msg::MoveTaskToBottom(id) => {
+            apply_action(model, Action::MoveTaskToBottom(id), &mut effects).ok();
+        }
+        Msg::DeleteTasks(ids) => {
+            effects.extend(delete_tasks(model, ids));
+        }
+        Msg::RestoreTasks(date, index, periods) => {
+            let action = Action::RestoreTasks(date, index, periods);
+            apply_action(model, action, &mut effects).ok();
+        }
+        Msg::MoveTasksToTop(ids) => {
+            apply_action(model, Action::MoveTasksToTop(ids), &mut effects).ok();
+        }
+        Msg::MoveTasksUp(ids) => {
+            apply_action(model, Action::MoveTasksUp(ids), &mut effects).ok();
+        }
+        Msg::MoveTasksDown(ids) => {
+            apply_action(model, Action::MoveTasksDown(ids), &mut effects).ok();
+        }
+        Msg::MoveTasksToBottom(ids) => {
+            apply_action(model, Action::MoveTasksToBottom(ids), &mut effects).ok();
+        }
+        Msg::SetTaskPriority(id, priority) => {
+            let action = Action::SetTaskPriority(id, priority);
+            apply_action(model, action, &mut effects).ok();
+        }
+        Msg::SetTasksPriority(ids, priority) => {
+            let action = Action::SetTasksPriority(ids, priority);
+            apply_action(model, action, &mut effects).ok();
+        }
+        Msg::SortTasksByPriority => {
+            apply_action(model, Action::SortTasksByPriority, &mut effects).ok();
+        }
+        Msg::CopyUnfinishedTasksFromYesterday => {
+            let action = Action::CopyUnfinishedTasksFromYesterday;
+            apply_action(model, action, &mut effects).ok();
+        }
+        Msg::ToggleGroupRepeatedTasks => {
+            apply_action(model, Action::ToggleGroupRepeatedTasks, &mut effects).ok();
+        }
+        Msg::StartTask(id) => {
+            let now = model.clock.now().time();
+            apply_action(model, Action::StartTask { id, now }, &mut effects).ok();
+        }
+        Msg::StartPomodoro(id) if !model.data.has_planned_task(id) => {
+            effects.push(push_missing_task_notice(model));
+        }
+        Msg::StartPomodoro(id) => {
+            model.pomodoro = Some(Pomodoro {
+                task_id: id,
+                remaining_secs: model.data.pomodoro_minutes * 60,
+                ticker: None,
+            });
+            model.pomodoro_finished = false;
+            effects.push(Effect::StartPomodoroTicker);
+            effects.extend(apply(Msg::StartTask(id), model));
+        }
+        Msg::PomodoroTick => {
+            if let Some(pomodoro) = &mut model.pomodoro {
+                pomodoro.remaining_secs -= 1;
+                if pomodoro.remaining_secs <= 0 {
+                    let task_id = pomodoro.task_id;
+                    model.pomodoro = None;
+                    model.pomodoro_finished = true;
+                    let front_id = model.data.current_plan().front().map(|wp| wp.id);
+                    effects.push(Effect::SendAfter(
+                        POMODORO_FLASH_MS,
+                        Msg::PomodoroFinishedDismissed,
+                    ));
+                    if front_id == Some(task_id) {
+                        effects.extend(apply(Msg::FinishedTopTask, model));
+                    }
+                } else {
+                    effects.push(Effect::PatchCountdown(pomodoro.remaining_secs));
+                }
+            }
+        }
+        Msg::PausePomodoro => {
+            if let Some(pomodoro) = &mut model.pomodoro {
+                pomodoro.ticker = None;
+            }
+        }
+        Msg::ResumePomodoro => {
+            if let Some(pomodoro) = &mut model.pomodoro {
+                if pomodoro.ticker.is_none() {
+                    effects.push(Effect::StartPomodoroTicker);
+                }
+            }
+        }
+        Msg::CancelPomodoro => {
+            model.pomodoro = None;
+        }
+        Msg::PomodoroFinishedDismissed => {
+            model.pomodoro_finished = false;
+        }
+        Msg::PomodoroLengthChanged(s) => {
+            if let Ok(minutes) = s.trim().parse() {
+                let action = Action::SetPomodoroMinutes(minutes);
+                apply_action(model, action, &mut effects).ok();
+            }
+        }
+        Msg::BreakTick => {
+            if let Some(work_break) = &mut model.work_break {
+                work_break.remaining_secs -= 1;
+                if work_break.remaining_secs <= 0 {
+                    model.work_break = None;
+                    let message = model.data.lang.tr(TextKey::BreakOver);
+                    effects.push(push_notice(model, Severity::Info, message));
+                } else {
+                    effects.push(Effect::PatchBreakCountdown(work_break.remaining_secs));
+                }
+            }
+        }
+        Msg::SkipBreak => {
+            model.work_break = None;
+        }
+        Msg::ToggleBreaks => {
+            apply_action(model, Action::ToggleBreaks, &mut effects).ok();
+            if !model.data.breaks_enabled {
+                model.work_break = None;
+            }
+        }
+        Msg::BreakLengthChanged(s) => {
+            if let Ok(minutes) = s.trim().parse() {
+                apply_action(model, Action::SetBreakMinutes(minutes), &mut effects).ok();
+            }
+        }
+        Msg::FinishedTopTask if model.is_planning() => {
+            let message = model.data.lang.tr(TextKey::FinishNotYet);
+            effects.push(push_notice(model, Severity::Warning, message));
+        }
+        Msg::FinishedTopTask if model.work_break.is_some() => {
+            let message = model.data.lang.tr(TextKey::OnBreak);
+            effects.push(push_notice(model, Severity::Warning, message));
+        }
+        Msg::FinishedTopTask => {
+            let date = model.data.current_date;
+            let day = |data: &Data| data.work_sleep_data.get(&date).cloned();
+            let before = day(&model.data);
+            let now = model.clock.now().time();
+            let today = model.today;
+            apply_action(model, Action::FinishTopTask { now, today }, &mut effects).ok();
+            let finished = day(&model.data).filter(|ws| {
+                let done_before = before.as_ref().map_or(0, |ws| ws.completed.len());
+                ws.completed.len() > done_before
+            });
+            if let Some(ws) = finished {
+                let defaults = &model.data.default_work_sleep_goals;
+                let target = ws.effective_goals(defaults).target_work_count;
+                let celebrated = before.is_some_and(|ws| ws.target_celebrated);
+                if ws.target_celebrated && !celebrated {
+                    model.target_reached = true;
+                    let sound = model.data.target_reached_sound;
+                    effects.push(Effect::CelebrateTargetReached(sound));
+                    effects.push(Effect::SendAfter(
+                        TARGET_REACHED_FLASH_MS,
+                        Msg::TargetReachedFlashEnded,
+                    ));
+                }
+                let lang = model.data.lang;
+                let done = lang.trf(
+                    TextKey::WorkDoneOf,
+                    &[&format_work_count(ws.weighted_work_count()), &target],
+                );
+                let name = ws.completed.last().map_or("", |cp| cp.name.as_str());
+                model.announcement = match model.data.current_plan().front() {
+                    Some(next) => lang.trf(TextKey::FinishedNext, &[&name, &done, &next.name]),
+                    None => lang.trf(TextKey::FinishedNoneLeft, &[&name, &done]),
+                };
+                if model.data.breaks_enabled {
+                    model.work_break = Some(WorkBreak {
+                        remaining_secs: model.data.break_minutes * 60,
+                        _ticker: None,
+                    });
+                    effects.push(Effect::StartBreakTicker);
+                }
+                let review = Some((date, target));
+                if model.data.current_plan().is_empty() && model.day_review_shown != review {
+                    model.day_review_shown = review;
+                    model.day_review_open = true;
+                }
+            }
+        }
+        Msg::ToggleWeekReview => {
+            model.week_review_open ^= true;
+        }
+        Msg::WeeklyNoteChanged(week_start, note) => {
+            let action = Action::SetWeeklyNote(week_start, note);
+            apply_action(model, action, &mut effects).ok();
+        }
+        Msg::DismissDayReview => {
+            model.day_review_open = false;
+        }
+        Msg::PlanTomorrow => {
+            model.day_review_open = false;
+            if let Some(tomorrow) = model.data.current_date.succ_opt() {
+                effects.extend(apply(Msg::SetCurrentDate(tomorrow), model));
+                effects.push(Effect::FocusNewTaskName);
+            }
+        }
+        Msg::RecordBedtimeAt(now) => {
+            let date = model.data.current_date;
+            match bedtime_now(now, model.data.day_cutoff_hour) {
+                Some((night, bedtime)) if night == date => {
+                    model.day_review_open = false;
+                    let ws = model.data.work_sleep_data.get(&date);
+                    if ws.is_some_and(|ws| ws.actual_bedtime.is_some()) {
+                        model.bedtime_to_confirm = Some(bedtime);
+                    } else {
+                        effects.extend(record_bedtime_now(model, bedtime));
+                    }
+                }
+                Some((night, _)) => {
+                    let message = (model.data.lang)
+                        .trf(TextKey::AlreadyNightOf, &[&model.data.fmt_date(night)]);
+                    effects.push(push_notice(model, Severity::Warning, message));
+                }
+                None => {}
+            }
+        }
+        Msg::ConfirmBedtimeOverwrite => {
+            if let Some(bedtime) = model.bedtime_to_confirm.take() {
+                effects.extend(record_bedtime_now(model, bedtime));
+            }
+        }
+        Msg::CancelBedtimeOverwrite => {
+            model.bedtime_to_confirm = None;
+        }
+        Msg::Undo => {
+            if let Some(step) = model.undo_stack.pop_back() {
+                let after = step.before.restore(&mut model.data);
+                model.redo_stack.push(after);
+            }
+        }
+        Msg::Redo => {
+            if let Some(after) = model.redo_stack.pop() {
+                let before = after.restore(&mut model.data);
+                model.push_history(HistoryStep {
+                    before,
+                    merge_key: None,
+                });
+            }
+        }
+        Msg::NewTaskNameChanged(s) => {
+            model.new_task.name = s;
+        }
+        Msg::NewTaskCategoryChanged(s) => {
+            model.new_task.category = s;
+        }
+        Msg::NewTaskPriorityChanged(priority) => {
+            model.new_task.priority = priority;
+        }
+        Msg::NewTaskNoteChanged(s) => {
+            model.new_task.note = s;
+        }
+        Msg::TaskNoteChanged(id, note) => {
+            apply_action(model, Action::SetTaskNote(id, note), &mut effects).ok();
+        }
+        Msg::FocusNewTaskName => {
+            effects.push(Effect::FocusNewTaskName);
+        }
+        Msg::ToggleShortcutHelp => {
+            model.show_shortcut_help ^= true;
+        }
+        Msg::EnterFocusMode => {
+            model.focus_mode = true;
+        }
+        Msg::ExitFocusMode => {
+            model.focus_mode = false;
+        }
+        Msg::NewTaskQuantityChanged(s) => {
+            model.new_task.quantity_parsed = parse_quantity(&s, MAX_TASK_QUANTITY, model.data.lang);
+            model.new_task.quantity = s;
+        }
+        Msg::NewTaskWeightChanged(s) => {
+            model.new_task.weight_parsed = parse_weight(&s, model.data.lang);
+            model.new_task.weight = s;
+        }
+        Msg::NewRecurringTaskNameChanged(s) => {
+            model.new_recurring_task.name = s;
+        }
+        Msg::NewRecurringTaskQuantityChanged(s) => {
+            model.new_recurring_task.quantity_parsed =
+                parse_quantity(&s, MAX_TASK_QUANTITY, model.data.lang);
+            model.new_recurring_task.quantity = s;
+        }
+        Msg::NewRecurringTaskWeekdayToggled(weekday) => {
+            model.new_recurring_task.weekdays[weekday] ^= true;
+        }
+        Msg::AddRecurringTask => {
+            let new_task = &model.new_recurring_task;
+            if let Ok(quantity) = new_task.quantity_parsed {
+                let weekdays = new_task.weekdays;
+                let task = RecurringTask {
+                    id: Uuid::new_v4(),
+                    name: new_task.name.clone(),
+                    quantity,
+                    weekdays: IF!(weekdays.contains(&true) => weekdays),
+                };
+                if apply_action(model, Action::AddRecurringTask(task), &mut effects).is_ok() {
+                    model.new_recurring_task = NewRecurringTask::default();
+                }
+            }
+        }
+        Msg::DeleteRecurringTask(id) => {
+            apply_action(model, Action::DeleteRecurringTask(id), &mut effects).ok();
+        }
+        Msg::BedtimeInputChanged(s) => {
+            model.current_date_bedtime.time = s;
+        }
+        Msg::BedtimeNextDayToggled(is_next_day) => {
+            model.current_date_bedtime.is_next_day = is_next_day;
+        }
+        Msg::RecordBedtime if model.is_planning() => {
+            let message = model.data.lang.tr(TextKey::BedtimeNotYet);
+            effects.push(push_notice(model, Severity::Warning, message));
+        }
+        Msg::WakeTimeInputChanged(s) => {
+            model.wake_time_input.time = s;
+        }
+        Msg::RecordWakeTime if model.is_planning() => {
+            let message = model.data.lang.tr(TextKey::WakeTimeNotYet);
+            effects.push(push_notice(model, Severity::Warning, message));
+        }
+        Msg::RecordWakeTime => {
+            let data = &model.data;
+            let night = data.current_date.pred_opt();
+            let time = parse_time(&model.wake_time_input.time);
+            let clock = data.clock_format;
+            let lang = data.lang;
+            let error = match (night, time) {
+                (Some(night), Some(time)) => {
+                    let wake_time = Bedtime {
+                        time,
+                        next_day: true,
+                    };
+                    let action = Action::SetWakeTime {
+                        night,
+                        wake_time: wake_time.clone(),
+                        today: model.today,
+                    };
+                    match apply_action(model, action, &mut effects) {
+                        Err(ActionError::SleepTooLong) => {
+                            let ws = model.data.work_sleep_data.get(&night);
+                            let bedtime = ws.and_then(|ws| ws.actual_bedtime.as_ref());
+                            let bedtime = bedtime.map_or_else(String::new, |bedtime| {
+                                format_bedtime(bedtime, clock, lang)
+                            });
+                            Some(lang.trf(
+                                TextKey::SleepTooLongAfter,
+                                &[
+                                    &clock.format(wake_time.time),
+                                    &(MAX_SLEEP_MINUTES / 60),
+                                    &bedtime,
+                                ],
+                            ))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => Some(lang.trf(
+                    TextKey::InvalidWakeTime,
+                    &[&model.wake_time_input.time, &clock.example()],
+                )),
+            };
+            model.wake_time_input.error = error;
+        }
+        Msg::RecordBedtime => {
+            let clock = model.data.clock_format;
+            let lang = model.data.lang;
+            let bedtime = &mut model.current_date_bedtime;
+            match parse_time(&bedtime.time) {
+                Some(time) => {
+                    bedtime.error = None;
+                    let next_day = bedtime.is_next_day;
+                    let action = Action::SetBedtime {
+                        date: model.data.current_date,
+                        bedtime: Some(Bedtime { time, next_day }),
+                        today: model.today,
+                    };
+                    apply_action(model, action, &mut effects).ok();
+                }
+                None => {
+                    bedtime.error =
+                        Some(lang.trf(TextKey::InvalidBedtime, &[&bedtime.time, &clock.example()]));
+                }
+            }
+        }
+        Msg::ViewNextWeek => {
+            if model.data.work_sleep_data.week_end() < model.data.current_date {
+                model
+                    .data
+                    .work_sleep_data
+                    .shift_weeks(1, model.data.week_mode);
+            }
+        }
+        Msg::ViewPreviousWeek => {
+            model
+                .data
+                .work_sleep_data
+                .shift_weeks(-1, model.data.week_mode);
+        }
+        Msg::DateFormatChanged(format) => {
+            apply_action(model, Action::SetDateFormat(format), &mut effects).ok();
+        }
+        Msg::ClockFormatChanged(format) => {
+            apply_action(model, Action::SetClockFormat(format), &mut effects).ok();
+        }
+        Msg::LangChanged(lang) => {
+            apply_action(model, Action::SetLang(lang), &mut effects).ok();
+        }
+        Msg::WeekModeChanged(mode) => {
+            apply_action(model, Action::SetWeekMode(mode), &mut effects).ok();
+        }
+        Msg::ViewNextMonth => {
+            let week_start = &mut model.data.work_sleep_data.week_start;
+            if let Some(next) = add_months(*week_start, 1) {
+                if next <= model.data.current_date {
+                    *week_start = next;
+                }
+            }
+        }
+        Msg::ViewPreviousMonth => {
+            let week_start = &mut model.data.work_sleep_data.week_start;
+            if let Some(previous) = add_months(*week_start, -1) {
+                *week_start = previous;
+            }
+        }
+        Msg::ViewCurrentMonth => {
+            if let Some(first) = add_months(model.data.current_date, 0) {
+                model.data.work_sleep_data.week_start = first;
+            }
+        }
+        Msg::SetHistoryView(history_view) => {
+            model.history_view = history_view;
+        }
+        Msg::ViewCurrentWeek => {
+            model
+                .data
+                .work_sleep_data
+                .set_week_start(&model.data.current_date, model.data.week_mode);
+        }
+        Msg::OnboardingGoalsChanged(goals) => {
+            if let Some(onboarding) = &mut model.onboarding {
+                onboarding.goals = goals.clamped();
+            }
+        }
+        Msg::OnboardingStepChanged(step) => {
+            if let Some(onboarding) = &mut model.onboarding {
+                onboarding.step = step;
+            }
+        }
+        Msg::CompleteOnboarding(goals) => {
+            apply_action(model, Action::CompleteOnboarding(goals), &mut effects).ok();
+            model.onboarding = None;
+        }
+        Msg::GoalBalanceChanged(s) => {
+            if let Some(balance) = parse_in_range(&s, 0, 100) {
+                effects.extend(set_default_goals(model, |goals| {
+                    goals.work_sleep_balance = balance
+                }));
+            }
+        }
+        Msg::GoalTargetWorkCountChanged(s) => {
+            if let Some(count) = parse_in_range(&s, 0, MAX_TARGET_WORK_COUNT) {
+                effects.extend(set_default_goals(model, |goals| {
+                    goals.target_work_count = count
+                }));
+            }
+        }
+        Msg::GoalTargetBedtimeChanged(s) => {
+            if let Ok(time) = NaiveTime::parse_from_str(&s, "%H:%M") {
+                effects.extend(set_default_goals(model, |goals| {
+                    goals.target_bedtime.time = time
+                }));
+            }
+        }
+        Msg::GoalCapWorkScoreToggled => {
+            effects.extend(set_default_goals(model, |goals| {
+                goals.cap_work_score ^= true
+            }));
+        }
+        Msg::GoalBedtimePenaltyModeChanged(mode) => {
+            effects.extend(set_default_goals(model, |goals| {
+                goals.bedtime_penalty_mode = mode
+            }));
+        }
+        Msg::GoalTargetBedtimeNextDayToggled(next_day) => {
+            effects.extend(set_default_goals(model, |goals| {
+                goals.target_bedtime.next_day = next_day
+            }));
+        }
+        Msg::GoalHalflifeChanged(s) => {
+            let (min, max) = (MIN_BEDTIME_PTS_HALFLIFE, MAX_BEDTIME_PTS_HALFLIFE);
+            if let Some(halflife) = parse_in_range(&s, min, max) {
+                effects.extend(set_default_goals(model, |goals| {
+                    goals.bedtime_pts_halflife = halflife
+                }));
+            }
+        }
+        Msg::ToggleDayGoalsEditor => {
+            model.day_goals_editor_open ^= true;
+        }
+        Msg::ToggleDayDetails(date) => {
+            model.expanded_day = if model.expanded_day == Some(date) {
+                None
+            } else {
+                Some(date)
+            };
+        }
+        Msg::CreateDayEntry(date) => {
+            let today = model.today;
+            apply_action(model, Action::CreateDayEntry { date, today }, &mut effects).ok();
+        }
+        Msg::SetDayBedtime(date, s) => {
+            if date <= model.today {
+                let bedtime = if s.trim().is_empty() {
+                    Some(None)
+                } else {
+                    parse_time(&s).map(|time| Some(Bedtime::from_time_of_day(time)))
+                };
+                if let Some(bedtime) = bedtime {
+                    let today = model.today;
+                    let action = Action::SetBedtime {
+                        date,
+                        bedtime,
+                        today,
+                    };
+                    apply_action(model, action, &mut effects).ok();
+                } else {
+                    let example = model.data.clock_format.example();
+                    let message = model
+                        .data
+                        .lang
+                        .trf(TextKey::InvalidBedtime, &[&s, &example]);
+                    effects.push(push_notice(model, Severity::Error, message));
+                }
+            }
+        }
+        Msg::SetDayGoals(date, goals) => {
+            apply_action(model, Action::SetDayGoals(date, goals), &mut effects).ok();
+        }
+        Msg::ResetDayGoals(date) => {
+            apply_action(model, Action::ResetDayGoals(date), &mut effects).ok();
+        }
+        Msg::ToggleWeekendGoals => {
+            apply_action(model, Action::ToggleWeekendGoals, &mut effects).ok();
+        }
+        Msg::SetWeekendGoals(goals) => {
+            apply_action(model, Action::SetWeekendGoals(goals), &mut effects).ok();
+        }
+        Msg::ToggleBedtimeReminder => {
+            if model.data.bedtime_reminder_enabled {
+                model.data.bedtime_reminder_enabled = false;
+            } else {
+                match model.notification_permission {
+                    Some(NotificationPermission::Granted) => {
+                        model.data.bedtime_reminder_enabled = true;
+                    }
+                    Some(NotificationPermission::Default) => {
+                        effects.push(Effect::RequestNotificationPermission);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Msg::NotificationPermissionChanged(permission) => {
+            model.notification_permission = permission;
+            model.data.bedtime_reminder_enabled =
+                permission == Some(NotificationPermission::Granted);
+        }
+        Msg::BedtimeReminderLeadChanged(s) => {
+            if let Ok(lead) = s.trim().parse() {
+                let action = Action::SetBedtimeReminderLead(lead);
+                apply_action(model, action, &mut effects).ok();
+            }
+        }
+        Msg::CheckBedtimeReminder => {
+            let data = &mut model.data;
+            let enabled = data.bedtime_reminder_enabled
+                && model.notification_permission == Some(NotificationPermission::Granted);
+            match data.bedtime_reminder_date(model.clock.now(), model.today) {
+                Some(date) if enabled => {
+                    data.last_bedtime_reminder = Some(date);
+                    effects.push(Effect::ShowBedtimeReminder(
+                        data.bedtime_reminder_lead_minutes,
+                    ));
+                }
+                _ => {
+                    effects.push(Effect::Skip);
+                }
+            }
+        }
+        Msg::DayCutoffHourChanged(s) => {
+            let action = s.trim().parse().map(Action::SetDayCutoffHour);
+            if let Ok(Ok(())) = action.map(|action| apply_action(model, action, &mut effects)) {
+                // Only which date counts as today changes; recorded days stay where they are.
+                effects.extend(apply(Msg::CheckDateRollover, model));
+            }
+        }
+        Msg::AdjustWorkCount(date, delta) => {
+            let today = model.today;
+            let action = Action::AdjustWorkCount { date, delta, today };
+            apply_action(model, action, &mut effects).ok();
+        }
+        Msg::SetMood(date, mood) => {
+            let today = model.today;
+            apply_action(model, Action::SetMood { date, mood, today }, &mut effects).ok();
+        }
+        Msg::ConsistencyDaysChanged(s) => {
+            if let Ok(days) = s.trim().parse() {
+                let action = Action::SetConsistencyDays(days);
+                apply_action(model, action, &mut effects).ok();
+            }
+        }
+        Msg::ExportData => {
+            effects.push(Effect::ExportData);
+        }
+        Msg::CalendarStartChanged(s) => {
+            if let Ok(start) = NaiveTime::parse_from_str(&s, "%H:%M") {
+                model.calendar_start = start;
+            }
+        }
+        Msg::CalendarBlockMinutesChanged(s) => {
+            if let Some(minutes) = parse_in_range(&s, 1, MAX_CALENDAR_BLOCK_MINUTES) {
+                model.calendar_block_minutes = minutes;
+            }
+        }
+        Msg::ExportCalendar => {
+            let data = &model.data;
+            let goals = data.goals_on(data.current_date).clamped();
+            effects.push(Effect::DownloadCalendar(plan_calendar(
+                data.current_date,
+                data.current_plan(),
+                model.calendar_start,
+                model.calendar_block_minutes,
+                &goals.target_bedtime,
+                model.now,
+            )));
+        }
+        Msg::ShowResetAllData => {
+            model.reset_confirmation = Some(String::new());
+        }
+        Msg::ResetConfirmationChanged(s) => {
+            model.reset_confirmation = Some(s);
+        }
+        Msg::CancelResetAllData => {
+            model.reset_confirmation = None;
+        }
+        Msg::ResetAllData => {
+            if model.reset_confirmation.as_deref() == Some(RESET_CONFIRMATION) {
+                let mut fresh = Data::new(model.today);
+                // Keep the revision counting up so that other tabs take the fresh data.
+                fresh.revision = model.data.revision;
+                fresh.onboarded = true;
+                let old = std::mem::replace(&mut model.data, fresh);
+                let fresh_json = serde_json::to_string(&model.data).unwrap_or_default();
+                model.reset_backup = Some((old, fresh_json));
+                model.reset_confirmation = None;
+                model.pomodoro = None;
+                model.editing_task = None;
+                let message = model.data.lang.tr(TextKey::DataDeleted);
+                effects.push(push_notice(model, Severity::Info, message));
+            }
+        }
+        Msg::UndoResetAllData => {
+            if let Some((old, _)) = model.reset_backup.take() {
+                model.data = old;
+                model.data.open_on(model.today);
+                let message = model.data.lang.tr(TextKey::DataRestored);
+                effects.push(push_notice(model, Severity::Info, message));
+            }
+        }
+        Msg::LeaveDemo => {
+            effects.push(Effect::LeaveDemo);
+        }
+        Msg::ArchiveCutoffChanged(s) => {
+            model.archive_cutoff = NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok();
+        }
+        Msg::ArchiveHistory => {
+            // Today's data is still being recorded, so it can't be archived.
+            if let Some(cutoff) = model.archive_cutoff.filter(|cutoff| *cutoff <= model.today) {
+                let archived = model.data.work_sleep_data.archive_before(cutoff);
+                if !archived.is_empty() {
+                    match serde_json::to_string_pretty(&archived) {
+                        Ok(json) => effects.push(Effect::DownloadArchive(json, cutoff)),
+                        Err(err) => {
+                            let message = model.data.lang.trf(TextKey::ArchiveSaveFailed, &[&err]);
+                            effects.push(push_notice(model, Severity::Error, message));
+                        }
+                    }
+                }
+                model.archive_cutoff = None;
+            }
+        }
+        Msg::WeekArchiveDaysChanged(s) => {
+            if let Ok(days) = s.trim().parse() {
+                model.week_archive_days = days;
+            }
+        }
+        Msg::ArchiveOldWeeks => {
+            let before = model.data.week_archives.len();
+            let action = Action::ArchiveOldWeeks {
+                today: model.today,
+                after_days: model.week_archive_days,
+            };
+            apply_action(model, action, &mut effects).ok();
+            let archived = model.data.week_archives.len() - before;
+            let lang = model.data.lang;
+            let message = match archived {
+                0 => lang.tr(TextKey::NoWeeksArchived).to_owned(),
+                n => lang.count(n, TextKey::ArchivedOneWeek, TextKey::ArchivedWeeks),
+            };
+            effects.push(push_notice(model, Severity::Info, message));
+        }
+        Msg::ImportFileChosen(file) => {
+            effects.push(Effect::ReadImportFile(file));
+        }
+        Msg::DataImported(data) if model.import_merge => {
+            let mut preview = model.data.clone();
+            let summary = merge_data(&mut preview, data.clone(), GoalsSource::Local);
+            model.pending_import = Some(PendingImport {
+                data,
+                goals: GoalsSource::Local,
+                summary,
+            });
+            model.import_error = None;
+        }
+        Msg::DataImported(data) => {
+            let today = model.clock.today(data.day_cutoff_hour);
+            model.today = today;
+            model.data = data;
+            model.data.open_on(today);
+            model.import_error = None;
+        }
+        Msg::ToggleImportMerge => {
+            model.import_merge ^= true;
+        }
+        Msg::ImportGoalsChosen(goals) => {
+            if let Some(pending) = &mut model.pending_import {
+                pending.goals = goals;
+            }
+        }
+        Msg::ConfirmImport => {
+            if let Some(pending) = model.pending_import.take() {
+                let summary = merge_data(&mut model.data, pending.data, pending.goals);
+                let lang = model.data.lang;
+                let message = lang.trf(TextKey::DataMerged, &[&summary.describe(lang)]);
+                effects.push(push_notice(model, Severity::Info, message));
+            }
+        }
+        Msg::CancelImport => {
+            model.pending_import = None;
+        }
+        Msg::ToggleSleepCsvOverwrite => {
+            model.sleep_csv_overwrite ^= true;
+        }
+        Msg::SleepCsvChosen(file) => {
+            effects.push(Effect::ReadSleepCsv(file));
+        }
+        Msg::SleepCsvRead(csv) => {
+            let parsed = parse_sleep_csv(&csv);
+            let (imported, skipped) = (model.data).import_bedtimes(
+                parsed.bedtimes,
+                model.sleep_csv_overwrite,
+                model.today,
+            );
+            let skipped = skipped + parsed.duplicates;
+            let message = (model.data.lang).trf(
+                TextKey::SleepCsvImported,
+                &[&imported, &skipped, &parsed.failed],
+            );
+            let severity = if parsed.failed > 0 {
+                Severity::Warning
+            } else {
+                Severity::Info
+            };
+            effects.push(push_notice(model, severity, message));
+        }
+        Msg::ImportFailed(err) => {
+            model.import_error = Some(err);
+        }
+        Msg::CopyWeekReport => {
+            let work_sleep_data = &model.data.work_sleep_data;
+            let defaults = &model.data.default_work_sleep_goals;
+            let report = week_report(
+                &work_sleep_data.get_current_week(),
+                &work_sleep_data.week_summary(defaults),
+                defaults,
+                model.data.date_format,
+                model.data.clock_format,
+                model.data.lang,
+            );
+            effects.push(Effect::CopyWeekReport(report));
+        }
+        Msg::WeekReportCopied => {
+            model.week_report = Some(WeekReportStatus::Copied);
+            effects.push(Effect::SendAfter(
+                COPIED_NOTICE_MS,
+                Msg::WeekReportDismissed,
+            ));
+        }
+        Msg::WeekReportCopyFailed(report) => {
+            model.week_report = Some(WeekReportStatus::CopyFailed(report));
+        }
+        Msg::WeekReportDismissed => {
+            model.week_report = None;
+        }
+        Msg::RemoteStateReceived(data) => {
+            if data.revision > model.data.revision {
+                take_newer_data(model, data);
+                model.last_synced = serde_json::to_string(&model.data).unwrap_or_default();
+            } else {
+                effects.push(Effect::Skip);
+            }
+        }
+        Msg::RemoteSyncUrlChanged(url) => {
+            model.remote_sync.url = url;
+            effects.push(Effect::SaveRemoteSyncSettings);
+        }
+        Msg::RemoteSyncTokenChanged(token) => {
+            model.remote_sync.token = token;
+            effects.push(Effect::SaveRemoteSyncSettings);
+        }
+        Msg::RemoteSyncEveryChanged(s) => {
+            if let Some(every) = parse_in_range(&s, 0, MAX_SYNC_EVERY) {
+                model.remote_sync.sync_every = every as u32;
+                effects.push(Effect::SaveRemoteSyncSettings);
+            }
+        }
+        Msg::SyncNow => {
+            if model.remote_sync_enabled() && !model.remote_sync_in_flight {
+                effects.push(start_remote_sync(model));
+            }
+        }
+        Msg::RemoteDataFetched(remote) => {
+            // The newest revision wins; the other copy's changes are lost.
+            match remote {
+                Some(remote) if remote.revision > model.data.revision => {
+                    take_newer_data(model, remote);
+                    // Undoing would bring back data from before the server's copy.
+                    model.undo_stack.clear();
+                    model.redo_stack.clear();
+                    finish_remote_sync(model);
+                    let message = model.data.lang.tr(TextKey::SyncedCopyNewer);
+                    effects.push(push_notice(model, Severity::Warning, message));
+                }
+                Some(remote) if remote.revision == model.data.revision => {
+                    finish_remote_sync(model);
+                }
+                _ => effects.push(Effect::PushRemoteData),
+            }
+        }
+        Msg::RemoteDataPushed => {
+            finish_remote_sync(model);
+        }
+        Msg::RemoteSyncFailed(message) => {
+            model.remote_sync_in_flight = false;
+            effects.push(push_notice(model, Severity::Error, message));
+        }
+    }
+    if let Some((_, fresh_json)) = &model.reset_backup {
+        if serde_json::to_string(&model.data).ok().as_ref() != Some(fresh_json) {
+            model.reset_backup = None;
+        }
+    }
+    effects
+}
+
+// Takes on `data`, a newer copy from another tab or the sync server. The date being viewed
+// belongs to this tab.
+fn take_newer_data(model: &mut Model, mut data: Data) {
+    let local = &mut model.data;
+    data.current_date = local.current_date;
+    data.work_sleep_data.week_start = local.work_sleep_data.week_start;
+    *local = data;
+}
+
+// Syncing first fetches the server's copy, then pushes this one if it's newer.
+fn start_remote_sync(model: &mut Model) -> Effect {
+    model.remote_sync_in_flight = true;
+    model.changes_since_remote_sync = 0;
+    Effect::PullRemoteData
+}
+
+fn finish_remote_sync(model: &mut Model) {
+    model.remote_sync_in_flight = false;
+    model.last_remote_sync = Some(model.now);
+}
+
+// Shows `message` until it's dismissed or times out; the returned effect times it out.
+fn push_notice(model: &mut Model, severity: Severity, message: impl Into<String>) -> Effect {
+    push_notice_with_action(model, severity, message, None)
+}
+
+// Like `push_notice`, with a button labelled `action.0` that sends `action.1`.
+fn push_notice_with_action(
+    model: &mut Model,
+    severity: Severity,
+    message: impl Into<String>,
+    action: Option<(String, Msg)>,
+) -> Effect {
+    let id = model.next_notice_id;
+    model.next_notice_id += 1;
+    let expires_at = model.clock.now() + Duration::milliseconds(NOTICE_TIMEOUT_MS.into());
+    model.notices.push_back(Notice {
+        id,
+        severity,
+        message: message.into(),
+        action,
+        expires_at,
+    });
+    if model.notices.len() > MAX_NOTICES {
+        model.notices.pop_front();
+    }
+    Effect::SendAfter(NOTICE_TIMEOUT_MS, Msg::NoticeExpired(id))
+}
+
+// Gives the badges just earned, with a notice for each.
+fn award_achievements(model: &mut Model) -> Vec<Effect> {
+    let earned = check_achievements(&model.data);
+    let mut effects = Vec::new();
+    for achievement in earned {
+        let lang = model.data.lang;
+        let message = lang.trf(TextKey::BadgeEarned, &[&lang.tr(achievement.badge.name())]);
+        model.data.achievements.push(achievement);
+        effects.push(push_notice(model, Severity::Info, message));
+    }
+    effects
+}
+
+// Records `bedtime` for the current date as if it were typed in, then tells the score
+// it gives.
+fn record_bedtime_now(model: &mut Model, bedtime: Bedtime) -> Vec<Effect> {
+    let input = &mut model.current_date_bedtime;
+    input.time = model.data.clock_format.format(bedtime.time);
+    input.is_next_day = bedtime.next_day;
+    let mut effects = apply(Msg::RecordBedtime, model);
+    let date = model.data.current_date;
+    if let Some(ws) = model.data.work_sleep_data.get(&date) {
+        let score =
+            model
+                .data
+                .work_sleep_data
+                .score(date, ws, &model.data.default_work_sleep_goals);
+        let message = (model.data.lang).trf(
+            TextKey::BedtimeRecorded,
+            &[&model.data.fmt_bedtime(&bedtime), &score],
+        );
+        effects.push(push_notice(model, Severity::Info, message));
+    }
+    effects
+}
+
+// Applies `action` to the data, telling about a task that's gone. Other errors are left to
+// the caller.
+fn apply_action(
+    model: &mut Model,
+    action: Action,
+    effects: &mut Vec<Effect>,
+) -> Result<(), ActionError> {
+    let result = action::apply(&mut model.data, action);
+    if result == Err(ActionError::MissingTask) {
+        effects.push(push_missing_task_notice(model));
+    }
+    result
+}
+
+// Deletes tasks from the current date's plan, offering to put them back.
+fn delete_tasks(model: &mut Model, ids: Vec<Uuid>) -> Vec<Effect> {
+    let mut effects = Vec::new();
+    let date = model.data.current_date;
+    // The deleted tasks are worked out beforehand, as the action doesn't return them.
+    let deleted = remove_tasks(&mut model.data.current_plan().clone(), &ids);
+    let action = Action::DeleteTasks(date, ids);
+    if let (Some((index, periods)), Ok(())) = (deleted, apply_action(model, action, &mut effects)) {
+        effects.push(push_deleted_notice(model, date, index, periods));
+    }
+    effects
+}
+
+// Changes the default goals with `change`, as the goal editor does one field at a time.
+fn set_default_goals(model: &mut Model, change: impl FnOnce(&mut WorkSleepGoals)) -> Vec<Effect> {
+    let mut goals = model.data.default_work_sleep_goals.clone();
+    change(&mut goals);
+    let mut effects = Vec::new();
+    apply_action(model, Action::SetDefaultGoals(goals), &mut effects).ok();
+    effects
+}
+
+// For a message about a task that was deleted or moved away, say by another tab, after
+// it was sent.
+fn push_missing_task_notice(model: &mut Model) -> Effect {
+    let message = model.data.lang.tr(TextKey::TaskNoLongerPlanned);
+    push_notice(model, Severity::Warning, message)
+}
+
+// Tells what was deleted from the plan of `date`, offering to put it back at `index`.
+fn push_deleted_notice(
+    model: &mut Model,
+    date: NaiveDate,
+    index: usize,
+    periods: Vec<Period>,
+) -> Effect {
+    let lang = model.data.lang;
+    let message = match &periods[..] {
+        [period] => lang.trf(TextKey::DeletedTask, &[&period.name]),
+        periods => lang.trf(TextKey::DeletedTasks, &[&periods.len()]),
+    };
+    let undo = Msg::RestoreTasks(date, index, periods);
+    push_notice_with_action(
+        model,
+        Severity::Info,
+        message,
+        Some((lang.tr(TextKey::Undo).to_owned(), undo)),
+    )
+}
+
+fn run_effect(effect: Effect, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    match effect {
+        Effect::Skip => {
+            orders.skip();
+        }
+        Effect::FocusNewTaskName => {
+            let name_input = model.refs.new_task_name.clone();
+            orders.after_next_render(move |_| {
+                if let Some(input) = name_input.get() {
+                    input.focus().ok();
+                }
+            });
+        }
+        Effect::FocusTaskNameEditor => {
+            let editor = model.refs.task_name_editor.clone();
+            orders.after_next_render(move |_| {
+                if let Some(input) = editor.get() {
+                    input.focus().ok();
+                    input.select();
+                }
+            });
+        }
+        Effect::StartPomodoroTicker => {
+            let ticker = orders
+                .stream_with_handle(streams::interval(POMODORO_TICK_MS, || Msg::PomodoroTick));
+            if let Some(pomodoro) = &mut model.pomodoro {
+                pomodoro.ticker = Some(ticker);
+            }
+        }
+        Effect::StartBreakTicker => {
+            let ticker =
+                orders.stream_with_handle(streams::interval(BREAK_TICK_MS, || Msg::BreakTick));
+            if let Some(work_break) = &mut model.work_break {
+                work_break._ticker = Some(ticker);
+            }
+        }
+        Effect::PatchBreakCountdown(remaining_secs) => {
+            if let Some(countdown) = model.refs.break_countdown.get() {
+                countdown.set_text_content(Some(&format_countdown(remaining_secs)));
+                orders.skip();
+            }
+        }
+        Effect::PatchCountdown(remaining_secs) => {
+            // Only the countdown text changes, so patch it in place
+            // rather than re-rendering the whole page every second.
+            if let Some(countdown) = model.refs.pomodoro_countdown.get() {
+                countdown.set_text_content(Some(&format_countdown(remaining_secs)));
+                orders.skip();
+            }
+        }
+        Effect::SendAfter(ms, msg) => {
+            orders.perform_cmd(cmds::timeout(ms, move || msg));
+        }
+        Effect::RequestNotificationPermission => match Notification::request_permission() {
+            Ok(promise) => {
+                orders.perform_cmd(async move {
+                    let permission = JsFuture::from(promise).await.ok();
+                    Msg::NotificationPermissionChanged(
+                        permission
+                            .as_ref()
+                            .and_then(NotificationPermission::from_js_value),
+                    )
+                });
+            }
+            Err(err) => log!("failed to request notification permission", err),
+        },
+        Effect::ShowBedtimeReminder(lead_minutes) => {
+            show_bedtime_reminder(lead_minutes, model.data.lang)
+        }
+        Effect::CelebrateTargetReached(sound) => {
+            if sound {
+                if let Err(err) = play_chime() {
+                    log!("failed to play the target reached chime", err);
+                }
+            }
+        }
+        Effect::ExportData => {
+            let filename = format!("slorz-{}.json", model.data.current_date);
+            let exported = serde_json::to_string_pretty(&model.data)
+                .map_err(|err| JsValue::from_str(&err.to_string()))
+                .and_then(|json| download(&json, "application/json", &filename));
+            match exported {
+                // A downloaded copy is as good as a saved one.
+                Ok(()) => model.dirty = false,
+                Err(err) => log!("failed to export data", err),
+            }
+        }
+        Effect::DownloadCalendar(ics) => {
+            let filename = format!("slorz-plan-{}.ics", model.data.current_date);
+            if let Err(err) = download(&ics, "text/calendar", &filename) {
+                log!("failed to export the calendar", err);
+            }
+        }
+        Effect::SaveRemoteSyncSettings => {
+            if let Err(err) = LocalStorage::insert(REMOTE_SYNC_KEY, &model.remote_sync) {
+                log!("failed to save the sync settings to LocalStorage", err);
+            }
+        }
+        Effect::PullRemoteData => {
+            let settings = model.remote_sync.clone();
+            let lang = model.data.lang;
+            orders.perform_cmd(async move {
+                match pull_remote_data(&settings).await {
+                    Ok(remote) => Msg::RemoteDataFetched(remote),
+                    Err(err) => Msg::RemoteSyncFailed(describe_fetch_error(err, lang)),
+                }
+            });
+        }
+        Effect::PushRemoteData => {
+            let settings = model.remote_sync.clone();
+            let data = model.data.clone();
+            let lang = data.lang;
+            orders.perform_cmd(async move {
+                match push_remote_data(&settings, &data).await {
+                    Ok(()) => Msg::RemoteDataPushed,
+                    Err(err) => Msg::RemoteSyncFailed(describe_fetch_error(err, lang)),
+                }
+            });
+        }
+        Effect::LeaveDemo => {
+            Url::new().go_and_load();
+        }
+        Effect::DownloadArchive(json, cutoff) => {
+            let filename = format!("slorz-archive-before-{}.json", cutoff);
+            if let Err(err) = download(&json, "application/json", &filename) {
+                log!("failed to download archived days", err);
+            }
+        }
+        Effect::ReadImportFile(file) => {
+            let lang = model.data.lang;
+            orders.perform_cmd(async move {
+                match JsFuture::from(file.text()).await {
+                    Ok(text) => match serde_json::from_str(&text.as_string().unwrap_or_default()) {
+                        Ok(data) => Msg::DataImported(data),
+                        Err(err) => Msg::ImportFailed(lang.trf(TextKey::InvalidDataFile, &[&err])),
+                    },
+                    Err(_) => Msg::ImportFailed(lang.tr(TextKey::FileUnreadable).to_owned()),
+                }
+            });
+        }
+        Effect::ReadSleepCsv(file) => {
+            let lang = model.data.lang;
+            orders.perform_cmd(async move {
+                match JsFuture::from(file.text()).await {
+                    Ok(text) => Msg::SleepCsvRead(text.as_string().unwrap_or_default()),
+                    Err(_) => Msg::ImportFailed(lang.tr(TextKey::FileUnreadable).to_owned()),
+                }
+            });
+        }
+        Effect::CopyWeekReport(report) => {
+            orders.perform_cmd(async move {
+                match write_to_clipboard(&report).await {
+                    Ok(()) => Msg::WeekReportCopied,
+                    Err(_) => Msg::WeekReportCopyFailed(report),
+                }
+            });
+        }
+    }
+}
+
+// Publishes `model.data` to other tabs if it changed since it was last synced, and tells
+// whether it did.
+fn sync_to_other_tabs(model: &mut Model) -> bool {
+    let json = serde_json::to_string(&model.data).unwrap_or_default();
+    if json == model.last_synced {
+        return false;
+    }
+    model.data.revision += 1;
+    model.last_synced = serde_json::to_string(&model.data).unwrap_or_default();
+    if let Some(sync) = &model.sync {
+        sync.publish(model.tab_id, &model.data);
+    }
+    true
+}
+
+fn remote_request(settings: &RemoteSyncSettings, method: Method) -> Request {
+    let request = Request::new(settings.url.trim()).method(method);
+    match settings.token.trim() {
+        "" => request,
+        token => request.header(Header::bearer(token)),
+    }
+}
+
+// The data stored on the sync server, or `None` if nothing was stored there yet.
+async fn pull_remote_data(settings: &RemoteSyncSettings) -> Result<Option<Data>, FetchError> {
+    let response = remote_request(settings, Method::Get).fetch().await?;
+    if response.status().code == 404 {
+        return Ok(None);
+    }
+    response.check_status()?.json().await.map(Some)
+}
+
+async fn push_remote_data(settings: &RemoteSyncSettings, data: &Data) -> Result<(), FetchError> {
+    remote_request(settings, Method::Put)
+        .json(data)?
+        .fetch()
+        .await?
+        .check_status()
+        .map(|_| ())
+}
+
+fn describe_fetch_error(err: FetchError, lang: Lang) -> String {
+    match err {
+        FetchError::NetworkError(_) => lang.tr(TextKey::SyncUnreachable).to_owned(),
+        FetchError::StatusError(status) => {
+            lang.trf(TextKey::SyncServerAnswered, &[&status.code, &status.text])
+        }
+        FetchError::SerdeError(err) => lang.trf(TextKey::SyncedDataUnreadable, &[&err]),
+        err => lang.trf(TextKey::SyncFailed, &[&format!("{:?}", err)]),
+    }
+}
+
+pub fn viewport_width() -> Option<u32> {
+    window()
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .map(|width| width as u32)
+}
+
+// The current notification permission, or `None` if notifications aren't supported.
+pub fn notification_permission() -> Option<NotificationPermission> {
+    let supported =
+        js_sys::Reflect::has(&window(), &JsValue::from_str("Notification")).unwrap_or(false);
+    IF!(supported => Notification::permission())
+}
+
+fn show_bedtime_reminder(lead_minutes: i64, lang: Lang) {
+    let mut options = web_sys::NotificationOptions::new();
+    options.body(&lang.trf(TextKey::BedtimeReminderBody, &[&lead_minutes]));
+    let title = lang.tr(TextKey::BedtimeReminderTitle);
+    if let Err(err) = Notification::new_with_options(title, &options) {
+        log!("failed to show bedtime reminder", err);
+    }
+}
+
+fn set_tab_title(title: &str) {
+    document().set_title(title);
+}
+
+// Toggles the `dark` class on `<html>`, which the page's stylesheet restyles.
+fn set_dark_mode(dark_mode: bool) {
+    if let Some(root) = document().document_element() {
+        if let Err(err) = root.class_list().toggle_with_force("dark", dark_mode) {
+            log!("failed to switch the theme", err);
+        }
+    }
+}
+
+// Plays two short rising tones.
+fn play_chime() -> Result<(), JsValue> {
+    let context = web_sys::AudioContext::new()?;
+    let oscillator = context.create_oscillator()?;
+    let gain = context.create_gain()?;
+    let start = context.current_time();
+    oscillator.frequency().set_value_at_time(660.0, start)?;
+    oscillator
+        .frequency()
+        .set_value_at_time(880.0, start + 0.15)?;
+    gain.gain().set_value_at_time(0.2, start)?;
+    gain.gain()
+        .exponential_ramp_to_value_at_time(0.001, start + 0.6)?;
+    oscillator.connect_with_audio_node(&gain)?;
+    gain.connect_with_audio_node(&context.destination())?;
+    oscillator.start()?;
+    oscillator.stop_with_when(start + 0.6)
+}
+
+// Writes `text` through the async Clipboard API, which `web_sys` only exposes behind
+// its unstable APIs flag, so it is looked up dynamically.
+async fn write_to_clipboard(text: &str) -> Result<(), JsValue> {
+    let clipboard = js_sys::Reflect::get(&window().navigator(), &JsValue::from_str("clipboard"))?;
+    let write_text: js_sys::Function =
+        js_sys::Reflect::get(&clipboard, &JsValue::from_str("writeText"))?.dyn_into()?;
+    let promise: js_sys::Promise = write_text
+        .call1(&clipboard, &JsValue::from_str(text))?
+        .dyn_into()?;
+    JsFuture::from(promise).await.map(|_| ())
+}
+
+// Downloads `text` of the MIME type as `filename` through a temporary object URL.
+fn download(text: &str, mime_type: &str, filename: &str) -> Result<(), JsValue> {
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(
+        &js_sys::Array::of1(&JsValue::from_str(text)),
+        web_sys::BlobPropertyBag::new().type_(mime_type),
+    )?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let anchor: web_sys::HtmlAnchorElement = document().create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Weekday;
+
+    use crate::model::{shift_days, Clock};
+
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn model() -> Model {
+        Model::on(ymd(2021, 5, 5))
+    }
+
+    fn apply_all(model: &mut Model, msgs: Vec<Msg>) -> Vec<Effect> {
+        msgs.into_iter().flat_map(|msg| apply(msg, model)).collect()
+    }
+
+    fn add_task(model: &mut Model, name: &str, quantity: &str) -> Vec<Effect> {
+        apply_all(
+            model,
+            vec![
+                Msg::NewTaskNameChanged(name.to_owned()),
+                Msg::NewTaskQuantityChanged(quantity.to_owned()),
+                Msg::AddNewTask,
+            ],
+        )
+    }
+
+    fn plan_names(model: &Model) -> Vec<&str> {
+        model
+            .data
+            .current_plan()
+            .iter()
+            .map(|wp| wp.name.as_str())
+            .collect()
+    }
+
+    fn plan_ids(model: &Model) -> Vec<Uuid> {
+        model.data.current_plan().iter().map(|wp| wp.id).collect()
+    }
+
+    #[test]
+    fn add_new_task_adds_the_parsed_quantity() {
+        let mut model = model();
+        let effects = add_task(&mut model, "read", " 3 ");
+        assert_eq!(plan_names(&model), vec!["read", "read", "read"]);
+        assert!(model.new_task.name.is_empty());
+        assert!(matches!(effects[..], [Effect::FocusNewTaskName]));
+        add_task(&mut model, "skim", "250");
+        assert_eq!(
+            model.data.current_plan().len() as i64,
+            3 + MAX_TASK_QUANTITY
+        );
+    }
+
+    #[test]
+    fn new_task_names_are_normalized_before_counting() {
+        let mut model = model();
+        add_task(&mut model, "  read  paper ", "1");
+        add_task(&mut model, "read paper", "1");
+        add_task(&mut model, "   ", "1");
+        assert_eq!(plan_names(&model), vec!["read paper", "read paper"]);
+        assert_eq!(model.data.task_frequency["read paper"], 2);
+        assert_eq!(model.data.task_frequency.len(), 1);
+    }
+
+    #[test]
+    fn resetting_the_new_task_form_empties_every_field() {
+        let mut model = model();
+        apply_all(
+            &mut model,
+            vec![
+                Msg::NewTaskNameChanged("read".to_owned()),
+                Msg::NewTaskCategoryChanged("study".to_owned()),
+                Msg::NewTaskQuantityChanged("abc".to_owned()),
+            ],
+        );
+        assert!(model.new_task.quantity_parsed.is_err());
+        apply(Msg::ResetNewTaskForm, &mut model);
+        assert!(model.new_task.name.is_empty());
+        assert!(model.new_task.category.is_empty());
+        assert_eq!(model.new_task.quantity, "1");
+        assert_eq!(model.new_task.quantity_parsed, Ok(1));
+        assert!(plan_names(&model).is_empty());
+    }
+
+    #[test]
+    fn postponing_a_task_is_undone_on_both_dates() {
+        let mut model = model();
+        add_task(&mut model, "write", "1");
+        add_task(&mut model, "read", "1");
+        let id = plan_ids(&model)[0];
+        apply(Msg::PostponeTask(id), &mut model);
+        assert_eq!(plan_names(&model), vec!["read"]);
+
+        apply(Msg::Undo, &mut model);
+        assert_eq!(plan_names(&model), vec!["write", "read"]);
+        assert_eq!(model.data.current_plan()[0].postponed_from, None);
+        assert!(model.data.plan(ymd(2021, 5, 6)).is_empty());
+    }
+
+    fn visit(model: &mut Model, path: &[&str], hash: Option<&str>) {
+        let url = Url::new().set_path(path);
+        let url = match hash {
+            Some(hash) => url.set_hash(hash),
+            None => url,
+        };
+        apply(Msg::UrlChanged(subs::UrlChanged(url)), model);
+    }
+
+    #[test]
+    fn urls_select_pages() {
+        let mut model = model();
+        visit(&mut model, &["settings"], None);
+        assert_eq!(model.page, Page::Settings);
+        visit(&mut model, &["history"], None);
+        assert_eq!(model.page, Page::History);
+        visit(&mut model, &[], None);
+        assert_eq!(model.page, Page::Tracking);
+        visit(&mut model, &["history", "2021-05-01"], None);
+        assert_eq!(model.page, Page::NotFound);
+        visit(&mut model, &["nowhere"], None);
+        assert_eq!(model.page, Page::NotFound);
+    }
+
+    #[test]
+    fn history_deep_links_select_the_week() {
+        let mut model = model();
+        let week_start = model.data.work_sleep_data.week_start;
+        visit(&mut model, &["history"], Some("not-a-date"));
+        visit(&mut model, &[], Some("2021-03-04"));
+        assert_eq!(model.data.work_sleep_data.week_start, week_start);
+
+        visit(&mut model, &["history"], Some("2021-03-04"));
+        assert_eq!(model.page, Page::History);
+        assert_eq!(model.data.work_sleep_data.week_start, ymd(2021, 2, 26));
+    }
+
+    #[test]
+    fn archiving_history_downloads_the_archived_days() {
+        let mut model = model();
+        for day in 1..=5 {
+            model
+                .data
+                .work_sleep_data
+                .get_mut_or_create(&ymd(2021, 5, day));
+        }
+        apply(
+            Msg::ArchiveCutoffChanged("2021-05-06".to_owned()),
+            &mut model,
+        );
+        assert!(apply(Msg::ArchiveHistory, &mut model).is_empty());
+        assert_eq!(model.data.work_sleep_data.days().len(), 5);
+
+        apply(
+            Msg::ArchiveCutoffChanged("2021-05-03".to_owned()),
+            &mut model,
+        );
+        let effects = apply(Msg::ArchiveHistory, &mut model);
+        match &effects[..] {
+            [Effect::DownloadArchive(json, cutoff)] => {
+                assert_eq!(*cutoff, ymd(2021, 5, 3));
+                let archived: Vec<(NaiveDate, crate::model::WorkSleep)> =
+                    serde_json::from_str(json).unwrap();
+                assert_eq!(archived.len(), 2);
+            }
+            _ => panic!("expected the archived days to be downloaded"),
+        }
+        assert_eq!(model.data.work_sleep_data.days().len(), 3);
+        assert_eq!(model.archive_cutoff, None);
+    }
+
+    #[test]
+    fn archiving_old_weeks_is_not_undoable() {
+        let mut model = model();
+        for day in [ymd(2021, 3, 1), ymd(2021, 5, 1)] {
+            model.data.day_mut(day).actual_work_count = 2;
+        }
+        apply_all(
+            &mut model,
+            vec![
+                Msg::WeekArchiveDaysChanged("20".to_owned()),
+                Msg::ArchiveOldWeeks,
+            ],
+        );
+        assert_eq!(model.data.week_archives.len(), 1);
+        assert_eq!(model.notices[0].message, "Archived 1 week");
+        assert!(model.undo_stack.is_empty());
+    }
+
+    #[test]
+    fn one_day_has_its_details_open_at_a_time() {
+        let mut model = model();
+        let yesterday = ymd(2021, 5, 4);
+        apply(Msg::ToggleDayDetails(yesterday), &mut model);
+        apply(Msg::ToggleDayDetails(model.today), &mut model);
+        assert_eq!(model.expanded_day, Some(model.today));
+        apply(Msg::ToggleDayDetails(model.today), &mut model);
+        assert_eq!(model.expanded_day, None);
+    }
+
+    #[test]
+    fn a_past_day_can_be_created_and_given_a_bedtime() {
+        let mut model = model();
+        let yesterday = ymd(2021, 5, 4);
+        apply_all(
+            &mut model,
+            vec![
+                Msg::CreateDayEntry(yesterday),
+                Msg::CreateDayEntry(ymd(2021, 5, 6)),
+                Msg::SetDayBedtime(yesterday, "12:30 AM".to_owned()),
+            ],
+        );
+        let days = model.data.work_sleep_data.days();
+        assert_eq!(days.len(), 1);
+        assert_eq!(
+            days[&yesterday].actual_bedtime,
+            Some(Bedtime {
+                time: NaiveTime::from_hms_opt(0, 30, 0).unwrap(),
+                next_day: true,
+            })
+        );
+
+        apply(Msg::SetDayBedtime(yesterday, "late".to_owned()), &mut model);
+        assert_eq!(model.notices.len(), 1);
+        apply(Msg::SetDayBedtime(yesterday, " ".to_owned()), &mut model);
+        assert_eq!(
+            model.data.work_sleep_data.days()[&yesterday].actual_bedtime,
+            None
+        );
+        apply(Msg::Undo, &mut model);
+        assert!(model.data.work_sleep_data.days()[&yesterday]
+            .actual_bedtime
+            .is_some());
+    }
+
+    #[test]
+    fn switching_to_calendar_weeks_aligns_the_past_week_shown() {
+        let mut model = model();
+        apply_all(
+            &mut model,
+            vec![Msg::ViewPreviousWeek, Msg::ViewPreviousWeek],
+        );
+        // Rolling weeks end on 2021-05-05, so this one runs from Thursday 04-15.
+        assert_eq!(model.data.work_sleep_data.week_start, ymd(2021, 4, 15));
+
+        apply(
+            Msg::WeekModeChanged(WeekMode::Calendar(Weekday::Mon)),
+            &mut model,
+        );
+        assert_eq!(model.data.work_sleep_data.week_start, ymd(2021, 4, 12));
+        apply(Msg::ViewNextWeek, &mut model);
+        assert_eq!(model.data.work_sleep_data.week_start, ymd(2021, 4, 19));
+
+        apply(Msg::WeekModeChanged(WeekMode::Rolling), &mut model);
+        assert_eq!(model.data.work_sleep_data.week_start, ymd(2021, 4, 19));
+        apply(Msg::ViewCurrentWeek, &mut model);
+        assert_eq!(model.data.work_sleep_data.week_start, ymd(2021, 4, 29));
+    }
+
+    #[test]
+    fn nothing_is_recorded_on_future_dates() {
+        let mut model = model();
+        let record = vec![
+            Msg::BedtimeInputChanged("23:00".to_owned()),
+            Msg::RecordBedtime,
+            Msg::FinishedTopTask,
+        ];
+        apply(Msg::SetCurrentDate(ymd(2021, 5, 6)), &mut model);
+        add_task(&mut model, "plan", "1");
+        apply_all(&mut model, record.clone());
+        assert_eq!(plan_names(&model), vec!["plan"]);
+        assert!(model.data.work_sleep_data.days().is_empty());
+        assert_eq!(model.notices.len(), 2);
+
+        apply(Msg::SetCurrentDate(ymd(2021, 5, 5)), &mut model);
+        add_task(&mut model, "today", "1");
+        apply_all(&mut model, record);
+        let ws = &model.data.work_sleep_data.days()[&ymd(2021, 5, 5)];
+        assert_eq!(ws.actual_work_count, 1);
+        assert!(ws.actual_bedtime.is_some());
+    }
+
+    #[test]
+    fn resetting_all_data_needs_the_typed_confirmation() {
+        let mut model = model();
+        add_task(&mut model, "read", "2");
+        apply_all(
+            &mut model,
+            vec![
+                Msg::ShowResetAllData,
+                Msg::ResetConfirmationChanged("delete".to_owned()),
+                Msg::ResetAllData,
+            ],
+        );
+        assert_eq!(plan_names(&model), vec!["read", "read"]);
+
+        apply_all(
+            &mut model,
+            vec![
+                Msg::ResetConfirmationChanged("DELETE".to_owned()),
+                Msg::ResetAllData,
+            ],
+        );
+        assert!(plan_names(&model).is_empty());
+        assert!(model.undo_stack.is_empty());
+        assert_eq!(model.reset_confirmation, None);
+        assert!(model.data.onboarded);
+
+        apply(Msg::ToggleShortcutHelp, &mut model);
+        apply(Msg::UndoResetAllData, &mut model);
+        assert_eq!(plan_names(&model), vec!["read", "read"]);
+    }
+
+    #[test]
+    fn undoing_a_reset_is_only_offered_until_the_data_changes() {
+        let mut model = model();
+        add_task(&mut model, "read", "1");
+        apply_all(
+            &mut model,
+            vec![
+                Msg::ShowResetAllData,
+                Msg::ResetConfirmationChanged("DELETE".to_owned()),
+                Msg::ResetAllData,
+            ],
+        );
+        assert!(model.reset_backup.is_some());
+        add_task(&mut model, "write", "1");
+        assert!(model.reset_backup.is_none());
+        apply(Msg::UndoResetAllData, &mut model);
+        assert_eq!(plan_names(&model), vec!["write"]);
+    }
+
+    #[test]
+    fn reaching_the_work_target_is_celebrated_once_per_date() {
+        let mut model = model();
+        model.data.default_work_sleep_goals.target_work_count = 2;
+        add_task(&mut model, "read", "3");
+        assert!(apply(Msg::FinishedTopTask, &mut model).is_empty());
+        assert!(matches!(
+            apply(Msg::FinishedTopTask, &mut model)[..],
+            [
+                Effect::CelebrateTargetReached(true),
+                Effect::SendAfter(_, Msg::TargetReachedFlashEnded)
+            ]
+        ));
+        assert!(model.target_reached);
+        apply(Msg::TargetReachedFlashEnded, &mut model);
+        assert!(!model.target_reached);
+
+        apply(Msg::Undo, &mut model);
+        assert!(apply(Msg::FinishedTopTask, &mut model).is_empty());
+        assert!(apply(Msg::FinishedTopTask, &mut model).is_empty());
+        assert!(!model.target_reached);
+    }
+
+    #[test]
+    fn finishing_a_task_locks_the_next_one_for_a_break() {
+        let mut model = model();
+        model.data.break_minutes = 1;
+        apply_all(
+            &mut model,
+            vec![
+                Msg::QuickAddTask("write".to_owned()),
+                Msg::QuickAddTask("read".to_owned()),
+                Msg::FinishedTopTask,
+            ],
+        );
+        assert!(model.work_break.is_none());
+
+        apply(Msg::ToggleBreaks, &mut model);
+        let effects = apply(Msg::FinishedTopTask, &mut model);
+        assert!(matches!(effects[..], [Effect::StartBreakTicker]));
+        assert_eq!(model.work_break.as_ref().unwrap().remaining_secs, 60);
+        apply(Msg::QuickAddTask("rest".to_owned()), &mut model);
+        apply(Msg::FinishedTopTask, &mut model);
+        assert_eq!(model.data.current_plan().len(), 1);
+        assert_eq!(model.notices.back().unwrap().severity, Severity::Warning);
+
+        let now = model.clock.now().date().and_hms_opt(23, 0, 0).unwrap();
+        apply(Msg::RecordBedtimeAt(now), &mut model);
+        assert!(model.data.work_sleep_data.days()[&ymd(2021, 5, 5)]
+            .actual_bedtime
+            .is_some());
+
+        for _ in 0..59 {
+            apply(Msg::BreakTick, &mut model);
+        }
+        assert_eq!(model.work_break.as_ref().unwrap().remaining_secs, 1);
+        apply(Msg::BreakTick, &mut model);
+        assert!(model.work_break.is_none());
+        assert_eq!(model.notices.back().unwrap().message, "Break's over");
+
+        apply_all(&mut model, vec![Msg::FinishedTopTask, Msg::SkipBreak]);
+        assert!(model.data.current_plan().is_empty());
+        assert!(model.work_break.is_none());
+    }
+
+    #[test]
+    fn merge_imports_are_previewed_before_changing_anything() {
+        let mut model = model();
+        model.data.day_mut(ymd(2021, 5, 4)).actual_work_count = 2;
+        let mut imported = Data::new(ymd(2021, 5, 5));
+        imported.day_mut(ymd(2021, 5, 3)).actual_work_count = 1;
+        imported.day_mut(ymd(2021, 5, 4)).actual_work_count = 3;
+        imported.default_work_sleep_goals.target_work_count = 9;
+
+        apply(Msg::ToggleImportMerge, &mut model);
+        apply(Msg::DataImported(imported.clone()), &mut model);
+        assert_eq!(
+            model
+                .pending_import
+                .as_ref()
+                .unwrap()
+                .summary
+                .describe(Lang::English),
+            "1 days added, 1 days merged, 1 conflicts resolved toward import, 0 tasks added"
+        );
+        assert_eq!(model.data.work_sleep_data.days().len(), 1);
+        apply(Msg::CancelImport, &mut model);
+        assert!(model.pending_import.is_none());
+
+        apply_all(
+            &mut model,
+            vec![
+                Msg::DataImported(imported),
+                Msg::ImportGoalsChosen(GoalsSource::Imported),
+                Msg::ConfirmImport,
+            ],
+        );
+        assert!(model.pending_import.is_none());
+        let days = model.data.work_sleep_data.days();
+        assert_eq!(days[&ymd(2021, 5, 3)].actual_work_count, 1);
+        assert_eq!(days[&ymd(2021, 5, 4)].actual_work_count, 3);
+        assert_eq!(model.data.default_work_sleep_goals.target_work_count, 9);
+        assert!(model
+            .notices
+            .back()
+            .unwrap()
+            .message
+            .starts_with("Merged the imported data: 1 days added"));
+    }
+
+    #[test]
+    fn sleep_csv_keeps_recorded_bedtimes_unless_told_to_overwrite() {
+        let mut model = model();
+        let recorded = Bedtime {
+            time: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            next_day: false,
+        };
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&ymd(2021, 5, 3))
+            .actual_bedtime = Some(recorded.clone());
+        let csv = "date,start,end\n\
+                   2021-05-03,23:30,07:00\n\
+                   2021-05-04,2021-05-05 00:15,2021-05-05 07:00\n\
+                   2021-05-07,23:00,07:00\n\
+                   oops\n";
+        apply(Msg::SleepCsvRead(csv.to_owned()), &mut model);
+        let days = model.data.work_sleep_data.days();
+        assert_eq!(days[&ymd(2021, 5, 3)].actual_bedtime, Some(recorded));
+        assert!(
+            days[&ymd(2021, 5, 4)]
+                .actual_bedtime
+                .as_ref()
+                .unwrap()
+                .next_day
+        );
+        assert!(!days.contains_key(&ymd(2021, 5, 7)));
+        assert_eq!(
+            model.notices.back().unwrap().message,
+            "Imported 1 bedtimes, skipped 2, couldn't read 1 rows"
+        );
+
+        apply(Msg::ToggleSleepCsvOverwrite, &mut model);
+        apply(Msg::SleepCsvRead(csv.to_owned()), &mut model);
+        let bedtime = model.data.work_sleep_data.days()[&ymd(2021, 5, 3)]
+            .actual_bedtime
+            .clone();
+        assert_eq!(
+            bedtime.unwrap().time,
+            NaiveTime::from_hms_opt(23, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn wake_time_ends_the_night_before() {
+        let mut model = model();
+        let yesterday = ymd(2021, 5, 4);
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&yesterday)
+            .actual_bedtime = Some(Bedtime {
+            time: NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+            next_day: false,
+        });
+        apply_all(
+            &mut model,
+            vec![
+                Msg::WakeTimeInputChanged("22:00".to_owned()),
+                Msg::RecordWakeTime,
+            ],
+        );
+        assert!(model.wake_time_input.error.is_some());
+        assert_eq!(
+            model.data.work_sleep_data.days()[&yesterday].wake_time,
+            None
+        );
+
+        apply_all(
+            &mut model,
+            vec![
+                Msg::WakeTimeInputChanged("07:00".to_owned()),
+                Msg::RecordWakeTime,
+            ],
+        );
+        assert_eq!(model.wake_time_input.error, None);
+        assert_eq!(
+            model.data.work_sleep_data.days()[&yesterday].sleep_minutes(),
+            Some(450)
+        );
+    }
+
+    #[test]
+    fn bulk_add_adds_every_line_and_undoes_at_once() {
+        let mut model = model();
+        add_task(&mut model, "plan", "1");
+        apply_all(
+            &mut model,
+            vec![
+                Msg::ToggleBulkAdd,
+                Msg::BulkAddTextChanged("flashcards x2\n\nread\n".to_owned()),
+                Msg::AddBulkTasks,
+            ],
+        );
+        assert_eq!(
+            plan_names(&model),
+            vec!["plan", "flashcards", "flashcards", "read"]
+        );
+        assert_eq!(model.bulk_add, None);
+        assert_eq!(model.data.task_frequency.get("flashcards"), Some(&1));
+
+        apply(Msg::Undo, &mut model);
+        assert_eq!(plan_names(&model), vec!["plan"]);
+    }
+
+    #[test]
+    fn onboarding_sets_the_default_goals_once() {
+        let clock = Clock::at_noon(ymd(2021, 5, 5));
+        let mut model = Model::new(Data::new(ymd(2021, 5, 5)), Uuid::new_v4(), None, clock);
+        let mut goals = model.onboarding.as_ref().unwrap().goals.clone();
+        goals.target_work_count = 4;
+        goals.target_bedtime.next_day = true;
+        apply_all(
+            &mut model,
+            vec![
+                Msg::OnboardingGoalsChanged(goals),
+                Msg::OnboardingStepChanged(OnboardingStep::WorkSleepBalance),
+            ],
+        );
+        let onboarding = model.onboarding.as_ref().unwrap();
+        assert_eq!(onboarding.step, OnboardingStep::WorkSleepBalance);
+        apply(
+            Msg::CompleteOnboarding(onboarding.goals.clone()),
+            &mut model,
+        );
+        assert!(model.onboarding.is_none());
+        assert!(model.data.onboarded);
+        assert_eq!(model.data.default_work_sleep_goals.target_work_count, 4);
+        assert!(model.data.default_work_sleep_goals.target_bedtime.next_day);
+
+        let saved = serde_json::to_string(&model.data).unwrap();
+        let data = serde_json::from_str(&saved).unwrap();
+        let reloaded = Model::new(data, Uuid::new_v4(), None, clock);
+        assert!(reloaded.onboarding.is_none());
+    }
+
+    #[test]
+    fn task_notes_are_trimmed_and_cleared_from_the_form() {
+        let mut model = model();
+        apply(
+            Msg::NewTaskNoteChanged(" pages 1-20 \n".to_owned()),
+            &mut model,
+        );
+        add_task(&mut model, "read", "1");
+        add_task(&mut model, "write", "1");
+        assert_eq!(model.new_task.note, "");
+        let notes: Vec<&str> = (model.data.current_plan().iter())
+            .map(|wp| wp.note.as_str())
+            .collect();
+        assert_eq!(notes, vec!["pages 1-20", ""]);
+    }
+
+    #[test]
+    fn add_new_task_ignores_invalid_quantities_and_empty_names() {
+        let mut model = model();
+        for quantity in ["0", "-1", "abc", ""].iter() {
+            let effects = add_task(&mut model, "read", quantity);
+            assert!(matches!(
+                effects[..],
+                [Effect::SendAfter(_, Msg::NoticeExpired(_))]
+            ));
+        }
+        assert_eq!(model.notices.len(), 4);
+        assert!(model
+            .notices
+            .iter()
+            .all(|notice| notice.severity == Severity::Error));
+        assert!(add_task(&mut model, "", "1").is_empty());
+        assert!(plan_names(&model).is_empty());
+        assert!(model.undo_stack.is_empty());
+    }
+
+    #[test]
+    fn notices_are_capped_and_dismissed_by_id() {
+        let mut model = model();
+        let missing = Uuid::new_v4();
+        for _ in 0..MAX_NOTICES + 2 {
+            apply(Msg::MoveTaskToTop(missing), &mut model);
+        }
+        let ids: Vec<usize> = model.notices.iter().map(|notice| notice.id).collect();
+        assert_eq!(ids, (2..MAX_NOTICES + 2).collect::<Vec<_>>());
+
+        apply(Msg::DismissNotice(3), &mut model);
+        assert!(model.notices.iter().all(|notice| notice.id != 3));
+        apply(Msg::NoticeExpired(4), &mut model);
+        assert_eq!(model.notices.len(), MAX_NOTICES - 2);
+    }
+
+    #[test]
+    fn capped_quantities_are_noticed() {
+        let mut model = model();
+        add_task(&mut model, "read", "250");
+        assert_eq!(model.notices.len(), 1);
+        assert_eq!(model.notices[0].severity, Severity::Info);
+    }
+
+    // Every message that names a planned task by its id.
+    fn task_messages(id: Uuid) -> Vec<Msg> {
+        vec![
+            Msg::DeleteTask(id),
+            Msg::PostponeTask(id),
+            Msg::RequestDeleteTask(id),
+            Msg::StartEditTask(id),
+            Msg::MoveTaskToTop(id),
+            Msg::MoveTaskUp(id),
+            Msg::MoveTaskDown(id),
+            Msg::MoveTaskToBottom(id),
+            Msg::DeleteTasks(vec![id]),
+            Msg::MoveTasksToTop(vec![id]),
+            Msg::MoveTasksUp(vec![id]),
+            Msg::MoveTasksDown(vec![id]),
+            Msg::MoveTasksToBottom(vec![id]),
+            Msg::SetTaskPriority(id, Priority::High),
+            Msg::SetTasksPriority(vec![id], Priority::High),
+            Msg::StartTask(id),
+            Msg::StartPomodoro(id),
+            Msg::TaskNoteChanged(id, "note".to_owned()),
+        ]
+    }
+
+    // Each message about the missing `id` leaves the plan alone and says so.
+    fn assert_missing_task_is_noticed(model: &mut Model, id: Uuid) {
+        let plan = model.data.current_plan().clone();
+        model.editing_task = Some((id, "renamed".to_owned()));
+        for msg in task_messages(id)
+            .into_iter()
+            .chain(vec![Msg::CommitEditTask])
+        {
+            model.notices.clear();
+            apply(msg, model);
+            assert_eq!(model.data.current_plan(), &plan);
+            assert_eq!(model.notices.len(), 1);
+            assert_eq!(model.notices[0].severity, Severity::Warning);
+        }
+        assert!(model.pending_delete.is_none());
+        assert!(model.pomodoro.is_none());
+        assert!(model.editing_task.is_none());
+    }
+
+    #[test]
+    fn task_messages_on_an_empty_queue_are_noticed() {
+        let mut model = model();
+        assert_missing_task_is_noticed(&mut model, Uuid::new_v4());
+        assert!(model.data.work_sleep_data.days().is_empty());
+    }
+
+    #[test]
+    fn task_messages_after_the_task_was_deleted_are_noticed() {
+        let mut model = model();
+        add_task(&mut model, "read", "1");
+        add_task(&mut model, "write", "1");
+        add_task(&mut model, "rest", "1");
+        let id = plan_ids(&model)[1];
+        apply(Msg::DeleteTask(id), &mut model);
+        apply(Msg::StartTask(plan_ids(&model)[0]), &mut model);
+        assert_missing_task_is_noticed(&mut model, id);
+        assert_eq!(plan_names(&model), vec!["read", "rest"]);
+        assert!(model.data.current_plan()[0].started_at.is_some());
+    }
+
+    #[test]
+    fn finishing_a_task_is_announced() {
+        let mut model = model();
+        add_task(&mut model, "write", "1");
+        add_task(&mut model, "read", "1");
+        apply(Msg::FinishedTopTask, &mut model);
+        assert_eq!(
+            model.announcement,
+            "Finished write, 1 of 6 done. Next: read"
+        );
+        apply(Msg::FinishedTopTask, &mut model);
+        assert_eq!(
+            model.announcement,
+            "Finished read, 2 of 6 done. No tasks left"
+        );
+    }
+
+    #[test]
+    fn undone_changes_can_be_redone_until_something_else_changes() {
+        let mut model = model();
+        add_task(&mut model, "write", "1");
+        apply_all(
+            &mut model,
+            vec![Msg::FinishedTopTask, Msg::Undo, Msg::Undo, Msg::Redo],
+        );
+        assert_eq!(plan_names(&model), vec!["write"]);
+        apply(Msg::Redo, &mut model);
+        assert!(plan_names(&model).is_empty());
+        assert_eq!(
+            model.data.work_sleep_data.days()[&ymd(2021, 5, 5)].actual_work_count,
+            1
+        );
+
+        apply_all(&mut model, vec![Msg::Undo, Msg::Undo]);
+        assert_eq!(
+            model.data.work_sleep_data.days()[&ymd(2021, 5, 5)].actual_work_count,
+            0
+        );
+        assert!(!model.redo_stack.is_empty());
+        add_task(&mut model, "read", "1");
+        assert!(model.redo_stack.is_empty());
+        apply(Msg::Redo, &mut model);
+        assert_eq!(plan_names(&model), vec!["read"]);
+    }
+
+    #[test]
+    fn the_system_theme_follows_the_browser() {
+        let mut model = model();
+        assert_eq!(model.data.theme, Theme::System);
+        apply(Msg::PrefersDarkChanged(true), &mut model);
+        assert!(model.data.theme.is_dark(model.prefers_dark));
+        apply(Msg::SetTheme(Theme::Light), &mut model);
+        assert!(!model.data.theme.is_dark(model.prefers_dark));
+        apply(Msg::SetTheme(Theme::Dark), &mut model);
+        apply(Msg::PrefersDarkChanged(false), &mut model);
+        assert!(model.data.theme.is_dark(model.prefers_dark));
+    }
+
+    #[test]
+    fn dragging_a_slider_is_undone_in_one_step() {
+        let mut model = model();
+        let balance = model.data.default_work_sleep_goals.work_sleep_balance;
+        for value in ["40", "45", "50"].iter() {
+            apply(Msg::GoalBalanceChanged((*value).to_owned()), &mut model);
+        }
+        assert_eq!(model.undo_stack.len(), 1);
+        apply(Msg::Undo, &mut model);
+        assert_eq!(
+            model.data.default_work_sleep_goals.work_sleep_balance,
+            balance
+        );
+    }
+
+    #[test]
+    fn changes_to_what_is_shown_are_not_undoable() {
+        let mut model = model();
+        apply_all(
+            &mut model,
+            vec![
+                Msg::NewTaskNameChanged("write".to_owned()),
+                Msg::ToggleShortcutHelp,
+                Msg::ViewPreviousWeek,
+            ],
+        );
+        assert!(model.undo_stack.is_empty());
+    }
+
+    #[test]
+    fn week_navigation_stops_at_the_current_week() {
+        let mut model = model();
+        let current_start = model.data.work_sleep_data.week_start;
+        apply(Msg::ViewNextWeek, &mut model);
+        assert_eq!(model.data.work_sleep_data.week_start, current_start);
+        apply_all(
+            &mut model,
+            vec![Msg::ViewPreviousWeek, Msg::ViewPreviousWeek],
+        );
+        assert_eq!(
+            model.data.work_sleep_data.week_start,
+            shift_days(current_start, -14)
+        );
+        apply(Msg::ViewNextWeek, &mut model);
+        assert_eq!(
+            model.data.work_sleep_data.week_start,
+            shift_days(current_start, -7)
+        );
+        apply(Msg::ViewCurrentWeek, &mut model);
+        assert_eq!(model.data.work_sleep_data.week_start, current_start);
+    }
+
+    #[test]
+    fn a_pending_delete_is_dropped_by_other_actions() {
+        let mut model = model();
+        add_task(&mut model, "write", "1");
+        let id = plan_ids(&model)[0];
+        let effects = apply(Msg::RequestDeleteTask(id), &mut model);
+        assert!(matches!(
+            effects[..],
+            [Effect::SendAfter(_, Msg::PendingDeleteTimedOut(_))]
+        ));
+        assert!(matches!(
+            apply(Msg::PendingDeleteTimedOut(Uuid::new_v4()), &mut model)[..],
+            [Effect::Skip]
+        ));
+        assert_eq!(model.pending_delete, Some(id));
+        apply(Msg::ToggleShortcutHelp, &mut model);
+        assert_eq!(model.pending_delete, None);
+    }
+
+    #[test]
+    fn only_user_actions_are_foreground_messages() {
+        assert!(Msg::CheckDateRollover.is_background());
+        assert!(Msg::PendingDeleteTimedOut(Uuid::new_v4()).is_background());
+        assert!(!Msg::AddNewTask.is_background());
+        assert!(!Msg::CancelDeleteTask.is_background());
+    }
+
+    #[test]
+    fn remote_sync_is_inert_without_a_url() {
+        let mut model = model();
+        model.remote_sync.sync_every = 1;
+        model.changes_since_remote_sync = 5;
+        assert!(apply(Msg::SyncNow, &mut model).is_empty());
+        assert!(!model.remote_sync_in_flight);
+        assert!(!model.remote_sync_due());
+        apply(
+            Msg::RemoteSyncUrlChanged("https://example.com/slorz".to_owned()),
+            &mut model,
+        );
+        assert!(model.remote_sync_due());
+        model.demo = true;
+        assert!(!model.remote_sync_due());
+    }
+
+    #[test]
+    fn syncing_pushes_unless_the_remote_copy_is_newer() {
+        let mut model = model();
+        model.remote_sync.url = "https://example.com/slorz".to_owned();
+        model.data.revision = 7;
+        let effects = apply(Msg::SyncNow, &mut model);
+        assert!(matches!(effects[..], [Effect::PullRemoteData]));
+        assert!(apply(Msg::SyncNow, &mut model).is_empty());
+
+        let mut older = Data::new(model.today);
+        older.revision = 6;
+        let effects = apply(Msg::RemoteDataFetched(Some(older)), &mut model);
+        assert!(matches!(effects[..], [Effect::PushRemoteData]));
+        let effects = apply(Msg::RemoteDataFetched(None), &mut model);
+        assert!(matches!(effects[..], [Effect::PushRemoteData]));
+        apply(Msg::RemoteDataPushed, &mut model);
+        assert!(!model.remote_sync_in_flight);
+        assert_eq!(model.last_remote_sync, Some(model.now));
+    }
+
+    #[test]
+    fn a_newer_remote_copy_replaces_the_data_with_a_warning() {
+        let mut model = model();
+        model.remote_sync.url = "https://example.com/slorz".to_owned();
+        add_task(&mut model, "write", "1");
+        apply(Msg::SyncNow, &mut model);
+        let mut newer = Data::new(model.today);
+        newer.revision = model.data.revision + 1;
+        newer
+            .plan_mut(model.today)
+            .push_back(Period::new("read".to_owned()));
+        apply(Msg::RemoteDataFetched(Some(newer)), &mut model);
+        assert_eq!(plan_names(&model), vec!["read"]);
+        assert!(model.undo_stack.is_empty());
+        assert!(!model.remote_sync_in_flight);
+        assert_eq!(model.notices.back().unwrap().severity, Severity::Warning);
+    }
+
+    #[test]
+    fn failed_syncs_are_shown_as_errors() {
+        let mut model = model();
+        model.remote_sync.url = "https://example.com/slorz".to_owned();
+        apply(Msg::SyncNow, &mut model);
+        apply(
+            Msg::RemoteSyncFailed("Couldn't reach the sync server".to_owned()),
+            &mut model,
+        );
+        assert!(!model.remote_sync_in_flight);
+        assert_eq!(model.notices.back().unwrap().severity, Severity::Error);
+        assert!(model.last_remote_sync.is_none());
+    }
+
+    #[test]
+    fn new_tasks_take_the_priority_picked() {
+        let mut model = model();
+        add_task(&mut model, "filler", "1");
+        apply(Msg::NewTaskPriorityChanged(Priority::High), &mut model);
+        add_task(&mut model, "urgent", "1");
+        add_task(&mut model, "reply", "1");
+        assert_eq!(model.data.current_plan()[0].priority, Priority::Normal);
+        assert_eq!(model.data.current_plan()[1].priority, Priority::High);
+        assert_eq!(model.data.current_plan()[2].priority, Priority::Normal);
+    }
+
+    #[test]
+    fn the_bedtime_countdown_hides_once_a_bedtime_is_recorded() {
+        let mut model = model();
+        model.now = model.today.and_hms_opt(21, 0, 0).unwrap();
+        assert!(model.bedtime_countdown().is_some());
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&model.today)
+            .actual_bedtime = Some(Bedtime {
+            time: NaiveTime::from_hms_opt(22, 30, 0).unwrap(),
+            next_day: false,
+        });
+        assert_eq!(model.bedtime_countdown(), None);
+    }
+
+    #[test]
+    fn the_work_target_is_reached_by_the_weights_typed() {
+        let mut model = model();
+        apply(Msg::NewTaskWeightChanged("2.5".to_owned()), &mut model);
+        add_task(&mut model, "deep work", "2");
+        add_task(&mut model, "chore", "1");
+        let weights: Vec<f64> = model
+            .data
+            .current_plan()
+            .iter()
+            .map(|wp| wp.weight)
+            .collect();
+        assert_eq!(weights, vec![2.5, 2.5, 1.0]);
+
+        apply_all(&mut model, vec![Msg::FinishedTopTask, Msg::FinishedTopTask]);
+        assert!(!model.target_reached);
+        apply(Msg::FinishedTopTask, &mut model);
+        assert!(model.target_reached);
+    }
+
+    #[test]
+    fn tasks_with_an_invalid_weight_are_not_added() {
+        let mut model = model();
+        apply(Msg::NewTaskWeightChanged("heavy".to_owned()), &mut model);
+        add_task(&mut model, "deep work", "1");
+        assert!(plan_names(&model).is_empty());
+        assert_eq!(model.notices.len(), 1);
+    }
+
+    #[test]
+    fn deleted_tasks_can_be_put_back_from_the_notice() {
+        let mut model = model();
+        add_task(&mut model, "read", "1");
+        add_task(&mut model, "write", "2");
+        add_task(&mut model, "rest", "1");
+        let ids = plan_ids(&model);
+        apply(Msg::DeleteTasks(ids[1..3].to_vec()), &mut model);
+        apply(Msg::DeleteTask(ids[0]), &mut model);
+        assert_eq!(plan_names(&model), vec!["rest"]);
+        let messages: Vec<&str> = model.notices.iter().map(|n| n.message.as_str()).collect();
+        assert_eq!(messages, vec!["Deleted 2 tasks", "Deleted \"read\""]);
+
+        let [write, read] = [model.notices[0].id, model.notices[1].id];
+        apply(Msg::NoticeActionClicked(read), &mut model);
+        assert_eq!(plan_names(&model), vec!["read", "rest"]);
+        apply(Msg::NoticeActionClicked(write), &mut model);
+        assert_eq!(plan_ids(&model), ids);
+        assert!(model.notices.is_empty());
+        // The notices are gone, so clicking again or expiring them does nothing.
+        apply(Msg::NoticeActionClicked(read), &mut model);
+        apply(Msg::NoticeExpired(read), &mut model);
+        assert_eq!(plan_ids(&model), ids);
+    }
+
+    #[test]
+    fn notices_are_worded_in_the_chosen_language() {
+        let mut model = model();
+        apply(Msg::LangChanged(Lang::Spanish), &mut model);
+        add_task(&mut model, "read", "1");
+        apply(Msg::DeleteTask(plan_ids(&model)[0]), &mut model);
+        let notice = &model.notices[0];
+        assert_eq!(notice.message, "Se borró \"read\"");
+        assert_eq!(notice.action.as_ref().unwrap().0, "Deshacer");
+    }
+
+    #[test]
+    fn restoring_tasks_already_put_back_does_nothing() {
+        let mut model = model();
+        add_task(&mut model, "read", "1");
+        let id = plan_ids(&model)[0];
+        apply(Msg::DeleteTask(id), &mut model);
+        apply(Msg::Undo, &mut model);
+        assert_eq!(plan_ids(&model), vec![id]);
+        let notice = model.notices[0].id;
+        apply(Msg::NoticeActionClicked(notice), &mut model);
+        assert_eq!(plan_ids(&model), vec![id]);
+    }
+
+    #[test]
+    fn finishing_the_plan_opens_the_day_review_once_per_target() {
+        let mut model = model();
+        add_task(&mut model, "read", "2");
+        apply(Msg::FinishedTopTask, &mut model);
+        assert!(!model.day_review_open);
+        apply(Msg::FinishedTopTask, &mut model);
+        assert!(model.day_review_open);
+
+        apply(Msg::DismissDayReview, &mut model);
+        add_task(&mut model, "write", "1");
+        apply(Msg::FinishedTopTask, &mut model);
+        assert!(!model.day_review_open);
+
+        apply(Msg::GoalTargetWorkCountChanged("8".to_owned()), &mut model);
+        add_task(&mut model, "write", "1");
+        apply(Msg::FinishedTopTask, &mut model);
+        assert!(model.day_review_open);
+    }
+
+    #[test]
+    fn the_day_review_plans_tomorrow_or_records_the_bedtime() {
+        let mut model = model();
+        add_task(&mut model, "read", "1");
+        apply(Msg::FinishedTopTask, &mut model);
+        let effects = apply(Msg::PlanTomorrow, &mut model);
+        assert!(!model.day_review_open);
+        assert_eq!(model.data.current_date, ymd(2021, 5, 6));
+        assert!(matches!(effects[..], [Effect::FocusNewTaskName]));
+
+        let mut model = self::model();
+        model.data.day_cutoff_hour = 3;
+        let now = ymd(2021, 5, 6).and_hms_opt(0, 40, 0).unwrap();
+        add_task(&mut model, "read", "1");
+        apply_all(
+            &mut model,
+            vec![Msg::FinishedTopTask, Msg::RecordBedtimeAt(now)],
+        );
+        assert!(!model.day_review_open);
+        let ws = &model.data.work_sleep_data.days()[&ymd(2021, 5, 5)];
+        assert_eq!(
+            ws.actual_bedtime,
+            Some(Bedtime {
+                time: NaiveTime::from_hms_opt(0, 40, 0).unwrap(),
+                next_day: true,
+            })
+        );
+    }
+
+    #[test]
+    fn going_to_bed_now_records_the_bedtime_and_tells_the_score() {
+        let mut model = model();
+        let now = ymd(2021, 5, 5).and_hms_opt(23, 30, 10).unwrap();
+        apply(Msg::RecordBedtimeAt(now), &mut model);
+        let ws = &model.data.work_sleep_data.days()[&ymd(2021, 5, 5)];
+        assert_eq!(
+            ws.actual_bedtime,
+            Some(Bedtime {
+                time: NaiveTime::from_hms_opt(23, 30, 0).unwrap(),
+                next_day: false,
+            })
+        );
+        assert_eq!(
+            model.notices[0].message,
+            "Bedtime recorded at 11:30 PM. The day's score: 15"
+        );
+        apply(Msg::Undo, &mut model);
+        assert!(model.data.work_sleep_data.days()[&ymd(2021, 5, 5)]
+            .actual_bedtime
+            .is_none());
+    }
+
+    #[test]
+    fn going_to_bed_now_asks_before_replacing_a_bedtime() {
+        let mut model = model();
+        let recorded = |model: &Model| {
+            model.data.work_sleep_data.days()[&ymd(2021, 5, 5)]
+                .actual_bedtime
+                .clone()
+                .map(|bedtime| bedtime.time)
+        };
+        let at = |h, m| ymd(2021, 5, 5).and_hms_opt(h, m, 0).unwrap();
+        apply(Msg::RecordBedtimeAt(at(22, 0)), &mut model);
+        apply(Msg::RecordBedtimeAt(at(23, 0)), &mut model);
+        assert!(model.bedtime_to_confirm.is_some());
+        assert_eq!(recorded(&model), NaiveTime::from_hms_opt(22, 0, 0));
+        apply(Msg::CancelBedtimeOverwrite, &mut model);
+        assert_eq!(recorded(&model), NaiveTime::from_hms_opt(22, 0, 0));
+
+        apply(Msg::RecordBedtimeAt(at(23, 0)), &mut model);
+        apply(Msg::ConfirmBedtimeOverwrite, &mut model);
+        assert!(model.bedtime_to_confirm.is_none());
+        assert_eq!(recorded(&model), NaiveTime::from_hms_opt(23, 0, 0));
+    }
+
+    #[test]
+    fn going_to_bed_after_the_cutoff_records_nothing() {
+        let mut model = model();
+        let now = ymd(2021, 5, 6).and_hms_opt(0, 30, 0).unwrap();
+        apply(Msg::RecordBedtimeAt(now), &mut model);
+        assert!(model.data.work_sleep_data.days().is_empty());
+        assert_eq!(model.notices[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn tasks_can_be_planned_for_a_later_date() {
+        let mut model = model();
+        let thursday = ymd(2021, 5, 13);
+        apply(Msg::NewTaskDateChanged("2021-05-13".to_owned()), &mut model);
+        add_task(&mut model, "renew passport", "1");
+        assert!(plan_names(&model).is_empty());
+        assert_eq!(model.data.plan(thursday).len(), 1);
+        assert_eq!(model.new_task.date, None);
+        assert_eq!(model.notices[0].message, "Added to the plan for 2021-05-13");
+
+        apply(Msg::Undo, &mut model);
+        assert!(model.data.plan(thursday).is_empty());
+        apply(Msg::Redo, &mut model);
+        assert_eq!(model.data.plan(thursday).len(), 1);
+
+        let id = model.data.plan(thursday)[0].id;
+        apply(Msg::StartEditTask(id), &mut model);
+        apply(
+            Msg::EditTaskNameChanged("renew visa".to_owned()),
+            &mut model,
+        );
+        apply(Msg::CommitEditTask, &mut model);
+        assert_eq!(model.data.plan(thursday)[0].name, "renew visa");
+        apply(Msg::SetTaskPriority(id, Priority::High), &mut model);
+        assert_eq!(model.data.plan(thursday)[0].priority, Priority::High);
+    }
+
+    #[test]
+    fn new_tasks_are_not_planned_for_days_gone_by() {
+        let mut model = model();
+        apply(Msg::NewTaskDateChanged("2021-05-04".to_owned()), &mut model);
+        assert_eq!(model.new_task.date, None);
+        apply(Msg::NewTaskDateChanged("not a date".to_owned()), &mut model);
+        assert_eq!(model.new_task.date, None);
+        apply(Msg::NewTaskDateChanged("2021-05-06".to_owned()), &mut model);
+        assert_eq!(model.new_task.date, Some(ymd(2021, 5, 6)));
+        apply(Msg::NewTaskDateChanged("2021-05-05".to_owned()), &mut model);
+        assert_eq!(model.new_task.date, None);
+    }
+
+    #[test]
+    fn deleting_upcoming_tasks_can_be_undone() {
+        let mut model = model();
+        let thursday = ymd(2021, 5, 13);
+        apply(Msg::NewTaskDateChanged("2021-05-13".to_owned()), &mut model);
+        add_task(&mut model, "renew passport", "2");
+        let id = model.data.plan(thursday)[0].id;
+        apply(Msg::DeleteUpcomingTask(thursday, id), &mut model);
+        apply(Msg::Undo, &mut model);
+        assert_eq!(model.data.plan(thursday).len(), 2);
+
+        model.notices.clear();
+        apply(Msg::DeleteUpcomingTask(ymd(2021, 5, 14), id), &mut model);
+        assert_eq!(model.data.plan(thursday).len(), 2);
+        assert_eq!(model.notices.len(), 1);
+    }
+
+    #[test]
+    fn bedtimes_can_be_typed_in_either_clock_format() {
+        let mut model = model();
+        apply_all(
+            &mut model,
+            vec![
+                Msg::BedtimeInputChanged("11:30pm".to_owned()),
+                Msg::RecordBedtime,
+            ],
+        );
+        let recorded = |model: &Model| {
+            model.data.work_sleep_data.days()[&model.data.current_date]
+                .actual_bedtime
+                .clone()
+        };
+        assert_eq!(
+            recorded(&model).map(|bedtime| bedtime.time),
+            NaiveTime::from_hms_opt(23, 30, 0)
+        );
+        apply_all(
+            &mut model,
+            vec![
+                Msg::BedtimeInputChanged("22:15".to_owned()),
+                Msg::RecordBedtime,
+            ],
+        );
+        assert_eq!(
+            recorded(&model).map(|bedtime| bedtime.time),
+            NaiveTime::from_hms_opt(22, 15, 0)
+        );
+
+        apply_all(
+            &mut model,
+            vec![
+                Msg::ClockFormatChanged(ClockFormat::TwentyFourHour),
+                Msg::BedtimeInputChanged("late".to_owned()),
+                Msg::RecordBedtime,
+            ],
+        );
+        assert_eq!(
+            model.current_date_bedtime.error.as_deref(),
+            Some("\"late\" is not a valid bedtime (expected a time like 23:00)")
+        );
+    }
+
+    #[test]
+    fn the_date_rolls_over_when_the_clock_passes_the_day_cutoff() {
+        let mut model = model();
+        let at = |y, m, d, h, min| Clock::Fixed(ymd(y, m, d).and_hms_opt(h, min, 0).unwrap());
+        model.data.current_date = ymd(2021, 5, 3);
+        model.clock = at(2021, 5, 5, 23, 59);
+        assert_eq!(apply(Msg::CheckDateRollover, &mut model).len(), 1);
+        assert_eq!(model.today, ymd(2021, 5, 5));
+        assert_eq!(model.data.current_date, ymd(2021, 5, 3));
+
+        model.clock = at(2021, 5, 6, 0, 0);
+        apply(Msg::CheckDateRollover, &mut model);
+        assert_eq!(model.today, ymd(2021, 5, 6));
+        assert_eq!(model.data.current_date, ymd(2021, 5, 6));
+        assert_eq!(model.data.work_sleep_data.week_start, ymd(2021, 4, 30));
+
+        // Until 4 AM, the night still counts towards the day before.
+        apply(Msg::DayCutoffHourChanged("4".to_owned()), &mut model);
+        assert_eq!(model.today, ymd(2021, 5, 5));
+        model.clock = at(2021, 5, 6, 3, 59);
+        apply(Msg::CheckDateRollover, &mut model);
+        assert_eq!(model.today, ymd(2021, 5, 5));
+        model.clock = at(2021, 5, 6, 4, 0);
+        apply(Msg::CheckDateRollover, &mut model);
+        assert_eq!(model.today, ymd(2021, 5, 6));
+    }
+
+    #[test]
+    fn badges_are_awarded_once_with_a_notice() {
+        let mut model = model();
+        let today = model.today;
+        model.data.day_mut(today).actual_work_count = 49;
+        apply(Msg::AdjustWorkCount(today, 1), &mut model);
+        assert_eq!(model.data.achievements.len(), 1);
+        assert_eq!(model.data.achievements[0].earned_on, today);
+        assert_eq!(model.notices[0].message, "Badge earned: 50 work periods!");
+
+        apply_all(
+            &mut model,
+            vec![
+                Msg::AdjustWorkCount(today, -1),
+                Msg::AdjustWorkCount(today, 1),
+            ],
+        );
+        assert_eq!(model.data.achievements.len(), 1);
+        assert_eq!(model.notices.len(), 1);
+    }
+
+    #[test]
+    fn badge_notices_are_in_the_language_chosen() {
+        let mut model = model();
+        let today = model.today;
+        model.data.day_mut(today).actual_work_count = 49;
+        apply_all(
+            &mut model,
+            vec![
+                Msg::LangChanged(Lang::Spanish),
+                Msg::AdjustWorkCount(today, 1),
+            ],
+        );
+        assert_eq!(model.data.lang, Lang::Spanish);
+        assert_eq!(
+            model.notices[0].message,
+            "¡Insignia obtenida: 50 periodos de trabajo!"
+        );
+    }
+}
@@ -0,0 +1,611 @@
+// Editors for the default goals, one day's goals and the bedtime reminder, and the
+// first-run setup of the default goals.
+
+use chrono::NaiveTime;
+use seed::{prelude::*, *};
+use web_sys::{HtmlInputElement, NotificationPermission};
+
+use crate::app::update::Msg;
+use crate::app::{Model, Onboarding, OnboardingStep};
+use crate::i18n::{Lang, TextKey};
+use crate::model::{
+    parse_in_range, sleep_points, BedtimePenaltyMode, WeekMode, WorkSleepGoals,
+    MAX_BEDTIME_PTS_HALFLIFE, MAX_BEDTIME_REMINDER_LEAD_MINUTES, MAX_DAY_CUTOFF_HOUR,
+    MAX_TARGET_WORK_COUNT, MIN_BEDTIME_PTS_HALFLIFE, WEEKDAYS,
+};
+
+const CURVE_WIDTH: i64 = 240;
+const CURVE_HEIGHT: i64 = 100;
+// Room below the curve for the marker labels.
+const CURVE_LABEL_HEIGHT: i64 = 14;
+// The preview curve runs from on time to this many minutes late.
+const CURVE_MINUTES: i64 = 180;
+const CURVE_STEP_MINUTES: usize = 5;
+const CURVE_MARKER_MINUTES: [i64; 3] = [15, 30, 60];
+
+pub fn view_day_goals_editor(model: &Model) -> Node<Msg> {
+    let date = model.data.current_date;
+    let ws = model.data.work_sleep_data.get(&date);
+    let goals = model.data.goals_on(date);
+    let with_goals = |update: fn(&mut WorkSleepGoals, i64), min: i64, max: i64| {
+        let goals = goals.clone();
+        input_ev(Ev::Change, move |s| {
+            parse_in_range(&s, min, max).map(|value| {
+                let mut goals = goals;
+                update(&mut goals, value);
+                Msg::SetDayGoals(date, goals)
+            })
+        })
+    };
+    let bedtime_goals = goals.clone();
+    let next_day_goals = goals.clone();
+    let lang = model.data.lang;
+    div![
+        h3![lang.trf(TextKey::GoalsFor, &[&date])],
+        label![lang.tr(TextKey::WorkPointsRange)],
+        input![
+            attrs! {At::Type=>"number", At::Min=>0, At::Max=>100, At::Value=>goals.work_sleep_balance},
+            with_goals(|goals, value| goals.work_sleep_balance = value, 0, 100),
+        ],
+        br![],
+        label![lang.tr(TextKey::TargetWorkPeriods)],
+        input![
+            attrs! {At::Type=>"number", At::Min=>0, At::Max=>MAX_TARGET_WORK_COUNT, At::Value=>goals.target_work_count},
+            with_goals(
+                |goals, value| goals.target_work_count = value,
+                0,
+                MAX_TARGET_WORK_COUNT
+            ),
+        ],
+        br![],
+        label![lang.tr(TextKey::TargetBedtime)],
+        input![
+            attrs! {At::Type=>"time", At::Value=>goals.target_bedtime.time.format("%H:%M")},
+            input_ev(Ev::Change, move |s| {
+                NaiveTime::parse_from_str(&s, "%H:%M").ok().map(|time| {
+                    let mut goals = bedtime_goals;
+                    goals.target_bedtime.time = time;
+                    Msg::SetDayGoals(date, goals)
+                })
+            }),
+        ],
+        label![lang.tr(TextKey::Tomorrow)],
+        input![
+            attrs! {At::Type=>"checkbox", At::Checked=>goals.target_bedtime.next_day.as_at_value()},
+            ev(Ev::Change, move |event| {
+                event
+                    .target()
+                    .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                    .map(|input| {
+                        let mut goals = next_day_goals;
+                        goals.target_bedtime.next_day = input.checked();
+                        Msg::SetDayGoals(date, goals)
+                    })
+            }),
+        ],
+        br![],
+        label![lang.tr(TextKey::HalflifeMinutes)],
+        input![
+            attrs! {At::Type=>"number", At::Min=>MIN_BEDTIME_PTS_HALFLIFE, At::Max=>MAX_BEDTIME_PTS_HALFLIFE, At::Value=>goals.bedtime_pts_halflife},
+            with_goals(
+                |goals, value| goals.bedtime_pts_halflife = value,
+                MIN_BEDTIME_PTS_HALFLIFE,
+                MAX_BEDTIME_PTS_HALFLIFE
+            ),
+        ],
+        br![],
+        IF!(ws.is_some_and(|ws| ws.goals.is_some()) => button![
+            lang.tr(TextKey::ResetDayGoals),
+            ev(Ev::Click, move |_| Msg::ResetDayGoals(date)),
+        ]),
+    ]
+}
+
+pub fn view_current_date_goals(model: &Model) -> Node<Msg> {
+    let goals = &model.data.default_work_sleep_goals;
+    let lang = model.data.lang;
+    div![
+        h2![lang.tr(TextKey::Goals)],
+        p![lang.tr(TextKey::GoalsHelp)],
+        label![lang.trf(
+            TextKey::Balance,
+            &[&goals.work_sleep_balance, &(100 - goals.work_sleep_balance)]
+        )],
+        br![],
+        input![
+            attrs![
+                At::Type => "range",
+                At::Min => "0",
+                At::Max => "100",
+                At::Step => "5",
+                At::Value => goals.work_sleep_balance,
+            ],
+            input_ev(Ev::Input, Msg::GoalBalanceChanged)
+        ],
+        br![],
+        label![lang.trf(TextKey::TargetWorkPeriodsValue, &[&goals.target_work_count])],
+        br![],
+        input![
+            attrs![
+                At::Type => "range",
+                At::Min => "0",
+                At::Max => MAX_TARGET_WORK_COUNT,
+                At::Step => "1",
+                At::Value => goals.target_work_count,
+            ],
+            input_ev(Ev::Input, Msg::GoalTargetWorkCountChanged)
+        ],
+        br![],
+        label![lang.tr(TextKey::TargetBedtime)],
+        input![
+            attrs! {At::Type=>"time",At::Value=>goals.target_bedtime.time.format("%H:%M")},
+            input_ev(Ev::Input, Msg::GoalTargetBedtimeChanged)
+        ],
+        label![lang.tr(TextKey::Tomorrow)],
+        input![
+            attrs! {At::Type=>"checkbox", At::Checked=>goals.target_bedtime.next_day.as_at_value()},
+            ev(Ev::Change, |event| {
+                event
+                    .target()
+                    .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                    .map(|input| Msg::GoalTargetBedtimeNextDayToggled(input.checked()))
+            })
+        ],
+        br![],
+        label![
+            input![
+                attrs! {At::Type=>"checkbox", At::Checked=>goals.cap_work_score.as_at_value()},
+                ev(Ev::Change, |_| Msg::GoalCapWorkScoreToggled),
+            ],
+            lang.tr(TextKey::CapWorkScore),
+        ],
+        br![],
+        label![
+            input![
+                attrs! {At::Type=>"checkbox", At::Checked=>model.data.target_reached_sound.as_at_value()},
+                ev(Ev::Change, |_| Msg::ToggleTargetReachedSound),
+            ],
+            lang.tr(TextKey::TargetReachedSound),
+        ],
+        br![],
+        label![lang.tr(TextKey::PenalizeBedtimes)],
+        select![
+            option![
+                attrs! {At::Value=>"symmetric", At::Selected=>(goals.bedtime_penalty_mode == BedtimePenaltyMode::Symmetric).as_at_value()},
+                lang.tr(TextKey::EarlyOrLate),
+            ],
+            option![
+                attrs! {At::Value=>"late-only", At::Selected=>(goals.bedtime_penalty_mode == BedtimePenaltyMode::LateOnly).as_at_value()},
+                lang.tr(TextKey::LateOnly),
+            ],
+            input_ev(Ev::Change, |value| {
+                Msg::GoalBedtimePenaltyModeChanged(if value == "late-only" {
+                    BedtimePenaltyMode::LateOnly
+                } else {
+                    BedtimePenaltyMode::Symmetric
+                })
+            }),
+        ],
+        br![],
+        label![lang.trf(TextKey::HalflifeValue, &[&goals.bedtime_pts_halflife])],
+        br![],
+        input![
+            attrs![
+                At::Type => "range",
+                At::Min => MIN_BEDTIME_PTS_HALFLIFE,
+                At::Max => MAX_BEDTIME_PTS_HALFLIFE,
+                At::Step => "1",
+                At::Value => goals.bedtime_pts_halflife,
+            ],
+            input_ev(Ev::Input, Msg::GoalHalflifeChanged)
+        ],
+        view_sleep_points_curve(goals, lang),
+        view_weekend_goals(model.data.weekend_goals.as_ref(), lang),
+        view_bedtime_reminder_settings(model),
+        h3![lang.tr(TextKey::DayBoundary)],
+        label![lang.tr(TextKey::NewDaysStartAt)],
+        input![
+            attrs! {
+                At::Type=>"number",
+                At::Min=>0,
+                At::Max=>MAX_DAY_CUTOFF_HOUR,
+                At::Value=>model.data.day_cutoff_hour,
+            },
+            input_ev(Ev::Input, Msg::DayCutoffHourChanged),
+        ],
+        ":00",
+        p![lang.tr(TextKey::DayBoundaryHelp)],
+        view_week_mode_setting(model.data.week_mode, lang),
+    ]
+}
+
+fn view_weekend_goals(weekend_goals: Option<&WorkSleepGoals>, lang: Lang) -> Node<Msg> {
+    div![
+        h3![lang.tr(TextKey::WeekendGoals)],
+        label![
+            input![
+                attrs! {At::Type=>"checkbox", At::Checked=>weekend_goals.is_some().as_at_value()},
+                ev(Ev::Change, |_| Msg::ToggleWeekendGoals),
+            ],
+            lang.tr(TextKey::UseWeekendGoals),
+        ],
+        weekend_goals.map(|goals| view_weekend_goals_editor(goals, lang)),
+    ]
+}
+
+// Weekend days get these goals when they're created, so changing them leaves days
+// already recorded alone.
+fn view_weekend_goals_editor(goals: &WorkSleepGoals, lang: Lang) -> Node<Msg> {
+    let with_goals = |update: fn(&mut WorkSleepGoals, i64), min: i64, max: i64| {
+        let goals = goals.clone();
+        input_ev(Ev::Change, move |s| {
+            parse_in_range(&s, min, max).map(|value| {
+                let mut goals = goals;
+                update(&mut goals, value);
+                Msg::SetWeekendGoals(goals)
+            })
+        })
+    };
+    let bedtime_goals = goals.clone();
+    let next_day_goals = goals.clone();
+    div![
+        p![lang.tr(TextKey::WeekendGoalsHelp)],
+        label![lang.tr(TextKey::WorkPointsRange)],
+        input![
+            attrs! {At::Type=>"number", At::Min=>0, At::Max=>100, At::Value=>goals.work_sleep_balance},
+            with_goals(|goals, value| goals.work_sleep_balance = value, 0, 100),
+        ],
+        br![],
+        label![lang.tr(TextKey::TargetWorkPeriods)],
+        input![
+            attrs! {At::Type=>"number", At::Min=>0, At::Max=>MAX_TARGET_WORK_COUNT, At::Value=>goals.target_work_count},
+            with_goals(
+                |goals, value| goals.target_work_count = value,
+                0,
+                MAX_TARGET_WORK_COUNT
+            ),
+        ],
+        br![],
+        label![lang.tr(TextKey::TargetBedtime)],
+        input![
+            attrs! {At::Type=>"time", At::Value=>goals.target_bedtime.time.format("%H:%M")},
+            input_ev(Ev::Change, move |s| {
+                NaiveTime::parse_from_str(&s, "%H:%M").ok().map(|time| {
+                    let mut goals = bedtime_goals;
+                    goals.target_bedtime.time = time;
+                    Msg::SetWeekendGoals(goals)
+                })
+            }),
+        ],
+        label![lang.tr(TextKey::Tomorrow)],
+        input![
+            attrs! {At::Type=>"checkbox", At::Checked=>goals.target_bedtime.next_day.as_at_value()},
+            ev(Ev::Change, move |event| {
+                event
+                    .target()
+                    .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                    .map(|input| {
+                        let mut goals = next_day_goals;
+                        goals.target_bedtime.next_day = input.checked();
+                        Msg::SetWeekendGoals(goals)
+                    })
+            }),
+        ],
+        br![],
+        label![lang.tr(TextKey::HalflifeMinutes)],
+        input![
+            attrs! {At::Type=>"number", At::Min=>MIN_BEDTIME_PTS_HALFLIFE, At::Max=>MAX_BEDTIME_PTS_HALFLIFE, At::Value=>goals.bedtime_pts_halflife},
+            with_goals(
+                |goals, value| goals.bedtime_pts_halflife = value,
+                MIN_BEDTIME_PTS_HALFLIFE,
+                MAX_BEDTIME_PTS_HALFLIFE
+            ),
+        ],
+    ]
+}
+
+// Sleep points by minutes late for `goals`, worked out as the score works them out.
+fn view_sleep_points_curve(goals: &WorkSleepGoals, lang: Lang) -> Node<Msg> {
+    let goals = goals.clamped();
+    let points_at = |minutes: i64| {
+        sleep_points(
+            minutes,
+            goals.work_sleep_balance,
+            goals.bedtime_pts_halflife,
+        )
+    };
+    let x_of = |minutes: i64| minutes * CURVE_WIDTH / CURVE_MINUTES;
+    let y_of = |points: f64| CURVE_HEIGHT as f64 * (1.0 - points / 100.0);
+    let line: Vec<String> = (0..=CURVE_MINUTES)
+        .step_by(CURVE_STEP_MINUTES)
+        .map(|minutes| format!("{},{:.1}", x_of(minutes), y_of(points_at(minutes))))
+        .collect();
+    let height = CURVE_HEIGHT + CURVE_LABEL_HEIGHT;
+    div![
+        svg![
+            attrs! {
+                At::Width => CURVE_WIDTH,
+                At::Height => height,
+                At::ViewBox => format!("0 0 {} {}", CURVE_WIDTH, height),
+                At::AriaHidden => "true",
+            },
+            line_![attrs! {
+                At::X1 => 0,
+                At::Y1 => CURVE_HEIGHT,
+                At::X2 => CURVE_WIDTH,
+                At::Y2 => CURVE_HEIGHT,
+                At::Stroke => "gray",
+            }],
+            polyline![attrs! {
+                At::Points => line.join(" "),
+                At::Fill => "none",
+                At::Stroke => "steelblue",
+                At::StrokeWidth => 2,
+            }],
+            CURVE_MARKER_MINUTES.iter().map(|&minutes| {
+                let x = x_of(minutes);
+                vec![
+                    circle![attrs! {
+                        At::Cx => x,
+                        At::Cy => format!("{:.1}", y_of(points_at(minutes))),
+                        At::R => 3,
+                        At::Fill => "crimson",
+                    }],
+                    text![
+                        attrs! {
+                            At::X => x,
+                            At::Y => height - 2,
+                            At::TextAnchor => "middle",
+                            At::FontSize => 10,
+                        },
+                        format!("{}m", minutes),
+                    ],
+                ]
+            }),
+        ],
+        p![small![CURVE_MARKER_MINUTES
+            .iter()
+            .map(|&minutes| lang.trf(
+                TextKey::MinutesLatePoints,
+                &[&minutes, &format!("{:.0}", points_at(minutes))]
+            ))
+            .collect::<Vec<_>>()
+            .join(" · ")]],
+    ]
+}
+
+pub fn view_onboarding(
+    onboarding: &Onboarding,
+    defaults: &WorkSleepGoals,
+    lang: Lang,
+) -> Node<Msg> {
+    let goals = &onboarding.goals;
+    let with_goals = |update: fn(&mut WorkSleepGoals, i64), min: i64, max: i64| {
+        let goals = goals.clone();
+        input_ev(Ev::Input, move |s| {
+            parse_in_range(&s, min, max).map(|value| {
+                let mut goals = goals;
+                update(&mut goals, value);
+                Msg::OnboardingGoalsChanged(goals)
+            })
+        })
+    };
+    let (number, previous, next) = match onboarding.step {
+        OnboardingStep::TargetWorkCount => (1, None, Some(OnboardingStep::TargetBedtime)),
+        OnboardingStep::TargetBedtime => (
+            2,
+            Some(OnboardingStep::TargetWorkCount),
+            Some(OnboardingStep::WorkSleepBalance),
+        ),
+        OnboardingStep::WorkSleepBalance => (3, Some(OnboardingStep::TargetBedtime), None),
+    };
+    let question = match onboarding.step {
+        OnboardingStep::TargetWorkCount => div![
+            label![lang.tr(TextKey::OnboardingWorkCount)],
+            input![
+                attrs! {At::Type=>"number", At::Min=>0, At::Max=>MAX_TARGET_WORK_COUNT, At::Value=>goals.target_work_count},
+                with_goals(
+                    |goals, value| goals.target_work_count = value,
+                    0,
+                    MAX_TARGET_WORK_COUNT
+                ),
+            ],
+        ],
+        OnboardingStep::TargetBedtime => {
+            let bedtime_goals = goals.clone();
+            let next_day_goals = goals.clone();
+            div![
+                label![lang.tr(TextKey::OnboardingBedtime)],
+                input![
+                    attrs! {At::Type=>"time", At::Value=>goals.target_bedtime.time.format("%H:%M")},
+                    input_ev(Ev::Input, move |s| {
+                        NaiveTime::parse_from_str(&s, "%H:%M").ok().map(|time| {
+                            let mut goals = bedtime_goals;
+                            goals.target_bedtime.time = time;
+                            Msg::OnboardingGoalsChanged(goals)
+                        })
+                    }),
+                ],
+                br![],
+                label![
+                    input![
+                        attrs! {At::Type=>"checkbox", At::Checked=>goals.target_bedtime.next_day.as_at_value()},
+                        ev(Ev::Change, move |_| {
+                            let mut goals = next_day_goals;
+                            goals.target_bedtime.next_day ^= true;
+                            Msg::OnboardingGoalsChanged(goals)
+                        }),
+                    ],
+                    lang.tr(TextKey::AfterMidnight),
+                ],
+            ]
+        }
+        OnboardingStep::WorkSleepBalance => div![
+            label![lang.trf(
+                TextKey::OnboardingBalance,
+                &[&goals.work_sleep_balance, &(100 - goals.work_sleep_balance)]
+            )],
+            br![],
+            input![
+                attrs![
+                    At::Type => "range",
+                    At::Min => "0",
+                    At::Max => "100",
+                    At::Step => "5",
+                    At::Value => goals.work_sleep_balance,
+                ],
+                with_goals(|goals, value| goals.work_sleep_balance = value, 0, 100),
+            ],
+        ],
+    };
+    let finished_goals = goals.clone();
+    let skipped_goals = defaults.clone();
+    div![
+        style! {St::Border=>"1px solid #ccc", St::Padding=>"1em", St::MaxWidth=>"30em"},
+        h2![lang.tr(TextKey::Welcome)],
+        p![lang.trf(TextKey::OnboardingStep, &[&number])],
+        question,
+        div![
+            previous.map(|step| button![
+                lang.tr(TextKey::Back),
+                ev(Ev::Click, move |_| Msg::OnboardingStepChanged(step)),
+            ]),
+            match next {
+                Some(step) => button![
+                    lang.tr(TextKey::Next),
+                    ev(Ev::Click, move |_| Msg::OnboardingStepChanged(step)),
+                ],
+                None => button![
+                    lang.tr(TextKey::FinishOnboarding),
+                    ev(Ev::Click, move |_| Msg::CompleteOnboarding(finished_goals)),
+                ],
+            },
+            button![
+                lang.tr(TextKey::SkipOnboarding),
+                ev(Ev::Click, move |_| Msg::CompleteOnboarding(skipped_goals)),
+            ],
+        ],
+    ]
+}
+
+fn view_week_mode_setting(week_mode: WeekMode, lang: Lang) -> Node<Msg> {
+    let option = |value: &str, label: &str, mode: WeekMode| {
+        option![
+            attrs! {At::Value=>value, At::Selected=>(week_mode == mode).as_at_value()},
+            label,
+        ]
+    };
+    div![
+        h3![lang.tr(TextKey::Weeks)],
+        label![lang.tr(TextKey::HistoryWeeksAre)],
+        select![
+            option("rolling", lang.tr(TextKey::RollingWeeks), WeekMode::Rolling),
+            WEEKDAYS.iter().enumerate().map(|(i, weekday)| {
+                option(
+                    &i.to_string(),
+                    &lang.trf(TextKey::CalendarWeeksFrom, &[&lang.weekday_short(*weekday)]),
+                    WeekMode::Calendar(*weekday),
+                )
+            }),
+            input_ev(Ev::Change, |value| {
+                let mode = value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| WEEKDAYS.get(i))
+                    .map_or(WeekMode::Rolling, |weekday| WeekMode::Calendar(*weekday));
+                Msg::WeekModeChanged(mode)
+            }),
+        ],
+    ]
+}
+
+fn view_bedtime_reminder_settings(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    let explanation = match model.notification_permission {
+        None => return p![lang.tr(TextKey::NoNotifications)],
+        Some(NotificationPermission::Denied) => Some(lang.tr(TextKey::NotificationsBlocked)),
+        Some(_) => None,
+    };
+    div![
+        h3![lang.tr(TextKey::BedtimeReminder)],
+        label![
+            input![
+                attrs! {
+                    At::Type=>"checkbox",
+                    At::Checked=>model.data.bedtime_reminder_enabled.as_at_value(),
+                    At::Disabled=>explanation.is_some().as_at_value(),
+                },
+                ev(Ev::Change, |_| Msg::ToggleBedtimeReminder),
+            ],
+            lang.tr(TextKey::RemindMe),
+        ],
+        input![
+            attrs! {
+                At::Type=>"number",
+                At::Min=>1,
+                At::Max=>MAX_BEDTIME_REMINDER_LEAD_MINUTES,
+                At::Value=>model.data.bedtime_reminder_lead_minutes,
+            },
+            input_ev(Ev::Input, Msg::BedtimeReminderLeadChanged),
+        ],
+        lang.tr(TextKey::MinutesBeforeBedtime),
+        explanation.map(|explanation| p![explanation]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::super::test_support::text_content;
+    use super::*;
+
+    #[test]
+    fn onboarding_asks_one_question_per_step() {
+        let defaults = WorkSleepGoals::default();
+        let mut onboarding = Onboarding {
+            step: OnboardingStep::TargetWorkCount,
+            goals: defaults.clone(),
+        };
+        let text = text_content(&view_onboarding(&onboarding, &defaults, Lang::English));
+        assert!(text.contains("Step 1 of 3"));
+        assert!(text.contains("work periods"));
+        assert!(!text.contains("Back"));
+        onboarding.step = OnboardingStep::WorkSleepBalance;
+        let text = text_content(&view_onboarding(&onboarding, &defaults, Lang::English));
+        assert!(text.contains("70 go to work and 30 to sleep"));
+        assert!(text.contains("Done"));
+    }
+
+    #[test]
+    fn the_sleep_points_curve_follows_the_goals() {
+        let mut goals = WorkSleepGoals::default();
+        let text = text_content(&view_sleep_points_curve(&goals, Lang::English));
+        assert!(text.contains("15 min late: 21 points"));
+        assert!(text.contains("30 min late: 15 points"));
+        goals.bedtime_pts_halflife = 60;
+        goals.work_sleep_balance = 0;
+        let text = text_content(&view_sleep_points_curve(&goals, Lang::English));
+        assert!(text.contains("60 min late: 50 points"));
+    }
+
+    #[test]
+    fn default_goals_show_the_work_sleep_split() {
+        let model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        assert!(text_content(&view_current_date_goals(&model))
+            .contains("70 points for work, 30 points for sleep"));
+    }
+
+    #[test]
+    fn onboarding_can_be_shown_in_spanish() {
+        let defaults = WorkSleepGoals::default();
+        let onboarding = Onboarding {
+            step: OnboardingStep::WorkSleepBalance,
+            goals: defaults.clone(),
+        };
+        let text = text_content(&view_onboarding(&onboarding, &defaults, Lang::Spanish));
+        assert!(text.contains("Paso 3 de 3"));
+        assert!(text.contains("70 son para el trabajo y 30 para el sueño"));
+        assert!(text.contains("Listo"));
+    }
+}
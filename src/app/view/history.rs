@@ -0,0 +1,1266 @@
+// The week and month history, with scores and stats for each day.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use seed::{prelude::*, *};
+
+use crate::achievements::Badge;
+use crate::app::update::Msg;
+use crate::app::{HistoryView, Model, WeekReportStatus};
+use crate::i18n::{Lang, TextKey};
+use crate::model::{
+    add_months, format_sleep, format_work_count, is_weekend, note_preview, shift_days, Bedtime,
+    CompletedPeriod, Data, RangeSummary, ScoreBreakdown, Streaks, WeekArchive, WeekSummary,
+    WorkProgress, WorkSleep, MAX_CONSISTENCY_DAYS, MIN_CONSISTENCY_NIGHTS, MOOD_EMOJIS, WEEKDAYS,
+};
+
+use super::goals::view_day_goals_editor;
+use super::tasks::NOTE_PREVIEW_CHARS;
+
+const NO_DATA_COLOR: &str = "#eee";
+const HEATMAP_CELL: i64 = 12;
+const HEATMAP_GAP: i64 = 2;
+
+pub fn view_work_sleep_data(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    let toggle = |label: TextKey, history_view: HistoryView| {
+        button![
+            lang.tr(label),
+            attrs! {At::Disabled=>(model.history_view == history_view).as_at_value()},
+            ev(Ev::Click, move |_| Msg::SetHistoryView(history_view)),
+        ]
+    };
+    div![
+        toggle(TextKey::Week, HistoryView::Week),
+        toggle(TextKey::Month, HistoryView::Month),
+        toggle(TextKey::Year, HistoryView::Year),
+        toggle(TextKey::AllTime, HistoryView::AllTime),
+        match model.history_view {
+            HistoryView::Week => view_week(model),
+            HistoryView::Month => view_month(model),
+            HistoryView::Year => view_year_heatmap(model),
+            HistoryView::AllTime => div![view_all_time_stats(model), view_badges(&model.data)],
+        },
+        view_bedtime_consistency(model),
+        IF!(model.day_goals_editor_open => view_day_goals_editor(model)),
+    ]
+}
+
+fn view_bedtime_consistency(model: &Model) -> Node<Msg> {
+    let days = model.data.consistency_days;
+    let stats = model
+        .data
+        .work_sleep_data
+        .bedtime_consistency(model.today, days);
+    let lang = model.data.lang;
+    p![
+        lang.tr(TextKey::ConsistencyOverTheLast),
+        input![
+            style! {St::Width=>"4em"},
+            attrs! {
+                At::Type=>"number",
+                At::Min=>MIN_CONSISTENCY_NIGHTS,
+                At::Max=>MAX_CONSISTENCY_DAYS,
+                At::Value=>days,
+            },
+            input_ev(Ev::Change, Msg::ConsistencyDaysChanged),
+        ],
+        lang.tr(TextKey::ConsistencyDays),
+        match stats {
+            Some(stats) => lang.trf(
+                TextKey::ConsistencyStats,
+                &[
+                    &format!("{:.0}", stats.stddev_minutes),
+                    &model.data.fmt_bedtime(&stats.mean),
+                    &stats.nights,
+                ]
+            ),
+            None => lang.tr(TextKey::NotEnoughData).to_owned(),
+        },
+    ]
+}
+
+// Every badge, with when it was earned or what it takes.
+fn view_badges(data: &Data) -> Node<Msg> {
+    let lang = data.lang;
+    div![
+        h3![lang.tr(TextKey::Badges)],
+        ul![Badge::ALL.iter().map(|badge| {
+            let earned = data
+                .achievements
+                .iter()
+                .find(|achievement| achievement.badge == *badge);
+            li![
+                IF!(earned.is_none() => style! {St::Opacity=>"0.5"}),
+                strong![
+                    if earned.is_some() { "🏆 " } else { "🔒 " },
+                    lang.tr(badge.name())
+                ],
+                format!(": {}", lang.tr(badge.criteria())),
+                earned.map(|achievement| {
+                    lang.trf(TextKey::EarnedOn, &[&data.fmt_date(achievement.earned_on)])
+                }),
+            ]
+        })],
+    ]
+}
+
+fn view_all_time_stats(model: &Model) -> Node<Msg> {
+    let work_sleep_data = &model.data.work_sleep_data;
+    let stats = work_sleep_data.all_time_stats(&model.data.default_work_sleep_goals);
+    let lang = model.data.lang;
+    if stats.days_tracked == 0 {
+        return p![lang.tr(TextKey::NothingRecorded)];
+    }
+    let row = |label: TextKey, value: String| {
+        tr![th![attrs! {At::Scope=>"row"}, lang.tr(label)], td![value]]
+    };
+    let day = |day: Option<(NaiveDate, i64)>| {
+        day.map_or("-".to_owned(), |(date, score)| {
+            lang.trf(TextKey::ScoreOnDate, &[&score, &model.data.fmt_date(date)])
+        })
+    };
+    div![
+        table![
+            caption![lang.tr(TextKey::AllTime)],
+            row(TextKey::DaysTracked, stats.days_tracked.to_string()),
+            row(
+                TextKey::WorkPeriodsCompleted,
+                format_work_count(stats.work_completed)
+            ),
+            row(
+                TextKey::AverageScoreRow,
+                stats
+                    .average_score
+                    .map_or("-".to_owned(), |score| format!("{:.1}", score)),
+            ),
+            row(TextKey::BestDay, day(stats.best_day)),
+            row(TextKey::WorstDay, day(stats.worst_day)),
+            row(
+                TextKey::LongestStreak,
+                lang.count(
+                    stats.longest_streak,
+                    TextKey::DayCountOne,
+                    TextKey::DayCountOther
+                ),
+            ),
+            row(
+                TextKey::AverageBedtimeRow,
+                stats
+                    .average_bedtime_deviation
+                    .map_or("-".to_owned(), |minutes| format_deviation(minutes, lang)),
+            ),
+        ],
+        p![small![lang.trf(
+            TextKey::DaysScored,
+            &[&stats.days_scored, &stats.days_tracked]
+        )]],
+        work_sleep_data.archived_before.map(|cutoff| {
+            p![small![lang.trf(
+                TextKey::ArchivedNotIncluded,
+                &[&model.data.fmt_date(cutoff)]
+            )]]
+        }),
+    ]
+}
+
+// Minutes from the target bedtime, with which side of it they were on.
+fn format_deviation(minutes: f64, lang: Lang) -> String {
+    let rounded = minutes.round();
+    if rounded > 0.0 {
+        lang.trf(TextKey::MinAfterTheTarget, &[&rounded])
+    } else if rounded < 0.0 {
+        lang.trf(TextKey::MinBeforeTheTarget, &[&-rounded])
+    } else {
+        lang.tr(TextKey::OnTheTarget).to_owned()
+    }
+}
+
+// A square per day, in a column per week from Monday at the top, colored by its score.
+fn view_year_heatmap(model: &Model) -> Node<Msg> {
+    let end = model.data.current_date;
+    let start = shift_days(end, -364);
+    let work_sleep_data = &model.data.work_sleep_data;
+    let defaults = &model.data.default_work_sleep_goals;
+    let lang = model.data.lang;
+    let offset = i64::from(start.weekday().num_days_from_monday());
+    let days = (end - start).num_days() + 1;
+    let slot = HEATMAP_CELL + HEATMAP_GAP;
+    let width = (offset + days + 6) / 7 * slot;
+    let height = 7 * slot;
+    svg![
+        attrs! {
+            At::Width => width,
+            At::Height => height,
+            At::ViewBox => format!("0 0 {} {}", width, height),
+        },
+        work_sleep_data
+            .dates_in(start, end)
+            .enumerate()
+            .map(|(i, (date, ws))| {
+                let score = ws.map(|ws| work_sleep_data.score(date, ws, defaults));
+                let cell = offset + i as i64;
+                rect![
+                    attrs! {
+                        At::X => cell / 7 * slot,
+                        At::Y => cell % 7 * slot,
+                        At::Width => HEATMAP_CELL,
+                        At::Height => HEATMAP_CELL,
+                        At::Fill => heatmap_color(score),
+                    },
+                    style! {St::Cursor => "pointer"},
+                    title![match score {
+                        Some(score) => format!("{}: {}", date, score),
+                        None => lang.trf(TextKey::DateNoData, &[&date]),
+                    }],
+                    ev(Ev::Click, move |_| Msg::SetCurrentDate(date)),
+                ]
+            }),
+    ]
+}
+
+fn heatmap_color(score: Option<i64>) -> &'static str {
+    match score {
+        None => NO_DATA_COLOR,
+        Some(score) if score < 50 => "#f4c7a1",
+        Some(score) if score < 80 => "#c6e48b",
+        Some(score) if score < 100 => "#7bc96f",
+        Some(_) => "#239a3b",
+    }
+}
+
+fn view_month(model: &Model) -> Node<Msg> {
+    let work_sleep_data = &model.data.work_sleep_data;
+    let month = work_sleep_data.week_start;
+    let days = work_sleep_data.get_month(month.year(), month.month());
+    let is_latest_month = add_months(month, 1)
+        .filter(|next| *next <= model.data.current_date)
+        .is_none();
+    let lang = model.data.lang;
+    div![
+        h2![lang.month_year(month)],
+        button![
+            lang.tr(TextKey::PreviousMonth),
+            ev(Ev::Click, |_| Msg::ViewPreviousMonth)
+        ],
+        button![
+            lang.tr(TextKey::Today),
+            ev(Ev::Click, |_| Msg::ViewCurrentMonth)
+        ],
+        button![
+            lang.tr(TextKey::NextMonth),
+            attrs! {At::Disabled=>is_latest_month.as_at_value()},
+            ev(Ev::Click, |_| Msg::ViewNextMonth),
+        ],
+        table![
+            tr![WEEKDAYS
+                .iter()
+                .map(|weekday| th![attrs! {At::Scope=>"col"}, lang.weekday_short(*weekday)])],
+            days.chunks(7).map(|week| {
+                tr![week.iter().map(|day| match day {
+                    Some((date, ws)) => {
+                        let defaults = &model.data.default_work_sleep_goals;
+                        view_work_sleep_data_compact_day(
+                            *date,
+                            ws.map(|ws| work_sleep_data.score(*date, ws, defaults)),
+                            *date == model.data.current_date,
+                            model.data.is_archived(*date),
+                            lang,
+                        )
+                    }
+                    None => td![],
+                })]
+            }),
+        ],
+    ]
+}
+
+// `score` is `None` for a day without data.
+fn view_work_sleep_data_compact_day(
+    date: NaiveDate,
+    score: Option<i64>,
+    is_current_date: bool,
+    is_archived: bool,
+    lang: Lang,
+) -> Node<Msg> {
+    let background = score.map_or(NO_DATA_COLOR.to_owned(), score_color);
+    td![
+        style! {
+            St::Background=>background,
+            St::Outline=>if is_current_date { "2px solid black" } else { "none" },
+        },
+        IF!(is_current_date => attrs! {At::AriaCurrent=>"date"}),
+        button![
+            date.day().to_string(),
+            attrs! {At::AriaLabel=>date_button_label(date, score, lang)},
+            ev(Ev::Click, move |_| Msg::SetCurrentDate(date)),
+        ],
+        br![],
+        match score {
+            Some(score) => score.to_string(),
+            None if is_archived => lang.tr(TextKey::ArchivedLower).to_owned(),
+            None => "-".to_owned(),
+        },
+    ]
+}
+
+fn view_week(model: &Model) -> Node<Msg> {
+    let work_sleep_data = &model.data.work_sleep_data;
+    let defaults = &model.data.default_work_sleep_goals;
+    let lang = model.data.lang;
+    let week = work_sleep_data.get_current_week();
+    let scores: Vec<Option<i64>> = week
+        .iter()
+        .map(|(date, ws)| ws.map(|ws| work_sleep_data.score(*date, ws, defaults)))
+        .collect();
+    let summary = work_sleep_data.week_summary(defaults);
+    let archives: Vec<&WeekArchive> = model
+        .data
+        .archives_in(work_sleep_data.week_start, work_sleep_data.week_end())
+        .collect();
+    // A week whose days were all archived away shows only what was kept of it.
+    let fully_archived = summary.days_with_data == 0
+        && week
+            .iter()
+            .all(|(date, _)| archives.iter().any(|archive| archive.contains(*date)));
+    div![
+        h2![
+            attrs! {At::Id=>"week-range"},
+            format!(
+                "{} – {}",
+                model.data.fmt_date(work_sleep_data.week_start),
+                model.data.fmt_date(work_sleep_data.week_end())
+            )
+        ],
+        view_score_chart(&week, &scores, &model.data),
+        button![
+            lang.tr(TextKey::PreviousWeek),
+            attrs! {At::Id=>"previous-week"},
+            ev(Ev::Click, |_| Msg::ViewPreviousWeek),
+        ],
+        button![
+            lang.tr(TextKey::Today),
+            ev(Ev::Click, |_| Msg::ViewCurrentWeek),
+        ],
+        button![
+            lang.tr(TextKey::NextWeek),
+            attrs! {At::Id=>"next-week", At::Disabled=>(work_sleep_data.week_end() >= model.data.current_date).as_at_value()},
+            ev(Ev::Click, |_| Msg::ViewNextWeek),
+        ],
+        archives
+            .iter()
+            .map(|archive| view_week_archive_card(archive, &model.data)),
+        // Narrow screens stack the days as cards, each already naming its date.
+        IF!(!fully_archived => table![
+            IF!(model.is_narrow() => C!["day-cards"]),
+            caption![week_numbers_caption(&week, lang)],
+            IF!(!model.is_narrow() => tr![
+                week.iter().map(|(date, _)| {
+                    th![
+                        IF!(is_weekend(*date) => C!["weekend"]),
+                        attrs! {At::Scope=>"col"},
+                        lang.weekday_short(date.weekday())
+                    ]
+                }),
+                th![attrs! {At::Scope=>"col"}, lang.tr(TextKey::Week)],
+            ]),
+            tr![
+                week.iter().zip(&scores).map(|((date, ws), score)| {
+                    view_work_sleep_data_one_day(
+                        *date,
+                        ws.zip(*score),
+                        &model.data,
+                        *date == model.data.current_date,
+                        model.data.is_archived(*date),
+                        model.expanded_day == Some(*date),
+                        model.data.plan(*date).len(),
+                    )
+                }),
+                view_week_summary(&summary, lang),
+            ],
+        ]),
+        model
+            .expanded_day
+            .filter(|expanded| week.iter().any(|(date, _)| date == expanded))
+            .map(|date| view_day_details(model, date)),
+        view_bedtime_stats(&summary, &model.data),
+        view_week_comparison(
+            &work_sleep_data.summarize_range(work_sleep_data.week_start, 7, defaults),
+            &work_sleep_data.summarize_range(
+                shift_days(work_sleep_data.week_start, -7),
+                7,
+                defaults
+            ),
+            lang,
+        ),
+        if model.week_review_open {
+            view_week_review(model, &week, &scores, &summary)
+        } else {
+            view_weekly_notes(model, None)
+        },
+        button![
+            IF!(model.today.weekday() == Weekday::Sun => style! {St::FontWeight=>"bold"}),
+            lang.tr(if model.week_review_open {
+                TextKey::CloseReview
+            } else {
+                TextKey::ReviewThisWeek
+            }),
+            ev(Ev::Click, |_| Msg::ToggleWeekReview)
+        ],
+        button![
+            lang.tr(TextKey::CopyWeekReport),
+            ev(Ev::Click, |_| Msg::CopyWeekReport)
+        ],
+        match &model.week_report {
+            Some(WeekReportStatus::Copied) => span![lang.tr(TextKey::Copied)],
+            Some(WeekReportStatus::CopyFailed(report)) => div![
+                p![lang.tr(TextKey::CopyFailed)],
+                pre![report],
+                button![
+                    lang.tr(TextKey::Close),
+                    ev(Ev::Click, |_| Msg::WeekReportDismissed)
+                ],
+            ],
+            None => empty![],
+        },
+    ]
+}
+
+// `scores` has the score of each day of `week`, or `None` for days without data.
+fn view_score_chart(
+    week: &[(NaiveDate, Option<&WorkSleep>)],
+    scores: &[Option<i64>],
+    data: &Data,
+) -> Node<Msg> {
+    const BAR_SLOT: i64 = 40;
+    const BAR_WIDTH: i64 = 30;
+    const HEIGHT: i64 = 150;
+    let max_score = scores.iter().flatten().copied().fold(100, i64::max);
+    let y_of = |score: i64| HEIGHT - score.max(0) * HEIGHT / max_score;
+    let width = BAR_SLOT * week.len() as i64;
+    svg![
+        attrs! {
+            At::Width => width,
+            At::Height => HEIGHT,
+            At::ViewBox => format!("0 0 {} {}", width, HEIGHT),
+        },
+        week.iter()
+            .zip(scores)
+            .enumerate()
+            .map(|(i, ((date, _), score))| {
+                let x = i as i64 * BAR_SLOT + (BAR_SLOT - BAR_WIDTH) / 2;
+                match *score {
+                    Some(score) => {
+                        rect![
+                            attrs! {
+                                At::X => x,
+                                At::Y => y_of(score),
+                                At::Width => BAR_WIDTH,
+                                At::Height => HEIGHT - y_of(score),
+                                At::Fill => score_color(score),
+                            },
+                            title![format!("{}: {}", data.fmt_date(*date), score)],
+                        ]
+                    }
+                    None => rect![
+                        attrs! {
+                            At::X => x,
+                            At::Y => 0,
+                            At::Width => BAR_WIDTH,
+                            At::Height => HEIGHT,
+                            At::Fill => "none",
+                            At::Stroke => "silver",
+                            At::StrokeDashArray => "4",
+                        },
+                        title![data.lang.trf(TextKey::DateNoData, &[&data.fmt_date(*date)])],
+                    ],
+                }
+            }),
+        line_![attrs! {
+            At::X1 => 0,
+            At::Y1 => y_of(100),
+            At::X2 => width,
+            At::Y2 => y_of(100),
+            At::Stroke => "gray",
+            At::StrokeDashArray => "2",
+        }],
+    ]
+}
+
+// The ISO week numbers the days fall in, which are two unless the week starts on a Monday.
+fn week_numbers_caption(week: &[(NaiveDate, Option<&WorkSleep>)], lang: Lang) -> String {
+    let mut numbers: Vec<u32> = week
+        .iter()
+        .map(|(date, _)| date.iso_week().week())
+        .collect();
+    numbers.dedup();
+    match numbers[..] {
+        [number] => lang.trf(TextKey::WeekNumber, &[&number]),
+        [first, .., last] => lang.trf(TextKey::WeekNumbers, &[&first, &last]),
+        [] => String::new(),
+    }
+}
+
+// Goes over the week shown day by day, with its review notes to write.
+fn view_week_review(
+    model: &Model,
+    week: &[(NaiveDate, Option<&WorkSleep>)],
+    scores: &[Option<i64>],
+    summary: &WeekSummary,
+) -> Node<Msg> {
+    let week_start = model.data.work_sleep_data.week_start;
+    let note = model
+        .data
+        .weekly_notes
+        .get(&week_start)
+        .map_or("", String::as_str);
+    let lang = model.data.lang;
+    div![
+        h3![lang.tr(TextKey::WeekReview)],
+        ul![week
+            .iter()
+            .zip(scores)
+            .map(|((date, _), score)| li![format!(
+                "{} {}: {}",
+                lang.weekday_short(date.weekday()),
+                model.data.fmt_date(*date),
+                score.map_or(lang.tr(TextKey::NoData).to_owned(), |score| score
+                    .to_string())
+            )])],
+        p![
+            lang.trf(
+                TextKey::TotalScoreAndWork,
+                &[
+                    &summary.total_score,
+                    &format_work_count(summary.work_completed),
+                    &summary.work_target
+                ]
+            ),
+            summary.average_score().map(|average| lang.trf(
+                TextKey::AverageScoreSuffix,
+                &[&format!("{:.1}", average)]
+            )),
+        ],
+        view_weekly_notes(model, Some(week_start)),
+        label![
+            attrs! {At::For=>"weekly-note"},
+            lang.tr(TextKey::ReviewNotes)
+        ],
+        br![],
+        textarea![
+            attrs! {At::Id=>"weekly-note", At::Rows=>6, At::Cols=>60, At::Value=>note},
+            input_ev(Ev::Input, move |note| Msg::WeeklyNoteChanged(
+                week_start, note
+            )),
+        ],
+    ]
+}
+
+// The review notes written for the week shown, except those of `editing`.
+fn view_weekly_notes(model: &Model, editing: Option<NaiveDate>) -> Node<Msg> {
+    let work_sleep_data = &model.data.work_sleep_data;
+    let week_start = work_sleep_data.week_start;
+    let notes = model
+        .data
+        .weekly_notes_in(week_start, work_sleep_data.week_end())
+        .filter(|(date, _)| Some(*date) != editing);
+    let lang = model.data.lang;
+    div![notes.map(|(date, note)| div![
+        h4![if date == week_start {
+            lang.tr(TextKey::ReviewNotes).to_owned()
+        } else {
+            lang.trf(TextKey::ReviewNotesFor, &[&model.data.fmt_date(date)])
+        }],
+        p![style! {St::WhiteSpace=>"pre-wrap"}, note],
+    ])]
+}
+
+fn view_week_summary(summary: &WeekSummary, lang: Lang) -> Node<Msg> {
+    td![
+        span![lang.tr(TextKey::WeekColumn)],
+        br![],
+        span![lang.trf(TextKey::TotalScore, &[&summary.total_score])],
+        br![],
+        span![format_average_score(summary.average_score(), lang)],
+        br![],
+        span![lang.trf(
+            TextKey::WorkOutOf,
+            &[
+                &format_work_count(summary.work_completed),
+                &summary.work_target
+            ]
+        )],
+        br![],
+        span![lang.trf(
+            TextKey::AverageSleep,
+            &[&summary
+                .average_sleep_minutes
+                .map_or("-".to_owned(), format_sleep)]
+        )],
+        br![],
+        span![lang.trf(
+            TextKey::AverageMood,
+            &[&summary
+                .average_mood
+                .map_or("-".to_owned(), |average| format!("{:.1}", average))]
+        )],
+        summary
+            .completed_by_category
+            .iter()
+            .map(|(category, count)| vec![br![], span![format!("{}: {}", category, count)]]),
+    ]
+}
+
+// Shared by the live week summary and archived weeks, which must read the same.
+fn format_average_score(average: Option<f64>, lang: Lang) -> String {
+    lang.trf(
+        TextKey::AverageScore,
+        &[&average.map_or("-".to_owned(), |average| format!("{:.1}", average))],
+    )
+}
+
+fn view_week_archive_card(archive: &WeekArchive, data: &Data) -> Node<Msg> {
+    let lang = data.lang;
+    div![
+        C!["week-archive"],
+        style! {St::Border=>"1px solid #999", St::Padding=>"0.5em", St::Margin=>"0.5em 0"},
+        strong![lang.trf(
+            TextKey::ArchivedWeek,
+            &[&data.fmt_date(archive.start), &data.fmt_date(archive.end())]
+        )],
+        br![],
+        span![lang.trf(TextKey::TotalScore, &[&archive.total_score])],
+        br![],
+        span![format_average_score(archive.avg_score, lang)],
+        br![],
+        span![lang.trf(TextKey::WorkDone, &[&format_work_count(archive.work_done)])],
+        br![],
+        span![lang.trf(TextKey::NightsWithBedtime, &[&archive.nights_with_bedtime])],
+    ]
+}
+
+fn view_bedtime_stats(summary: &WeekSummary, data: &Data) -> Node<Msg> {
+    let lang = data.lang;
+    let format_bedtime = |bedtime: &Option<Bedtime>| {
+        bedtime
+            .as_ref()
+            .map_or("-".to_owned(), |bedtime| data.fmt_bedtime(bedtime))
+    };
+    p![
+        lang.trf(
+            TextKey::AverageBedtime,
+            &[&format_bedtime(&summary.average_bedtime)]
+        ),
+        br![],
+        lang.trf(
+            TextKey::EarliestLatest,
+            &[
+                &format_bedtime(&summary.earliest_bedtime),
+                &format_bedtime(&summary.latest_bedtime)
+            ]
+        ),
+        br![],
+        lang.trf(TextKey::NightsNearTarget, &[&summary.nights_near_target]),
+    ]
+}
+
+fn view_week_comparison(
+    this_week: &RangeSummary,
+    last_week: &RangeSummary,
+    lang: Lang,
+) -> Node<Msg> {
+    if last_week.days_with_data == 0 {
+        return p![lang.tr(TextKey::NoPriorWeek)];
+    }
+    let row = |label: TextKey, this: Option<f64>, last: Option<f64>, decimals: usize| {
+        let format = |value: Option<f64>| {
+            value.map_or("-".to_owned(), |value| format!("{:.*}", decimals, value))
+        };
+        tr![
+            th![attrs! {At::Scope=>"row"}, lang.tr(label)],
+            td![format(this)],
+            td![format(last)],
+            td![match (this, last) {
+                (Some(this), Some(last)) => format_change(this - last, decimals),
+                _ => "-".to_owned(),
+            }],
+        ]
+    };
+    let total = |count: i64| Some(count as f64);
+    table![
+        caption![lang.tr(TextKey::ComparedWithWeekBefore)],
+        tr![
+            th![],
+            th![attrs! {At::Scope=>"col"}, lang.tr(TextKey::ThisWeek)],
+            th![attrs! {At::Scope=>"col"}, lang.tr(TextKey::LastWeek)],
+            th![attrs! {At::Scope=>"col"}, lang.tr(TextKey::Change)],
+        ],
+        row(
+            TextKey::TotalScoreRow,
+            total(this_week.total_score),
+            total(last_week.total_score),
+            0
+        ),
+        row(
+            TextKey::AverageScoreRow,
+            this_week.average_score(),
+            last_week.average_score(),
+            1
+        ),
+        row(
+            TextKey::WorkPeriods,
+            Some(this_week.work_completed),
+            Some(last_week.work_completed),
+            0
+        ),
+        row(
+            TextKey::AverageBedtimeDeviation,
+            this_week.average_bedtime_deviation,
+            last_week.average_bedtime_deviation,
+            0
+        ),
+    ]
+}
+
+// An arrow for which way `delta` went, and by how much.
+fn format_change(delta: f64, decimals: usize) -> String {
+    let shown = format!("{:.*}", decimals, delta.abs());
+    if shown
+        .trim_start_matches(|c| c == '0' || c == '.')
+        .is_empty()
+    {
+        "=".to_owned()
+    } else if delta > 0.0 {
+        format!("▲ +{}", shown)
+    } else {
+        format!("▼ −{}", shown)
+    }
+}
+
+// What a screen reader reads out for the button selecting `date`.
+fn date_button_label(date: NaiveDate, score: Option<i64>, lang: Lang) -> String {
+    let date = lang.month_day_weekday(date);
+    match score {
+        Some(score) => lang.trf(TextKey::SelectDateScore, &[&date, &score]),
+        None => lang.trf(TextKey::SelectDateNoData, &[&date]),
+    }
+}
+
+// Maps a score onto a red (0) to green (100+) background color.
+fn score_color(score: i64) -> String {
+    format!("hsl({}, 70%, 80%)", score.clamp(0, 100) * 120 / 100)
+}
+
+pub fn view_streaks(streaks: &Streaks, lang: Lang) -> Node<Msg> {
+    p![
+        style! {St::FontSize=>"1.5em"},
+        lang.trf(
+            TextKey::Streak,
+            &[
+                &lang.count(
+                    streaks.current,
+                    TextKey::DayCountOne,
+                    TextKey::DayCountOther
+                ),
+                &streaks.longest
+            ]
+        ),
+    ]
+}
+
+// `day` is the day's data and its score, if it has any.
+// Kept to the date, work count and score; the rest of the day is in its details.
+fn view_work_sleep_data_one_day(
+    date: NaiveDate,
+    day: Option<(&WorkSleep, i64)>,
+    data: &Data,
+    is_current_date: bool,
+    is_archived: bool,
+    is_expanded: bool,
+    tasks_remaining: usize,
+) -> Node<Msg> {
+    let defaults = &data.default_work_sleep_goals;
+    let lang = data.lang;
+    let ws = day.map(|(ws, _)| ws);
+    let background = day.map_or(NO_DATA_COLOR.to_owned(), |(_, score)| score_color(score));
+    td![
+        style! {St::Background=>background},
+        IF!(is_weekend(date) => C!["weekend"]),
+        IF!(is_weekend(date) => style! {St::BorderBottom=>"3px solid #7e57c2"}),
+        IF!(is_current_date => attrs! {At::AriaCurrent=>"date"}),
+        IF!(is_current_date => vec![span![lang.tr(TextKey::CurrentDay)], br![]]),
+        button![
+            data.fmt_date(date),
+            attrs! {At::AriaLabel=>date_button_label(date, day.map(|(_, score)| score), lang)},
+            ev(Ev::Click, move |_| Msg::SetCurrentDate(date)),
+        ],
+        br![],
+        IF!(is_current_date || ws.is_some() => {
+            let empty = WorkSleep::new();
+            let ws = ws.unwrap_or(&empty);
+            let target = ws.effective_goals(defaults).clamped().target_work_count;
+            let count = format!("{}/{}", format_work_count(ws.weighted_work_count()), target);
+            if is_current_date {
+                view_work_progress_bar(&ws.work_progress(defaults, tasks_remaining), count)
+            } else {
+                div![small![count]]
+            }
+        }),
+        span![match day {
+            Some((_, score)) => lang.trf(TextKey::Score, &[&score]),
+            None if is_archived => lang.tr(TextKey::Archived).to_owned(),
+            None => lang.tr(TextKey::NoDataDay).to_owned(),
+        }],
+        br![],
+        button![
+            lang.tr(if is_expanded {
+                TextKey::HideDetails
+            } else {
+                TextKey::Details
+            }),
+            attrs! {
+                At::AriaExpanded=>is_expanded,
+                At::AriaControls=>"day-details",
+                At::AriaLabel=>lang.trf(TextKey::DetailsOf, &[&lang.long_date(date)]),
+            },
+            ev(Ev::Click, move |_| Msg::ToggleDayDetails(date)),
+        ],
+    ]
+}
+
+// The drawer under the week with everything about one day, and ways to change it.
+fn view_day_details(model: &Model, date: NaiveDate) -> Node<Msg> {
+    let data = &model.data;
+    let defaults = &data.default_work_sleep_goals;
+    let tasks_remaining = data.plan(date).len();
+    let lang = data.lang;
+    section![
+        attrs! {At::Id=>"day-details"},
+        style! {St::Border=>"1px solid #999", St::Padding=>"0.5em", St::Margin=>"0.5em 0"},
+        h3![lang.trf(TextKey::DetailsOf, &[&data.fmt_date(date)])],
+        button![
+            lang.tr(TextKey::Close),
+            ev(Ev::Click, move |_| Msg::ToggleDayDetails(date))
+        ],
+        IF!(tasks_remaining > 0 => p![lang.trf(TextKey::TasksPlanned, &[&tasks_remaining])]),
+        match data.work_sleep_data.get(&date) {
+            Some(ws) => {
+                let score = data.work_sleep_data.score(date, ws, defaults);
+                view_day_details_data(model, date, ws, score)
+            }
+            None if data.is_archived(date) => p![lang.tr(TextKey::DayWasArchived)],
+            None if date > model.today => p![lang.tr(TextKey::NothingToRecordYet)],
+            None => p![
+                lang.tr(TextKey::NoDataForDay),
+                button![
+                    lang.tr(TextKey::CreateEntry),
+                    ev(Ev::Click, move |_| Msg::CreateDayEntry(date))
+                ],
+            ],
+        },
+    ]
+}
+
+fn view_day_details_data(model: &Model, date: NaiveDate, ws: &WorkSleep, score: i64) -> Node<Msg> {
+    let data = &model.data;
+    let defaults = &data.default_work_sleep_goals;
+    let goals = ws.effective_goals(defaults).clamped();
+    let editable = date <= model.today;
+    let lang = data.lang;
+    div![
+        p![
+            lang.trf(
+                TextKey::DayGoals,
+                &[
+                    &goals.target_work_count,
+                    &data.fmt_bedtime(&goals.target_bedtime),
+                    &goals.work_sleep_balance
+                ]
+            ),
+            if ws.weekend_preset {
+                lang.tr(TextKey::WeekendGoalsSuffix)
+            } else if ws.goals.is_some() {
+                lang.tr(TextKey::CustomGoalsSuffix)
+            } else {
+                ""
+            },
+            IF!(date == data.current_date => button![
+                lang.tr(TextKey::EditDayGoals),
+                ev(Ev::Click, |_| Msg::ToggleDayGoalsEditor)
+            ]),
+        ],
+        p![
+            lang.trf(
+                TextKey::WorkCompleted,
+                &[&format_work_count(ws.weighted_work_count())]
+            ),
+            IF!(editable => view_work_count_adjusters(date)),
+            IF!(ws.work_count_edited => lang.tr(TextKey::Edited)),
+            IF!(ws.tracked_minutes() > 0 => vec![
+                br![],
+                span![lang.trf(TextKey::TimeTracked, &[&ws.tracked_minutes()])],
+            ]),
+        ],
+        p![
+            label![attrs! {At::For=>"day-bedtime"}, lang.tr(TextKey::Bedtime)],
+            input![
+                attrs! {
+                    At::Id=>"day-bedtime",
+                    At::Placeholder=>data.clock_format.example(),
+                    At::Value=>ws.actual_bedtime.as_ref().map_or(String::new(), |bedtime| {
+                        data.fmt_time(bedtime.time)
+                    }),
+                    At::Disabled=>(!editable).as_at_value(),
+                },
+                input_ev(Ev::Change, move |s| Msg::SetDayBedtime(date, s)),
+            ],
+            ws.actual_bedtime
+                .as_ref()
+                .filter(|bedtime| bedtime.next_day)
+                .map(|_| span![lang.tr(TextKey::NextDay)]),
+            ws.wake_time.as_ref().map(|wake_time| vec![
+                br![],
+                span![lang.trf(TextKey::WokeUp, &[&data.fmt_time(wake_time.time)])],
+            ]),
+            ws.sleep_minutes().map(|minutes| {
+                vec![
+                    br![],
+                    span![lang.trf(TextKey::Slept, &[&format_sleep(minutes)])],
+                ]
+            }),
+            ws.mood.map(|mood| {
+                // Imported data could have any value in there.
+                let emoji = usize::from(mood)
+                    .checked_sub(1)
+                    .and_then(|i| MOOD_EMOJIS.get(i))
+                    .unwrap_or(&"");
+                vec![br![], span![lang.trf(TextKey::Mood, &[emoji, &mood])]]
+            }),
+        ],
+        p![
+            span![lang.trf(TextKey::Score, &[&score])],
+            br![],
+            view_score_breakdown(&ws.score_breakdown(defaults), lang),
+        ],
+        view_completed_periods(&ws.completed, data),
+    ]
+}
+
+// Completed work fills the bar in green and planned work in grey, up to the target.
+// Past the target the bar turns gold rather than growing out of the cell.
+fn view_work_progress_bar(progress: &WorkProgress, count: String) -> Node<Msg> {
+    let percent = |fraction: f64| format!("{:.1}%", fraction.max(0.0) * 100.0);
+    let completed = progress.completed.min(1.0);
+    let planned = progress.planned.min(1.0 - completed);
+    let completed_color = if progress.completed > 1.0 {
+        "#d4a017"
+    } else {
+        "#4caf50"
+    };
+    div![
+        div![
+            style! {
+                St::Display=>"flex",
+                St::Width=>"100%",
+                St::Height=>"0.6em",
+                St::Overflow=>"hidden",
+                St::Background=>"#fff",
+                St::Border=>"1px solid #888",
+            },
+            div![style! {St::Width=>percent(completed), St::Background=>completed_color}],
+            div![style! {St::Width=>percent(planned), St::Background=>"#bbb"}],
+        ],
+        small![count],
+    ]
+}
+
+fn view_score_breakdown(breakdown: &ScoreBreakdown, lang: Lang) -> Node<Msg> {
+    let bedtime = match breakdown.bedtime_deviation {
+        Some(0) => lang.tr(TextKey::BreakdownOnTarget).to_owned(),
+        Some(minutes) if minutes > 0 => lang.trf(TextKey::BreakdownPastTarget, &[&minutes]),
+        Some(minutes) => lang.trf(TextKey::BreakdownBeforeTarget, &[&-minutes]),
+        None => String::new(),
+    };
+    small![lang.trf(
+        TextKey::Breakdown,
+        &[
+            &breakdown.work_score,
+            &breakdown.work_points,
+            &breakdown.sleep_score,
+            &breakdown.sleep_points,
+            &bedtime
+        ]
+    )]
+}
+
+fn view_work_count_adjusters(date: NaiveDate) -> Node<Msg> {
+    span![
+        button!["−", ev(Ev::Click, move |_| Msg::AdjustWorkCount(date, -1))],
+        button!["+", ev(Ev::Click, move |_| Msg::AdjustWorkCount(date, 1))],
+    ]
+}
+
+fn view_completed_periods(completed: &[CompletedPeriod], data: &Data) -> Node<Msg> {
+    if completed.is_empty() {
+        empty![]
+    } else {
+        ul![completed.iter().map(|cp| li![
+            format!("{} {}", data.fmt_time(cp.completed_at), cp.name),
+            cp.duration_minutes
+                .map(|minutes| format!(" ({} min)", minutes))
+                .unwrap_or_default(),
+            IF!(!cp.note.is_empty() => {
+                let preview = note_preview(&cp.note, NOTE_PREVIEW_CHARS);
+                if preview == cp.note {
+                    div![small![preview]]
+                } else {
+                    details![summary![small![preview]], pre![&cp.note]]
+                }
+            }),
+        ])]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::text_content;
+    use super::*;
+    use crate::achievements::Achievement;
+
+    #[test]
+    fn work_progress_is_shown_as_a_count() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        let yesterday = today.pred_opt().unwrap();
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&yesterday)
+            .actual_work_count = 4;
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("4/6"));
+        assert!(text.contains("0/6"));
+    }
+
+    #[test]
+    fn week_has_weekday_headers_and_iso_week_numbers() {
+        // A Thursday, so the week shown runs from Friday to Thursday.
+        let today = NaiveDate::from_ymd_opt(2021, 5, 6).unwrap();
+        let model = Model::on(today);
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Weeks 17–18FriSatSunMonTueWedThu"));
+    }
+
+    #[test]
+    fn archived_weeks_read_like_their_live_summary() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 5).unwrap();
+        let mut model = Model::on(today);
+        for (day, work) in [(1, 3), (3, 5)] {
+            let date = NaiveDate::from_ymd_opt(2021, 4, day).unwrap();
+            let ws = model.data.work_sleep_data.get_mut_or_create(&date);
+            ws.actual_work_count = work;
+            ws.actual_bedtime = Some(Bedtime {
+                time: chrono::NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+                next_day: false,
+            });
+        }
+        model.data.work_sleep_data.week_start = NaiveDate::from_ymd_opt(2021, 4, 1).unwrap();
+        let live = model
+            .data
+            .work_sleep_data
+            .week_summary(&model.data.default_work_sleep_goals);
+
+        model.data.archive_weeks(today, 7);
+        let archived = text_content(&view_week(&model));
+        assert!(archived.starts_with("2021-04-01 – 2021-04-07"));
+        assert!(archived.contains("Archived week: 2021-04-01 – 2021-04-07"));
+        assert!(archived.contains("Work done: 8Nights with a bedtime: 2"));
+        assert!(!archived.contains("No data"));
+        assert!(archived.contains(&format!("Total score: {}", live.total_score)));
+        assert!(archived.contains(&format_average_score(live.average_score(), Lang::English)));
+    }
+
+    #[test]
+    fn narrow_screens_show_days_as_cards() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 6).unwrap());
+        model.viewport_width = Some(400);
+        let text = text_content(&view_week(&model));
+        assert!(!text.contains("FriSatSun"));
+        assert!(text.contains("2021-05-06"));
+    }
+
+    #[test]
+    fn week_shows_the_score_of_each_recorded_day() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&today)
+            .actual_work_count = 3;
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Score: 35"));
+        assert!(!text.contains("Work Completed"));
+
+        model.expanded_day = Some(today);
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Hide details"));
+        assert!(text.contains("Work Completed: 3−+"));
+        assert!(text.contains("Goals: 6 work periods"));
+    }
+
+    #[test]
+    fn details_of_a_day_without_data_offer_to_create_it() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        model.expanded_day = today.pred_opt();
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Details of 2021-05-02"));
+        assert!(text.contains("No data for this day. Create an entry"));
+
+        // Days outside the week shown keep their details closed.
+        model.expanded_day = NaiveDate::from_ymd_opt(2021, 4, 1);
+        assert!(!text_content(&view_week(&model)).contains("Details of"));
+    }
+
+    #[test]
+    fn week_marks_days_that_took_the_weekend_goals() {
+        // A Monday, so the week shown runs from Tuesday to Monday.
+        let today = NaiveDate::from_ymd_opt(2021, 5, 10).unwrap();
+        let mut model = Model::on(today);
+        model.data.weekend_goals = Some(model.data.default_work_sleep_goals.clone());
+        model
+            .data
+            .day_mut(NaiveDate::from_ymd_opt(2021, 5, 8).unwrap());
+        model.data.day_mut(today);
+        model.expanded_day = NaiveDate::from_ymd_opt(2021, 5, 8);
+        assert!(text_content(&view_week(&model)).contains("(weekend goals)"));
+        model.expanded_day = Some(today);
+        assert!(!text_content(&view_week(&model)).contains("(weekend goals)"));
+    }
+
+    #[test]
+    fn week_is_compared_with_the_week_before() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("no prior data"));
+
+        let work_sleep_data = &mut model.data.work_sleep_data;
+        work_sleep_data.get_mut_or_create(&today).actual_work_count = 5;
+        work_sleep_data
+            .get_mut_or_create(&shift_days(today, -7))
+            .actual_work_count = 2;
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Work periods52▲ +3"));
+    }
+
+    #[test]
+    fn date_buttons_are_labelled_with_the_weekday_and_score() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(
+            date_button_label(date, Some(84), Lang::English),
+            "Select March 4, Monday, score 84"
+        );
+        assert_eq!(
+            date_button_label(date, None, Lang::English),
+            "Select March 4, Monday, no data"
+        );
+        assert_eq!(
+            date_button_label(date, None, Lang::Spanish),
+            "Seleccionar 4 de marzo, lunes, sin datos"
+        );
+    }
+
+    #[test]
+    fn year_heatmap_has_a_square_per_day() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&today)
+            .actual_work_count = 3;
+        let text = text_content(&view_year_heatmap(&model));
+        assert_eq!(text.matches("no data").count(), 364);
+        assert!(text.contains("2020-05-04: no data"));
+        assert!(text.ends_with("2021-05-03: 35"));
+    }
+
+    #[test]
+    fn all_time_stats_show_the_best_and_worst_days() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        assert!(text_content(&view_all_time_stats(&model)).contains("Nothing has been recorded"));
+
+        let work_sleep_data = &mut model.data.work_sleep_data;
+        let mut day = |date: NaiveDate, work, minute| {
+            let ws = work_sleep_data.get_mut_or_create(&date);
+            ws.actual_work_count = work;
+            ws.actual_bedtime = Some(Bedtime {
+                time: chrono::NaiveTime::from_hms_opt(23, minute, 0).unwrap(),
+                next_day: false,
+            });
+        };
+        day(shift_days(today, -2), 6, 0);
+        day(shift_days(today, -1), 3, 30);
+        work_sleep_data.get_mut_or_create(&today).actual_work_count = 1;
+        let text = text_content(&view_all_time_stats(&model));
+        assert!(text.contains("Days tracked3"));
+        assert!(text.contains("Best day100 on 2021-05-01"));
+        assert!(text.contains("Worst day50 on 2021-05-02"));
+        assert!(text.contains("Average bedtime15 min after the target"));
+        assert!(text.contains("over the 2 of 3 days"));
+    }
+
+    #[test]
+    fn week_reviews_show_the_notes_of_the_week_shown() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 9).unwrap());
+        let week_start = model.data.work_sleep_data.week_start;
+        model
+            .data
+            .set_weekly_note(week_start, "Slept late on Friday".to_owned());
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Review notesSlept late on Friday"));
+        assert!(text.contains("Review this week"));
+
+        model.week_review_open = true;
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Week review"));
+        assert!(text.contains("Sun 2021-05-09: no data"));
+        assert!(!text.contains("Slept late on Friday"));
+
+        // Aligned to start on Wednesdays, the week before holds the note's start date.
+        model.week_review_open = false;
+        model.data.work_sleep_data.week_start = NaiveDate::from_ymd_opt(2021, 4, 28).unwrap();
+        let text = text_content(&view_week(&model));
+        assert!(text.contains("Review notes for the week from 2021-05-03Slept late on Friday"));
+    }
+
+    #[test]
+    fn badges_show_what_they_take_until_earned() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 5).unwrap());
+        model.data.achievements.push(Achievement {
+            badge: Badge::WeekStreak,
+            earned_on: NaiveDate::from_ymd_opt(2021, 5, 2).unwrap(),
+        });
+        let text = text_content(&view_badges(&model.data));
+        assert!(text.contains("🔒 First perfect day: Score 100 or more in a day"));
+        assert!(text
+            .contains("🏆 7-day streak: Meet both targets 7 days in a row (earned on 2021-05-02)"));
+    }
+
+    #[test]
+    fn the_week_is_shown_in_the_language_chosen() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 6).unwrap());
+        model.data.lang = Lang::Spanish;
+        model.data.date_format = crate::model::DateFormat::DayMonth;
+        let text = text_content(&view_week(&model));
+        assert!(text.starts_with("30 abr – 6 may"));
+        assert!(text.contains("Semanas 17–18viesábdomlunmarmiéjueSemana"));
+        assert!(text.contains("DÍA ACTUAL"));
+        assert!(!text.contains("Previous Week"));
+
+        model.history_view = HistoryView::Month;
+        assert!(text_content(&view_work_sleep_data(&model)).contains("abril de 2021"));
+    }
+}
@@ -0,0 +1,932 @@
+// `view` and the parts of the page that don't belong to one area.
+
+mod goals;
+mod history;
+mod tasks;
+
+use seed::{prelude::*, *};
+use web_sys::HtmlInputElement;
+
+use crate::app::update::Msg;
+use crate::app::{Model, Notice, Page, PendingImport, Severity};
+use crate::i18n::{Lang, TextKey};
+use crate::merge::GoalsSource;
+use crate::model::{
+    format_hours_minutes, format_sleep, format_work_count, Bedtime, BedtimeCountdown, ClockFormat,
+    DateFormat, Theme, WorkSleep, MOOD_EMOJIS,
+};
+
+use goals::{view_current_date_goals, view_onboarding};
+use history::{view_streaks, view_work_sleep_data};
+use tasks::{view_current_date_planning, view_focus, view_recurring_tasks};
+
+// `view` describes what to display.
+pub fn view(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    // Another tab may have finished the setup already.
+    if let Some(onboarding) = model.onboarding.as_ref().filter(|_| !model.data.onboarded) {
+        return div![
+            view_notices(&model.notices),
+            view_onboarding(onboarding, &model.data.default_work_sleep_goals, lang),
+        ];
+    }
+    if model.focus_mode {
+        return div![
+            view_notices(&model.notices),
+            view_announcement(&model.announcement),
+            view_focus(model),
+        ];
+    }
+    div![
+        view_notices(&model.notices),
+        view_announcement(&model.announcement),
+        IF!(model.reset_backup.is_some() => div![
+            style! {St::Background=>"#f8d7da", St::Padding=>"0.5em"},
+            lang.tr(TextKey::AllDataDeleted),
+            button![lang.tr(TextKey::Undo), ev(Ev::Click, |_| Msg::UndoResetAllData)],
+        ]),
+        IF!(model.demo => div![
+            style! {St::Background=>"#fff3cd", St::Padding=>"0.5em"},
+            lang.tr(TextKey::DemoData),
+            button![lang.tr(TextKey::BackToMyData), ev(Ev::Click, |_| Msg::LeaveDemo)],
+        ]),
+        view_shortcut_help(model),
+        IF!(model.pomodoro_finished => div![
+            style! {St::Background=>"gold", St::Padding=>"0.5em"},
+            lang.tr(TextKey::PomodoroFinished),
+            button![lang.tr(TextKey::Dismiss), ev(Ev::Click, |_| Msg::PomodoroFinishedDismissed)],
+        ]),
+        IF!(model.target_reached => div![
+            style! {St::Background=>"#d4edda", St::Padding=>"0.5em"},
+            lang.tr(TextKey::TargetReached),
+        ]),
+        view_nav(
+            model.page,
+            model.data.theme,
+            model.bedtime_countdown(),
+            model.dirty,
+            lang
+        ),
+        match model.page {
+            Page::Tracking => view_current_date_reality(model),
+            Page::History => view_work_sleep_data(model),
+            Page::Settings => view_settings(model),
+            Page::NotFound => view_not_found(lang),
+        },
+    ]
+}
+
+fn view_notices<'a>(notices: impl IntoIterator<Item = &'a Notice>) -> Node<Msg> {
+    div![
+        style! {
+            St::Position=>"fixed",
+            St::Top=>px(8),
+            St::Right=>px(8),
+            St::ZIndex=>"1",
+            St::MaxWidth=>"20em",
+        },
+        attrs! {At::AriaLive=>"polite"},
+        notices.into_iter().map(|notice| {
+            let id = notice.id;
+            let background = match notice.severity {
+                Severity::Info => "#e3f2fd",
+                Severity::Warning => "#fff3cd",
+                Severity::Error => "#f8d7da",
+            };
+            div![
+                style! {
+                    St::Background=>background,
+                    St::Border=>"1px solid gray",
+                    St::Padding=>px(8),
+                    St::MarginBottom=>px(4),
+                },
+                span![&notice.message],
+                notice.action.as_ref().map(|(label, _)| {
+                    button![label, ev(Ev::Click, move |_| Msg::NoticeActionClicked(id))]
+                }),
+                button!["×", ev(Ev::Click, move |_| Msg::DismissNotice(id))],
+            ]
+        }),
+    ]
+}
+
+// Read out by screen readers, but not shown.
+fn view_announcement(announcement: &str) -> Node<Msg> {
+    div![
+        style! {
+            St::Position=>"absolute",
+            St::Width=>px(1),
+            St::Height=>px(1),
+            St::Overflow=>"hidden",
+            St::Clip=>"rect(0 0 0 0)",
+        },
+        attrs! {At::AriaLive=>"polite"},
+        announcement,
+    ]
+}
+
+// `dirty` marks changes that couldn't be saved yet.
+fn view_nav(
+    current: Page,
+    theme: Theme,
+    countdown: Option<BedtimeCountdown>,
+    dirty: bool,
+    lang: Lang,
+) -> Node<Msg> {
+    let link = |label: TextKey, page: Page| {
+        let label = lang.tr(label);
+        if page == current {
+            strong![label]
+        } else {
+            a![attrs! {At::Href=>page.path()}, label]
+        }
+    };
+    nav![
+        style! {St::Display=>"flex", St::Gap=>"1em"},
+        link(TextKey::Tracking, Page::Tracking),
+        link(TextKey::History, Page::History),
+        link(TextKey::Settings, Page::Settings),
+        button![
+            lang.trf(TextKey::ThemeButton, &[&lang.tr(theme.label())]),
+            ev(Ev::Click, move |_| Msg::SetTheme(theme.next())),
+        ],
+        countdown.map(|countdown| view_bedtime_countdown(countdown, lang)),
+        IF!(dirty => span![
+            style! {St::Color=>"crimson"},
+            attrs! {
+                At::Title=>lang.tr(TextKey::UnsavedChanges),
+                At::AriaLabel=>lang.tr(TextKey::UnsavedChanges),
+            },
+            "●"
+        ]),
+    ]
+}
+
+fn view_bedtime_countdown(countdown: BedtimeCountdown, lang: Lang) -> Node<Msg> {
+    match countdown {
+        BedtimeCountdown::Left(minutes) => span![lang.trf(
+            TextKey::UntilTargetBedtime,
+            &[&format_hours_minutes(minutes)]
+        )],
+        BedtimeCountdown::Past(minutes) => span![
+            style! {St::Color=>"red", St::FontWeight=>"bold"},
+            lang.trf(
+                TextKey::PastTargetBedtime,
+                &[&format_hours_minutes(minutes)]
+            )
+        ],
+    }
+}
+
+fn view_not_found(lang: Lang) -> Node<Msg> {
+    div![
+        h2![lang.tr(TextKey::PageNotFound)],
+        a![
+            attrs! {At::Href=>Page::Tracking.path()},
+            lang.tr(TextKey::BackToTracking)
+        ],
+    ]
+}
+
+fn view_shortcut_help(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        button!["?", ev(Ev::Click, |_| Msg::ToggleShortcutHelp)],
+        IF!(model.show_shortcut_help => div![
+            style! {St::Position=>"absolute", St::Background=>"white", St::Border=>"1px solid gray", St::Padding=>px(8)},
+            h3![lang.tr(TextKey::KeyboardShortcuts)],
+            ul![[
+                TextKey::ShortcutFinish,
+                TextKey::ShortcutNewTask,
+                TextKey::ShortcutWeeks,
+                TextKey::ShortcutUndo,
+                TextKey::ShortcutRedo,
+                TextKey::ShortcutFocus,
+                TextKey::ShortcutHelp,
+            ]
+            .iter()
+            .map(|key| li![lang.tr(*key)])],
+        ]),
+    ]
+}
+
+fn view_import_export(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        h2![lang.tr(TextKey::Data)],
+        button![lang.tr(TextKey::Export), ev(Ev::Click, |_| Msg::ExportData)],
+        label![lang.tr(TextKey::Import)],
+        input![
+            attrs! {At::Type=>"file", At::Accept=>"application/json,.json"},
+            ev(Ev::Change, |event| {
+                event
+                    .target()
+                    .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                    .and_then(|input| input.files())
+                    .and_then(|files| files.get(0))
+                    .map(Msg::ImportFileChosen)
+            })
+        ],
+        label![
+            input![
+                attrs! {At::Type=>"checkbox", At::Checked=>model.import_merge.as_at_value()},
+                ev(Ev::Change, |_| Msg::ToggleImportMerge),
+            ],
+            lang.tr(TextKey::ImportMerge),
+        ],
+        model
+            .import_error
+            .as_ref()
+            .map(|err| p![style! {St::Color=>"red"}, err]),
+        model
+            .pending_import
+            .as_ref()
+            .map(|pending| view_merge_preview(pending, lang)),
+        view_sleep_csv_import(model),
+        view_archive(model),
+        view_week_archive(model),
+    ]
+}
+
+fn view_merge_preview(pending: &PendingImport, lang: Lang) -> Node<Msg> {
+    let option = |label: TextKey, goals: GoalsSource| {
+        label![
+            input![
+                attrs! {
+                    At::Type=>"radio",
+                    At::Name=>"merge-goals",
+                    At::Checked=>(pending.goals == goals).as_at_value(),
+                },
+                ev(Ev::Change, move |_| Msg::ImportGoalsChosen(goals)),
+            ],
+            lang.tr(label),
+        ]
+    };
+    div![
+        h3![lang.tr(TextKey::MergePreview)],
+        p![pending.summary.describe(lang)],
+        p![small![lang.tr(TextKey::MergeRules)]],
+        fieldset![
+            legend![lang.tr(TextKey::MergeGoals)],
+            option(TextKey::KeepLocalGoals, GoalsSource::Local),
+            br![],
+            option(TextKey::UseImportedGoals, GoalsSource::Imported),
+        ],
+        button![
+            lang.tr(TextKey::Merge),
+            ev(Ev::Click, |_| Msg::ConfirmImport)
+        ],
+        button![
+            lang.tr(TextKey::Cancel),
+            ev(Ev::Click, |_| Msg::CancelImport)
+        ],
+    ]
+}
+
+fn view_sleep_csv_import(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        h3![lang.tr(TextKey::SleepCsvTitle)],
+        p![lang.tr(TextKey::SleepCsvHelp)],
+        input![
+            attrs! {At::Type=>"file", At::Accept=>"text/csv,.csv"},
+            ev(Ev::Change, |event| {
+                event
+                    .target()
+                    .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                    .and_then(|input| input.files())
+                    .and_then(|files| files.get(0))
+                    .map(Msg::SleepCsvChosen)
+            })
+        ],
+        label![
+            input![
+                attrs! {At::Type=>"checkbox", At::Checked=>model.sleep_csv_overwrite.as_at_value()},
+                ev(Ev::Change, |_| Msg::ToggleSleepCsvOverwrite),
+            ],
+            lang.tr(TextKey::SleepCsvOverwrite),
+        ],
+    ]
+}
+
+fn view_archive(model: &Model) -> Node<Msg> {
+    let work_sleep_data = &model.data.work_sleep_data;
+    let lang = model.data.lang;
+    div![
+        h3![lang.tr(TextKey::Archive)],
+        p![lang.tr(TextKey::ArchiveHelp)],
+        label![lang.tr(TextKey::ArchiveBefore)],
+        input![
+            attrs! {
+                At::Type=>"date",
+                At::Max=>model.today.to_string(),
+                At::Value=>model.archive_cutoff.map_or(String::new(), |cutoff| cutoff.to_string()),
+            },
+            input_ev(Ev::Input, Msg::ArchiveCutoffChanged),
+        ],
+        button![
+            lang.tr(TextKey::ArchiveAndDownload),
+            attrs! {At::Disabled=>model.archive_cutoff.is_none().as_at_value()},
+            ev(Ev::Click, |_| Msg::ArchiveHistory)
+        ],
+        work_sleep_data.archived_before.map(|cutoff| {
+            p![lang.trf(
+                TextKey::DaysArchivedBefore,
+                &[&work_sleep_data.archived_days, &cutoff]
+            )]
+        }),
+    ]
+}
+
+// Previews exactly the weeks that archiving would compact.
+fn view_week_archive(model: &Model) -> Node<Msg> {
+    let data = &model.data;
+    let lang = data.lang;
+    let preview = data.weeks_to_archive(model.today, model.week_archive_days);
+    let days: usize = preview
+        .iter()
+        .map(|archive| {
+            data.work_sleep_data
+                .days_in(archive.start, archive.end())
+                .count()
+        })
+        .sum();
+    div![
+        h3![lang.tr(TextKey::ArchiveOldWeeks)],
+        p![lang.tr(TextKey::WeekArchiveHelp)],
+        label![
+            attrs! {At::For=>"week-archive-days"},
+            lang.tr(TextKey::WeekArchiveDays)
+        ],
+        input![
+            attrs! {At::Id=>"week-archive-days", At::Type=>"number", At::Min=>0, At::Value=>model.week_archive_days},
+            input_ev(Ev::Input, Msg::WeekArchiveDaysChanged),
+        ],
+        match (preview.first(), preview.last()) {
+            (Some(first), Some(last)) => p![lang.trf(
+                TextKey::WeeksToCompact,
+                &[
+                    &lang.count(
+                        preview.len(),
+                        TextKey::WeekCountOne,
+                        TextKey::WeekCountOther
+                    ),
+                    &data.fmt_date(first.start),
+                    &data.fmt_date(last.end()),
+                    &lang.count(days, TextKey::DayCountOne, TextKey::DayCountOther),
+                ]
+            )],
+            _ => p![lang.tr(TextKey::NoWeeksToArchive)],
+        },
+        button![
+            lang.tr(TextKey::ArchiveOldWeeks),
+            attrs! {At::Disabled=>preview.is_empty().as_at_value()},
+            ev(Ev::Click, |_| Msg::ArchiveOldWeeks)
+        ],
+    ]
+}
+
+fn view_settings(model: &Model) -> Node<Msg> {
+    div![
+        view_current_date_goals(model),
+        view_display_settings(model),
+        view_recurring_tasks(model),
+        view_import_export(model),
+        view_remote_sync(model),
+        view_danger_zone(model),
+    ]
+}
+
+fn view_display_settings(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        h2![lang.tr(TextKey::Display)],
+        label![attrs! {At::For=>"lang"}, lang.tr(TextKey::Language)],
+        select![
+            attrs! {At::Id=>"lang"},
+            Lang::ALL.iter().enumerate().map(|(i, option)| {
+                option![
+                    attrs! {At::Value=>i, At::Selected=>(*option == lang).as_at_value()},
+                    option.label(),
+                ]
+            }),
+            input_ev(Ev::Change, |value| {
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| Lang::ALL.get(i))
+                    .map(|lang| Msg::LangChanged(*lang))
+            }),
+        ],
+        label![attrs! {At::For=>"date-format"}, lang.tr(TextKey::Dates)],
+        select![
+            attrs! {At::Id=>"date-format"},
+            DateFormat::ALL.iter().enumerate().map(|(i, format)| {
+                option![
+                    attrs! {At::Value=>i, At::Selected=>(*format == model.data.date_format).as_at_value()},
+                    format.label(lang),
+                ]
+            }),
+            input_ev(Ev::Change, |value| {
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| DateFormat::ALL.get(i))
+                    .map(|format| Msg::DateFormatChanged(*format))
+            }),
+        ],
+        label![attrs! {At::For=>"clock-format"}, lang.tr(TextKey::Times)],
+        select![
+            attrs! {At::Id=>"clock-format"},
+            ClockFormat::ALL.iter().enumerate().map(|(i, format)| {
+                option![
+                    attrs! {At::Value=>i, At::Selected=>(*format == model.data.clock_format).as_at_value()},
+                    lang.tr(format.label()),
+                ]
+            }),
+            input_ev(Ev::Change, |value| {
+                value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| ClockFormat::ALL.get(i))
+                    .map(|format| Msg::ClockFormatChanged(*format))
+            }),
+        ],
+    ]
+}
+
+fn view_remote_sync(model: &Model) -> Node<Msg> {
+    let settings = &model.remote_sync;
+    let lang = model.data.lang;
+    div![
+        h2![lang.tr(TextKey::Sync)],
+        p![lang.tr(TextKey::SyncHelp)],
+        label![
+            attrs! {At::For=>"remote-sync-url"},
+            lang.tr(TextKey::SyncUrl)
+        ],
+        input![
+            attrs! {At::Id=>"remote-sync-url", At::Type=>"url", At::Placeholder=>"https://", At::Value=>settings.url},
+            input_ev(Ev::Input, Msg::RemoteSyncUrlChanged),
+        ],
+        label![
+            attrs! {At::For=>"remote-sync-token"},
+            lang.tr(TextKey::BearerToken)
+        ],
+        input![
+            attrs! {At::Id=>"remote-sync-token", At::Type=>"password", At::Value=>settings.token},
+            input_ev(Ev::Input, Msg::RemoteSyncTokenChanged),
+        ],
+        label![
+            attrs! {At::For=>"remote-sync-every"},
+            lang.tr(TextKey::SyncEvery)
+        ],
+        input![
+            attrs! {At::Id=>"remote-sync-every", At::Type=>"number", At::Min=>0, At::Value=>settings.sync_every},
+            input_ev(Ev::Input, Msg::RemoteSyncEveryChanged),
+        ],
+        button![
+            lang.tr(if model.remote_sync_in_flight {
+                TextKey::Syncing
+            } else {
+                TextKey::SyncNow
+            }),
+            attrs! {At::Disabled=>(!model.remote_sync_enabled() || model.remote_sync_in_flight).as_at_value()},
+            ev(Ev::Click, |_| Msg::SyncNow)
+        ],
+        model
+            .last_remote_sync
+            .map(|at| p![lang.trf(TextKey::LastSynced, &[&model.data.fmt_time(at.time())])]),
+    ]
+}
+
+fn view_danger_zone(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        h2![lang.tr(TextKey::DangerZone)],
+        match &model.reset_confirmation {
+            None => button![
+                lang.tr(TextKey::DeleteAllData),
+                ev(Ev::Click, |_| Msg::ShowResetAllData)
+            ],
+            Some(typed) => div![
+                p![lang.tr(TextKey::DeleteAllDataConfirm)],
+                input![
+                    attrs! {At::Value=>typed},
+                    input_ev(Ev::Input, Msg::ResetConfirmationChanged),
+                ],
+                button![
+                    lang.tr(TextKey::DeleteAllData),
+                    attrs! {At::Disabled=>(typed != "DELETE").as_at_value()},
+                    ev(Ev::Click, |_| Msg::ResetAllData),
+                ],
+                button![
+                    lang.tr(TextKey::Cancel),
+                    ev(Ev::Click, |_| Msg::CancelResetAllData)
+                ],
+            ],
+        },
+    ]
+}
+
+fn view_current_date_reality(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        h2![if model.data.current_date == model.today {
+            lang.tr(TextKey::Today).to_owned()
+        } else {
+            model.data.fmt_date(model.data.current_date)
+        }],
+        IF!(model.is_planning() => p![
+            style! {St::Background=>"#e3f2fd", St::Padding=>"0.5em"},
+            lang.tr(TextKey::PlanningAhead),
+        ]),
+        view_streaks(
+            &model.data.work_sleep_data.streaks(
+                model.data.current_date,
+                &model.data.default_work_sleep_goals,
+            ),
+            lang
+        ),
+        view_day_review(model),
+        view_current_date_planning(model),
+        br![],
+        view_current_date_bedtime(model),
+        view_wake_time(model),
+        view_mood_picker(model),
+        br![],
+        view_current_date_score_calculation(model),
+    ]
+}
+
+// Shown on finishing the last planned task of the day.
+fn view_day_review(model: &Model) -> Node<Msg> {
+    let date = model.data.current_date;
+    let shown_for_date = model
+        .day_review_shown
+        .is_some_and(|(shown, _)| shown == date);
+    if !model.day_review_open || !shown_for_date {
+        return empty![];
+    }
+    let defaults = &model.data.default_work_sleep_goals;
+    let empty = WorkSleep::new();
+    let ws = model.data.work_sleep_data.get(&date).unwrap_or(&empty);
+    let target = ws.effective_goals(defaults).target_work_count;
+    let score = model.data.work_sleep_data.score(date, ws, defaults);
+    let lang = model.data.lang;
+    div![
+        style! {St::Border=>"1px solid gray", St::Padding=>"0.5em 1em", St::MarginBottom=>"1em"},
+        h3![lang.tr(TextKey::DayReviewTitle)],
+        p![lang.trf(
+            TextKey::DayReviewSummary,
+            &[
+                &format_work_count(ws.weighted_work_count()),
+                &target,
+                &score
+            ]
+        )],
+        model
+            .bedtime_countdown()
+            .filter(|_| date == model.today)
+            .map(|countdown| p![view_bedtime_countdown(countdown, lang)]),
+        button![
+            lang.tr(TextKey::PlanTomorrow),
+            ev(Ev::Click, |_| Msg::PlanTomorrow)
+        ],
+        IF!(ws.actual_bedtime.is_none() => button![
+            lang.tr(TextKey::RecordBedtimeNow),
+            ev(Ev::Click, {
+                let clock = model.clock;
+                move |_| Msg::RecordBedtimeAt(clock.now())
+            })
+        ]),
+        button![
+            lang.tr(TextKey::Dismiss),
+            ev(Ev::Click, |_| Msg::DismissDayReview)
+        ],
+    ]
+}
+
+fn view_current_date_score_calculation(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    let calc = match model.data.work_sleep_data.get(&model.data.current_date) {
+        Some(ws) => ws
+            .show_score_calc(&model.data.default_work_sleep_goals, lang)
+            .unwrap_or_else(|| lang.tr(TextKey::NoBedtimeData).to_owned()),
+        None => lang.tr(TextKey::NoDayData).to_owned(),
+    };
+    div![h3![lang.tr(TextKey::ScoreCalculation)], raw![&calc]]
+}
+
+fn view_current_date_bedtime(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    if model.is_planning() {
+        return p![lang.tr(TextKey::BedtimeWhenDayComes)];
+    }
+    let bedtime = &model.current_date_bedtime;
+    div![
+        IF!(model.is_narrow() => C!["stacked"]),
+        IF!(model.data.current_date == model.today => view_going_to_bed_now(model)),
+        label![attrs! {At::For=>"bedtime"}, lang.tr(TextKey::ActualBedtime)],
+        input![
+            attrs! {
+                At::Id=>"bedtime",
+                At::Placeholder=>model.data.clock_format.example(),
+                At::Value=>bedtime.time,
+            },
+            input_ev(Ev::Input, Msg::BedtimeInputChanged)
+        ],
+        label![
+            attrs! {At::For=>"bedtime-next-day"},
+            lang.tr(TextKey::Tomorrow)
+        ],
+        input![
+            attrs! {At::Id=>"bedtime-next-day", At::Type=>"checkbox", At::Checked=>bedtime.is_next_day.as_at_value()},
+            ev(Ev::Change, |event| {
+                event
+                    .target()
+                    .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                    .map(|input| Msg::BedtimeNextDayToggled(input.checked()))
+            })
+        ],
+        button![
+            lang.tr(TextKey::RecordBedtime),
+            attrs! {At::Id=>"record-bedtime"},
+            ev(Ev::Click, |_| Msg::RecordBedtime)
+        ],
+        bedtime
+            .error
+            .as_ref()
+            .map(|err| p![style! {St::Color=>"red"}, err]),
+        view_bedtime_score_preview(model),
+    ]
+}
+
+// Records the bedtime without typing it in, asking first before replacing one.
+fn view_going_to_bed_now(model: &Model) -> Node<Msg> {
+    let recorded = model
+        .data
+        .work_sleep_data
+        .get(&model.data.current_date)
+        .and_then(|ws| ws.actual_bedtime.as_ref());
+    let lang = model.data.lang;
+    match (&model.bedtime_to_confirm, recorded) {
+        (Some(bedtime), Some(recorded)) => p![
+            lang.trf(
+                TextKey::ReplaceBedtime,
+                &[
+                    &model.data.fmt_bedtime(recorded),
+                    &model.data.fmt_bedtime(bedtime)
+                ]
+            ),
+            button![
+                lang.tr(TextKey::Replace),
+                ev(Ev::Click, |_| Msg::ConfirmBedtimeOverwrite)
+            ],
+            button![
+                lang.tr(TextKey::Keep),
+                ev(Ev::Click, |_| Msg::CancelBedtimeOverwrite)
+            ],
+        ],
+        _ => p![button![
+            style! {St::FontSize=>"1.5em", St::Padding=>"0.5em 1em"},
+            lang.tr(TextKey::GoingToBedNow),
+            ev(Ev::Click, {
+                let clock = model.clock;
+                move |_| Msg::RecordBedtimeAt(clock.now())
+            }),
+        ]],
+    }
+}
+
+// The wake time ends the night before the current date, so it's recorded there.
+fn view_wake_time(model: &Model) -> Node<Msg> {
+    if model.is_planning() {
+        return empty![];
+    }
+    let date = model.data.current_date;
+    let night = date
+        .pred_opt()
+        .and_then(|night| model.data.work_sleep_data.get(&night));
+    let input = &model.wake_time_input;
+    let lang = model.data.lang;
+    div![
+        IF!(model.is_narrow() => C!["stacked"]),
+        label![
+            attrs! {At::For=>"wake-time"},
+            if date == model.today {
+                lang.tr(TextKey::WokeUpThisMorning).to_owned()
+            } else {
+                lang.trf(TextKey::WokeUpOn, &[&model.data.fmt_date(date)])
+            }
+        ],
+        input![
+            attrs! {
+                At::Id=>"wake-time",
+                At::Placeholder=>model.data.clock_format.example(),
+                At::Value=>input.time,
+            },
+            input_ev(Ev::Input, Msg::WakeTimeInputChanged)
+        ],
+        button![
+            lang.tr(TextKey::RecordWakeTime),
+            ev(Ev::Click, |_| Msg::RecordWakeTime)
+        ],
+        night
+            .and_then(WorkSleep::sleep_minutes)
+            .map(|minutes| span![lang.trf(TextKey::SleptFor, &[&format_sleep(minutes)])]),
+        input
+            .error
+            .as_ref()
+            .map(|err| p![style! {St::Color=>"red"}, err]),
+    ]
+}
+
+fn view_mood_picker(model: &Model) -> Node<Msg> {
+    if model.is_planning() {
+        return empty![];
+    }
+    let date = model.data.current_date;
+    let mood = model.data.work_sleep_data.get(&date).and_then(|ws| ws.mood);
+    let lang = model.data.lang;
+    div![
+        label![lang.tr(TextKey::HowDidTheDayFeel)],
+        MOOD_EMOJIS.iter().zip(1..).map(|(emoji, value)| {
+            button![
+                IF!(mood == Some(value) => style! {St::FontWeight=>"bold", St::Background=>"#cce5ff"}),
+                attrs! {
+                    At::Title=>lang.trf(TextKey::MoodOutOf, &[&value, &MOOD_EMOJIS.len()]),
+                    At::AriaPressed=>(mood == Some(value)).to_string(),
+                },
+                format!("{} {}", emoji, value),
+                ev(Ev::Click, move |_| Msg::SetMood(date, value)),
+            ]
+        }),
+    ]
+}
+
+// What today's score would be for going to bed now, at the target or a little later.
+fn view_bedtime_score_preview(model: &Model) -> Node<Msg> {
+    let date = model.data.current_date;
+    let now = match Bedtime::at(date, model.now) {
+        Some(now) if date == model.today => now,
+        _ => return empty![],
+    };
+    let defaults = &model.data.default_work_sleep_goals;
+    let empty = WorkSleep::new();
+    let ws = model.data.work_sleep_data.get(&date).unwrap_or(&empty);
+    let target = ws.effective_goals(defaults).target_bedtime.clone();
+    let score = |bedtime: Option<Bedtime>| {
+        bedtime.map_or("–".to_owned(), |bedtime| {
+            ws.score_with_bedtime(defaults, bedtime).to_string()
+        })
+    };
+    p![small![model.data.lang.trf(
+        TextKey::BedtimePreview,
+        &[
+            &model.data.fmt_bedtime(&now),
+            &score(Some(now.clone())),
+            &model.data.fmt_bedtime(&target),
+            &score(Some(target.clone())),
+            &score(now.plus_minutes(30)),
+            &score(now.plus_minutes(60)),
+        ]
+    )]]
+}
+
+// For checking what views would show without a browser.
+#[cfg(test)]
+mod test_support {
+    use seed::prelude::*;
+
+    use crate::app::update::Msg;
+
+    // All the text in `node` and its descendants, in document order.
+    pub fn text_content(node: &Node<Msg>) -> String {
+        match node {
+            Node::Element(el) => el.children.iter().map(text_content).collect(),
+            Node::Text(text) => text.text.to_string(),
+            Node::Empty | Node::NoChange => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::test_support::text_content;
+    use super::*;
+
+    #[test]
+    fn unknown_routes_show_not_found_with_nav_links() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        model.page = Page::NotFound;
+        let text = text_content(&view(&model));
+        assert!(text.contains("Page not found"));
+        assert!(text.contains("TrackingHistorySettings"));
+    }
+
+    #[test]
+    fn bedtime_preview_follows_the_clock_on_today_only() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        model.now = today.succ_opt().unwrap().and_hms_opt(0, 30, 0).unwrap();
+        let text = text_content(&view_bedtime_score_preview(&model));
+        assert!(text.contains("If you go to bed now (12:30 AM (next day))"));
+
+        model.data.current_date = today.pred_opt().unwrap();
+        assert!(text_content(&view_bedtime_score_preview(&model)).is_empty());
+    }
+
+    #[test]
+    fn shortcut_help_is_hidden_until_toggled() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        assert!(!text_content(&view_shortcut_help(&model)).contains("Keyboard shortcuts"));
+        model.show_shortcut_help = true;
+        assert!(text_content(&view_shortcut_help(&model)).contains("Keyboard shortcuts"));
+    }
+
+    #[test]
+    fn day_review_shows_the_work_done_and_the_bedtime_countdown() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        model.now = today.and_hms_opt(21, 30, 0).unwrap();
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&today)
+            .actual_work_count = 3;
+        assert!(text_content(&view_day_review(&model)).is_empty());
+
+        model.day_review_open = true;
+        model.day_review_shown = Some((today, 6));
+        let text = text_content(&view_day_review(&model));
+        assert!(text.contains("Work done: 3 of 6 · Score so far: 35"));
+        assert!(text.contains("1h 30m until target bedtime"));
+        assert!(text.contains("Record bedtime now"));
+
+        model.data.current_date = today.succ_opt().unwrap();
+        assert!(text_content(&view_day_review(&model)).is_empty());
+    }
+
+    #[test]
+    fn going_to_bed_now_asks_before_replacing_the_bedtime() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        let at = |hour| Bedtime {
+            time: chrono::NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+            next_day: false,
+        };
+        assert!(text_content(&view_going_to_bed_now(&model)).contains("I'm going to bed now"));
+
+        model.data.day_mut(today).actual_bedtime = Some(at(22));
+        model.bedtime_to_confirm = Some(at(23));
+        assert_eq!(
+            text_content(&view_going_to_bed_now(&model)),
+            "Replace the bedtime of 10:00 PM with 11:00 PM? ReplaceKeep"
+        );
+    }
+
+    #[test]
+    fn week_archive_previews_the_weeks_it_compacts() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 5).unwrap());
+        assert!(text_content(&view_week_archive(&model)).contains("No weeks are old enough"));
+        for day in [26, 27] {
+            model
+                .data
+                .work_sleep_data
+                .get_mut_or_create(&NaiveDate::from_ymd_opt(2021, 3, day).unwrap());
+        }
+        let text = text_content(&view_week_archive(&model));
+        assert!(text
+            .contains("1 week from 2021-03-25 to 2021-03-31 will be compacted, removing 2 days."));
+    }
+
+    #[test]
+    fn unsaved_changes_are_marked_in_the_header() {
+        let nav = |dirty| {
+            text_content(&view_nav(
+                Page::Tracking,
+                Theme::System,
+                None,
+                dirty,
+                Lang::English,
+            ))
+        };
+        assert!(!nav(false).contains('●'));
+        assert!(nav(true).contains('●'));
+    }
+
+    #[test]
+    fn the_nav_is_shown_in_the_language_chosen() {
+        let text = text_content(&view_nav(
+            Page::Tracking,
+            Theme::System,
+            None,
+            false,
+            Lang::Spanish,
+        ));
+        assert!(text.contains("SeguimientoHistorialAjustes"));
+    }
+}
@@ -0,0 +1,1105 @@
+// The task list for the current date and the forms for adding tasks.
+
+use chrono::Datelike;
+use seed::{prelude::*, *};
+use uuid::Uuid;
+
+use crate::app::update::Msg;
+use crate::app::Model;
+use crate::i18n::{Lang, TextKey};
+use crate::model::{
+    bedtime_overshoot, estimate_finish, format_countdown, format_work_count, group_consecutive,
+    note_preview, Data, Period, Priority, MAX_BREAK_MINUTES, MAX_CALENDAR_BLOCK_MINUTES,
+    MAX_POMODORO_MINUTES, MAX_TASK_QUANTITY, MAX_TASK_WEIGHT, MIN_TASK_WEIGHT, WEEKDAYS,
+};
+
+// How many of the latest timed tasks the finish estimate averages over.
+const RECENT_PERIODS_FOR_ESTIMATE: usize = 20;
+pub const NOTE_PREVIEW_CHARS: usize = 40;
+
+pub fn view_recurring_tasks(model: &Model) -> Node<Msg> {
+    let new_task = &model.new_recurring_task;
+    let lang = model.data.lang;
+    div![
+        h2![lang.tr(TextKey::RecurringTasks)],
+        p![lang.tr(TextKey::RecurringTasksHelp)],
+        ul![model.data.recurring_tasks.iter().map(|task| {
+            let id = task.id;
+            let days = match task.weekdays {
+                Some(weekdays) => WEEKDAYS
+                    .iter()
+                    .zip(weekdays.iter())
+                    .filter(|(_, due)| **due)
+                    .map(|(weekday, _)| lang.weekday_short(*weekday))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => lang.tr(TextKey::EveryDay).to_owned(),
+            };
+            li![
+                format!("{} ×{} ({}) ", task.name, task.quantity, days),
+                button![
+                    lang.tr(TextKey::Remove),
+                    ev(Ev::Click, move |_| Msg::DeleteRecurringTask(id))
+                ],
+            ]
+        })],
+        label![
+            attrs! {At::For=>"new-recurring-task-name"},
+            lang.tr(TextKey::NewRecurringTask)
+        ],
+        input![
+            attrs! {At::Id=>"new-recurring-task-name", At::Placeholder=>lang.tr(TextKey::TaskNamePlaceholder),At::Value=>new_task.name},
+            input_ev(Ev::Input, Msg::NewRecurringTaskNameChanged),
+        ],
+        label![attrs! {At::For=>"new-recurring-task-quantity"}, "×"],
+        input![
+            attrs! {At::Id=>"new-recurring-task-quantity", At::AriaLabel=>lang.tr(TextKey::Quantity), At::Placeholder=>lang.tr(TextKey::Quantity),At::Value=>new_task.quantity},
+            input_ev(Ev::Input, Msg::NewRecurringTaskQuantityChanged),
+        ],
+        WEEKDAYS.iter().enumerate().map(|(i, weekday)| {
+            label![
+                input![
+                    attrs! {At::Type=>"checkbox", At::Checked=>new_task.weekdays[i].as_at_value()},
+                    ev(Ev::Change, move |_| Msg::NewRecurringTaskWeekdayToggled(i)),
+                ],
+                lang.weekday_short(*weekday),
+            ]
+        }),
+        button![
+            lang.tr(TextKey::AddRecurringTask),
+            ev(Ev::Click, |_| Msg::AddRecurringTask)
+        ],
+        p![lang.tr(TextKey::RecurringDailyHelp)],
+        if let Err(err) = &new_task.quantity_parsed {
+            p![style! {St::Color=>"red"}, err]
+        } else {
+            empty![]
+        },
+    ]
+}
+
+pub fn view_current_date_planning(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        IF!(model.is_narrow() => C!["stacked"]),
+        button![
+            lang.tr(TextKey::Undo),
+            attrs! {At::Disabled=>model.undo_stack.is_empty().as_at_value()},
+            ev(Ev::Click, |_| Msg::Undo)
+        ],
+        button![
+            lang.tr(TextKey::Redo),
+            attrs! {At::Disabled=>model.redo_stack.is_empty().as_at_value()},
+            ev(Ev::Click, |_| Msg::Redo)
+        ],
+        button![
+            lang.tr(TextKey::Focus),
+            attrs! {At::Title=>lang.tr(TextKey::FocusTitle)},
+            ev(Ev::Click, |_| Msg::EnterFocusMode)
+        ],
+        button![
+            lang.tr(TextKey::SortByPriority),
+            attrs! {At::Title=>lang.tr(TextKey::SortByPriorityTitle)},
+            ev(Ev::Click, |_| Msg::SortTasksByPriority)
+        ],
+        label![
+            attrs! {At::For=>"one-click-delete"},
+            lang.tr(TextKey::OneClickDelete)
+        ],
+        input![
+            attrs! {At::Id=>"one-click-delete", At::Type=>"checkbox", At::Checked=>model.data.one_click_delete.as_at_value()},
+            ev(Ev::Change, |_| Msg::ToggleOneClickDelete),
+        ],
+        label![
+            attrs! {At::For=>"group-repeated-tasks"},
+            lang.tr(TextKey::GroupRepeatedTasks)
+        ],
+        input![
+            attrs! {At::Id=>"group-repeated-tasks", At::Type=>"checkbox", At::Checked=>model.data.group_repeated_tasks.as_at_value()},
+            ev(Ev::Change, |_| Msg::ToggleGroupRepeatedTasks),
+        ],
+        label![
+            attrs! {At::For=>"pomodoro-length"},
+            lang.tr(TextKey::PomodoroLength)
+        ],
+        input![
+            attrs! {At::Id=>"pomodoro-length", At::Type=>"number", At::Min=>1, At::Max=>MAX_POMODORO_MINUTES, At::Value=>model.data.pomodoro_minutes},
+            input_ev(Ev::Input, Msg::PomodoroLengthChanged),
+        ],
+        label![
+            attrs! {At::For=>"breaks-enabled"},
+            lang.tr(TextKey::BreaksEnabled)
+        ],
+        input![
+            attrs! {At::Id=>"breaks-enabled", At::Type=>"checkbox", At::Checked=>model.data.breaks_enabled.as_at_value()},
+            ev(Ev::Change, |_| Msg::ToggleBreaks),
+        ],
+        IF!(model.data.breaks_enabled => vec![
+            label![
+                attrs! {At::For=>"break-length"},
+                lang.tr(TextKey::BreakLength)
+            ],
+            input![
+                attrs! {At::Id=>"break-length", At::Type=>"number", At::Min=>1, At::Max=>MAX_BREAK_MINUTES, At::Value=>model.data.break_minutes},
+                input_ev(Ev::Input, Msg::BreakLengthChanged),
+            ],
+        ]),
+        view_break(model),
+        if model.data.group_repeated_tasks {
+            view_task_groups(model)
+        } else {
+            view_tasks(model)
+        },
+        view_finish_estimate(model),
+        view_calendar_export(model),
+        match model.data.postponed_count() {
+            0 => empty![],
+            n => p![lang.count(n, TextKey::TasksPostponedOne, TextKey::TasksPostponedOther)],
+        },
+        view_quick_add_chips(model),
+        label![attrs! {At::For=>"new-task-name"}, lang.tr(TextKey::NewTask)],
+        input![
+            el_ref(&model.refs.new_task_name),
+            attrs! {At::Id=>"new-task-name", At::Placeholder=>lang.tr(TextKey::TaskNamePlaceholder),At::Value=>model.new_task.name},
+            input_ev(Ev::Input, Msg::NewTaskNameChanged),
+            keyboard_ev(Ev::KeyDown, new_task_key_down),
+        ],
+        input![
+            attrs! {
+                At::AriaLabel=>lang.tr(TextKey::Category),
+                At::Placeholder=>lang.tr(TextKey::CategoryPlaceholder),
+                At::Value=>model.new_task.category,
+                At::List=>"task-categories",
+            },
+            input_ev(Ev::Input, Msg::NewTaskCategoryChanged),
+            keyboard_ev(Ev::KeyDown, new_task_key_down),
+        ],
+        textarea![
+            attrs! {
+                At::AriaLabel=>lang.tr(TextKey::Notes),
+                At::Placeholder=>lang.tr(TextKey::NotesPlaceholder),
+                At::Rows=>1,
+                At::Value=>model.new_task.note,
+            },
+            input_ev(Ev::Input, Msg::NewTaskNoteChanged),
+        ],
+        view_priority_select(model.new_task.priority, Msg::NewTaskPriorityChanged, lang),
+        datalist![
+            id!["task-categories"],
+            model
+                .data
+                .used_categories()
+                .into_iter()
+                .map(|category| option![attrs! {At::Value=>category}]),
+        ],
+        label![attrs! {At::For=>"new-task-quantity"}, "×"],
+        input![
+            attrs! {At::Id=>"new-task-quantity", At::AriaLabel=>lang.tr(TextKey::Quantity), At::Placeholder=>lang.tr(TextKey::Quantity),At::Value=>model.new_task.quantity},
+            input_ev(Ev::Input, Msg::NewTaskQuantityChanged),
+            keyboard_ev(Ev::KeyDown, new_task_key_down),
+        ],
+        label![
+            attrs! {At::For=>"new-task-weight"},
+            lang.tr(TextKey::CountsAs)
+        ],
+        input![
+            attrs! {
+                At::Id=>"new-task-weight",
+                At::Type=>"number",
+                At::Min=>MIN_TASK_WEIGHT,
+                At::Max=>MAX_TASK_WEIGHT,
+                At::Step=>0.1,
+                At::Title=>lang.tr(TextKey::WeightTitle),
+                At::Value=>model.new_task.weight,
+            },
+            input_ev(Ev::Input, Msg::NewTaskWeightChanged),
+            keyboard_ev(Ev::KeyDown, new_task_key_down),
+        ],
+        label![attrs! {At::For=>"new-task-date"}, lang.tr(TextKey::On)],
+        input![
+            attrs! {
+                At::Id=>"new-task-date",
+                At::Type=>"date",
+                At::Min=>model.today.format("%Y-%m-%d"),
+                At::Value=>model
+                    .new_task
+                    .date
+                    .unwrap_or(model.data.current_date)
+                    .format("%Y-%m-%d"),
+            },
+            input_ev(Ev::Change, Msg::NewTaskDateChanged),
+        ],
+        button![
+            lang.tr(TextKey::AddNewTask),
+            attrs! {At::Id=>"add-new-task"},
+            ev(Ev::Click, |_| Msg::AddNewTask)
+        ],
+        button![
+            lang.tr(TextKey::Clear),
+            attrs! {At::Title=>lang.tr(TextKey::ClearTitle)},
+            ev(Ev::Click, |_| Msg::ResetNewTaskForm)
+        ],
+        button![
+            lang.tr(if model.bulk_add.is_some() {
+                TextKey::CancelAddingSeveral
+            } else {
+                TextKey::AddSeveralTasks
+            }),
+            ev(Ev::Click, |_| Msg::ToggleBulkAdd)
+        ],
+        button![
+            lang.tr(TextKey::CopyUnfinished),
+            attrs! {At::Disabled=>model
+            .data
+            .current_date
+            .pred_opt()
+            .filter(|yesterday| !model.data.plan(*yesterday).is_empty())
+            .is_none()
+            .as_at_value()},
+            ev(Ev::Click, |_| Msg::CopyUnfinishedTasksFromYesterday)
+        ],
+        match &model.new_task.quantity_parsed {
+            Ok(quantity) if *quantity == MAX_TASK_QUANTITY => {
+                p![lang.trf(TextKey::QuantityCapped, &[&MAX_TASK_QUANTITY])]
+            }
+            Ok(_) => empty![],
+            Err(err) => p![style! {St::Color=>"red"}, err],
+        },
+        match &model.new_task.weight_parsed {
+            Ok(_) => empty![],
+            Err(err) => p![style! {St::Color=>"red"}, err],
+        },
+        model
+            .bulk_add
+            .as_ref()
+            .map(|text| view_bulk_add(text, lang)),
+        view_upcoming(model),
+    ]
+}
+
+// The tasks planned for the days after both today and the current date, which can be
+// renamed, reprioritized or deleted before their day.
+fn view_upcoming(model: &Model) -> Node<Msg> {
+    let after = model.today.max(model.data.current_date);
+    let upcoming: Vec<_> = model.data.upcoming_plans(after).collect();
+    if upcoming.is_empty() {
+        return empty![];
+    }
+    let count: usize = upcoming.iter().map(|(_, plan)| plan.len()).sum();
+    let lang = model.data.lang;
+    details![
+        summary![lang.trf(TextKey::Upcoming, &[&count])],
+        upcoming.into_iter().map(|(date, plan)| div![
+            h4![
+                model.data.fmt_date(date),
+                button![
+                    lang.tr(TextKey::OpenDay),
+                    ev(Ev::Click, move |_| Msg::SetCurrentDate(date))
+                ],
+            ],
+            ul![plan.iter().map(|wp| {
+                let id = wp.id;
+                li![
+                    el_key(&id),
+                    priority_style(wp.priority),
+                    view_task_name(model, wp, ""),
+                    view_priority_select(
+                        wp.priority,
+                        move |priority| Msg::SetTaskPriority(id, priority),
+                        lang
+                    ),
+                    button![
+                        lang.tr(TextKey::Delete),
+                        ev(Ev::Click, move |_| Msg::DeleteUpcomingTask(date, id))
+                    ],
+                ]
+            })],
+        ]),
+    ]
+}
+
+fn view_bulk_add(text: &str, lang: Lang) -> Node<Msg> {
+    div![
+        textarea![
+            attrs! {
+                At::AriaLabel=>lang.tr(TextKey::BulkAddLabel),
+                At::Placeholder=>lang.tr(TextKey::BulkAddPlaceholder),
+                At::Rows=>6,
+                At::Value=>text,
+            },
+            input_ev(Ev::Input, Msg::BulkAddTextChanged),
+        ],
+        br![],
+        button![
+            lang.tr(TextKey::AddTheseTasks),
+            ev(Ev::Click, |_| Msg::AddBulkTasks)
+        ],
+    ]
+}
+
+// The current task on its own, with the day's progress and a way to finish it.
+pub fn view_focus(model: &Model) -> Node<Msg> {
+    let date = model.data.current_date;
+    let ws = model.data.work_sleep_data.get(&date);
+    let done = format_work_count(ws.map_or(0.0, |ws| ws.weighted_work_count()));
+    let target = model.data.goals_on(date).target_work_count;
+    let lang = model.data.lang;
+    div![
+        style! {St::TextAlign=>"center", St::Padding=>"10vh 1em"},
+        match model.data.current_plan().front() {
+            Some(wp) => vec![
+                h1![style! {St::FontSize=>"4em"}, &wp.name],
+                p![
+                    style! {St::FontSize=>"1.5em"},
+                    format!("{}/{}", done, target)
+                ],
+                match &model.pomodoro {
+                    Some(pomodoro) if pomodoro.task_id == wp.id => p![
+                        style! {St::FontSize=>"2em"},
+                        el_ref(&model.refs.pomodoro_countdown),
+                        format_countdown(pomodoro.remaining_secs)
+                    ],
+                    _ => empty![],
+                },
+                view_break(model),
+                div![view_done_button(model)],
+                br![],
+                button![
+                    lang.tr(TextKey::ExitFocusMode),
+                    ev(Ev::Click, |_| Msg::ExitFocusMode)
+                ],
+            ],
+            None => vec![
+                h1![lang.tr(TextKey::QueueEmpty)],
+                p![format!("{}/{}", done, target)],
+                button![
+                    lang.tr(TextKey::BackToPlanning),
+                    ev(Ev::Click, |_| Msg::ExitFocusMode)
+                ],
+            ],
+        },
+    ]
+}
+
+// Downloads the plan as back-to-back calendar blocks, plus the target bedtime.
+fn view_calendar_export(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    div![
+        label![
+            attrs! {At::For=>"calendar-start"},
+            lang.tr(TextKey::BlocksFrom)
+        ],
+        input![
+            attrs! {At::Id=>"calendar-start", At::Type=>"time", At::Value=>model.calendar_start.format("%H:%M").to_string()},
+            input_ev(Ev::Input, Msg::CalendarStartChanged),
+        ],
+        label![
+            attrs! {At::For=>"calendar-block-minutes"},
+            lang.tr(TextKey::BlockMinutes)
+        ],
+        input![
+            attrs! {At::Id=>"calendar-block-minutes", At::Type=>"number", At::Min=>1, At::Max=>MAX_CALENDAR_BLOCK_MINUTES, At::Value=>model.calendar_block_minutes},
+            input_ev(Ev::Input, Msg::CalendarBlockMinutesChanged),
+        ],
+        button![
+            lang.tr(TextKey::ExportIcs),
+            attrs! {At::Title=>lang.tr(TextKey::ExportIcsTitle)},
+            ev(Ev::Click, |_| Msg::ExportCalendar)
+        ],
+    ]
+}
+
+// How many tasks are left and, for today, when they'd be done at the usual pace.
+fn view_finish_estimate(model: &Model) -> Node<Msg> {
+    let remaining = model.data.current_plan().len();
+    if remaining == 0 {
+        return empty![];
+    }
+    let lang = model.data.lang;
+    let count = lang.count(remaining, TextKey::TasksLeftOne, TextKey::TasksLeftOther);
+    let date = model.data.current_date;
+    if date != model.today {
+        return p![count];
+    }
+    let period_minutes = model
+        .data
+        .work_sleep_data
+        .average_period_minutes(RECENT_PERIODS_FOR_ESTIMATE)
+        .unwrap_or(model.data.pomodoro_minutes);
+    let finish = estimate_finish(remaining, period_minutes, model.now);
+    let target = model.data.goals_on(date).target_bedtime.clone();
+    let estimate = lang.trf(
+        TextKey::FinishEstimate,
+        &[&count, &period_minutes, &model.data.fmt_time(finish.time())],
+    );
+    match bedtime_overshoot(date, &target, finish) {
+        Some(minutes) => p![
+            style! {St::Color=>"#c62828"},
+            lang.trf(
+                TextKey::PastBedtimeTarget,
+                &[&estimate, &minutes, &model.data.fmt_bedtime(&target)]
+            )
+        ],
+        None => p![estimate],
+    }
+}
+
+fn view_quick_add_chips(model: &Model) -> Node<Msg> {
+    let chips = model.data.quick_add_tasks(model.today);
+    if chips.is_empty() {
+        return empty![];
+    }
+    let lang = model.data.lang;
+    div![chips.into_iter().map(|name| {
+        let pinned = model.data.pinned_quick_tasks.contains(name);
+        let (add, pin, remove) = (name.to_owned(), name.to_owned(), name.to_owned());
+        span![
+            style! {St::Margin=>"0 0.5em 0 0", St::WhiteSpace=>"nowrap"},
+            button![
+                format!("+ {}", name),
+                ev(Ev::Click, move |_| Msg::QuickAddTask(add))
+            ],
+            button![
+                lang.tr(if pinned { TextKey::Unpin } else { TextKey::Pin }),
+                ev(Ev::Click, move |_| Msg::TogglePinQuickTask(pin))
+            ],
+            button![
+                lang.tr(TextKey::Remove),
+                attrs! {At::Title=>lang.tr(TextKey::StopSuggesting)},
+                ev(Ev::Click, move |_| Msg::RemoveQuickTask(remove))
+            ],
+        ]
+    })]
+}
+
+fn new_task_key_down(event: web_sys::KeyboardEvent) -> Option<Msg> {
+    match event.key().as_str() {
+        "Enter" => Some(Msg::AddNewTask),
+        "Escape" => Some(Msg::ResetNewTaskForm),
+        _ => None,
+    }
+}
+
+fn view_tasks(model: &Model) -> Node<Msg> {
+    let periods = model.data.current_plan();
+    let lang = model.data.lang;
+    ul![
+        if let Some(wp) = periods.front() {
+            view_first_work_period(
+                wp,
+                view_task_name(model, wp, lang.tr(TextKey::CurrentTask)),
+                view_delete_button(model, wp.id),
+                view_done_button(model),
+                view_pomodoro(model, wp.id),
+                &model.data,
+            )
+        } else {
+            li![lang.tr(TextKey::TaskListEmpty)]
+        },
+        periods.iter().skip(1).map(|wp| view_work_period(
+            wp,
+            view_task_name(model, wp, ""),
+            view_delete_button(model, wp.id),
+            model.is_narrow(),
+            lang,
+        )),
+    ]
+}
+
+fn view_task_groups(model: &Model) -> Node<Msg> {
+    let periods = model.data.current_plan();
+    let groups = group_consecutive(periods);
+    if groups.is_empty() {
+        return ul![li![model.data.lang.tr(TextKey::TaskListEmpty)]];
+    }
+    ul![groups.into_iter().enumerate().map(|(i, group)| {
+        let first = &periods[group.start];
+        let ids: Vec<Uuid> = periods.range(group).map(|wp| wp.id).collect();
+        let pomodoro = IF!(i == 0 => view_pomodoro(model, first.id));
+        let delete_button = view_delete_button(model, ids[ids.len() - 1]);
+        let done_button = IF!(i == 0 => view_done_button(model));
+        let narrow = model.is_narrow();
+        view_task_group(
+            first,
+            ids,
+            delete_button,
+            done_button,
+            pomodoro,
+            &model.data,
+            narrow,
+        )
+    })]
+}
+
+// `done_button` and `pomodoro` are only given for the current task.
+fn view_task_group(
+    first: &Period,
+    ids: Vec<Uuid>,
+    delete_button: Node<Msg>,
+    done_button: Option<Node<Msg>>,
+    pomodoro: Option<Node<Msg>>,
+    data: &Data,
+    narrow: bool,
+) -> Node<Msg> {
+    let lang = data.lang;
+    let is_first = pomodoro.is_some();
+    let label = if ids.len() > 1 {
+        format!("{} ×{}", task_label(first, lang), ids.len())
+    } else {
+        task_label(first, lang)
+    };
+    let group_msg = |to_msg: fn(Vec<Uuid>) -> Msg| {
+        let ids = ids.clone();
+        ev(Ev::Click, move |_| to_msg(ids))
+    };
+    let mut move_buttons = Vec::new();
+    if !is_first {
+        move_buttons.push(button![
+            lang.tr(TextKey::MoveToTop),
+            group_msg(Msg::MoveTasksToTop)
+        ]);
+        move_buttons.push(button![
+            lang.tr(TextKey::MoveUp),
+            group_msg(Msg::MoveTasksUp)
+        ]);
+    }
+    move_buttons.push(button![
+        lang.tr(TextKey::MoveDown),
+        group_msg(Msg::MoveTasksDown)
+    ]);
+    move_buttons.push(button![
+        lang.tr(TextKey::MoveToBottom),
+        group_msg(Msg::MoveTasksToBottom)
+    ]);
+    li![
+        el_key(&first.id),
+        IF!(is_first => attrs! {At::AriaCurrent=>"true"}),
+        div![
+            category_style(&first.category),
+            priority_style(first.priority),
+            if is_first {
+                label![format!("{}{}", lang.tr(TextKey::CurrentTask), label)]
+            } else {
+                label![label]
+            },
+            delete_button,
+            view_priority_select(
+                first.priority,
+                {
+                    let ids = ids.clone();
+                    move |priority| Msg::SetTasksPriority(ids, priority)
+                },
+                lang
+            ),
+            view_postpone_button(ids[ids.len() - 1], lang),
+            IF!(ids.len() > 1 => button![lang.tr(TextKey::DeleteAll), group_msg(Msg::DeleteTasks)]),
+            IF!(is_first => view_task_timer(first, data)),
+            done_button,
+            pomodoro,
+            view_move_buttons(narrow, move_buttons, lang),
+        ]
+    ]
+}
+
+// On narrow screens the buttons for moving a task are folded into a menu.
+fn view_move_buttons(narrow: bool, buttons: Vec<Node<Msg>>, lang: Lang) -> Vec<Node<Msg>> {
+    if narrow {
+        vec![details![
+            C!["more-actions"],
+            summary![attrs! {At::AriaLabel=>lang.tr(TextKey::MoreActions)}, "⋯"],
+            buttons,
+        ]]
+    } else {
+        buttons
+    }
+}
+
+fn view_task_timer(wp: &Period, data: &Data) -> Node<Msg> {
+    let id = wp.id;
+    match wp.started_at {
+        Some(started_at) => span![data
+            .lang
+            .trf(TextKey::TimingSince, &[&data.fmt_time(started_at)])],
+        None => button![
+            data.lang.tr(TextKey::Start),
+            ev(Ev::Click, move |_| Msg::StartTask(id))
+        ],
+    }
+}
+
+fn view_postpone_button(id: Uuid, lang: Lang) -> Node<Msg> {
+    button![
+        lang.tr(TextKey::Postpone),
+        attrs! {At::Title=>lang.tr(TextKey::PostponeTitle)},
+        ev(Ev::Click, move |_| Msg::PostponeTask(id))
+    ]
+}
+
+fn task_label(wp: &Period, lang: Lang) -> String {
+    let label = match &wp.category {
+        Some(category) => format!("{} [{}]", wp.name, category),
+        None => wp.name.clone(),
+    };
+    let label = if wp.weight == 1.0 {
+        label
+    } else {
+        lang.trf(
+            TextKey::TaskCountsAs,
+            &[&label, &format_work_count(wp.weight)],
+        )
+    };
+    match wp.postponed_from {
+        Some(date) => lang.trf(
+            TextKey::PostponedFrom,
+            &[&label, &lang.weekday_short(date.weekday())],
+        ),
+        None => label,
+    }
+}
+
+// High-priority rows are marked with a bar down their side.
+fn priority_style(priority: Priority) -> Style {
+    match priority {
+        Priority::High => style! {St::BorderLeft=>"4px solid crimson", St::FontWeight=>"bold"},
+        Priority::Normal | Priority::Low => style! {},
+    }
+}
+
+fn view_priority_select(
+    current: Priority,
+    to_msg: impl FnOnce(Priority) -> Msg + Clone + 'static,
+    lang: Lang,
+) -> Node<Msg> {
+    select![
+        attrs! {At::AriaLabel=>lang.tr(TextKey::Priority)},
+        Priority::ALL.iter().enumerate().map(|(i, priority)| {
+            option![
+                attrs! {At::Value=>i, At::Selected=>(*priority == current).as_at_value()},
+                lang.tr(priority.label()),
+            ]
+        }),
+        input_ev(Ev::Change, move |value| {
+            value
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| Priority::ALL.get(i))
+                .map(|priority| to_msg(*priority))
+        }),
+    ]
+}
+
+fn category_style(category: &Option<String>) -> Style {
+    match category {
+        Some(category) => style! {St::Background=>category_color(category)},
+        None => style! {},
+    }
+}
+
+// A pastel color derived from a stable hash of `category`, so each category keeps its color.
+fn category_color(category: &str) -> String {
+    let hash = category.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte.into())
+    });
+    format!("hsl({}, 60%, 85%)", hash % 360)
+}
+
+fn view_pomodoro(model: &Model, task_id: Uuid) -> Node<Msg> {
+    let lang = model.data.lang;
+    match &model.pomodoro {
+        Some(pomodoro) if pomodoro.task_id == task_id => span![
+            lang.tr(TextKey::PomodoroLabel),
+            span![
+                el_ref(&model.refs.pomodoro_countdown),
+                format_countdown(pomodoro.remaining_secs)
+            ],
+            if pomodoro.ticker.is_some() {
+                button![
+                    lang.tr(TextKey::Pause),
+                    ev(Ev::Click, |_| Msg::PausePomodoro)
+                ]
+            } else {
+                button![
+                    lang.tr(TextKey::Resume),
+                    ev(Ev::Click, |_| Msg::ResumePomodoro)
+                ]
+            },
+            button![
+                lang.tr(TextKey::Cancel),
+                ev(Ev::Click, |_| Msg::CancelPomodoro)
+            ],
+        ],
+        _ => button![
+            lang.tr(TextKey::StartPomodoro),
+            ev(Ev::Click, move |_| Msg::StartPomodoro(task_id))
+        ],
+    }
+}
+
+// The break countdown, kept in view over the tasks while the next one is locked.
+fn view_break(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    match &model.work_break {
+        Some(work_break) => div![
+            C!["break-overlay"],
+            style! {
+                St::Position=>"sticky",
+                St::Top=>0,
+                St::ZIndex=>1,
+                St::Background=>"rgba(128, 128, 128, 0.9)",
+                St::Padding=>"1em",
+                St::TextAlign=>"center",
+                St::FontSize=>"1.5em",
+            },
+            lang.tr(TextKey::BreakLabel),
+            span![
+                el_ref(&model.refs.break_countdown),
+                format_countdown(work_break.remaining_secs)
+            ],
+            " ",
+            button![
+                lang.tr(TextKey::SkipBreak),
+                ev(Ev::Click, |_| Msg::SkipBreak)
+            ],
+        ],
+        None => empty![],
+    }
+}
+
+fn view_done_button(model: &Model) -> Node<Msg> {
+    let lang = model.data.lang;
+    if model.is_planning() {
+        button![
+            lang.tr(TextKey::FinishTask),
+            attrs! {At::Id=>"finish-task", At::Disabled=>true.as_at_value(), At::Title=>lang.tr(TextKey::DayNotYet)},
+        ]
+    } else if model.work_break.is_some() {
+        button![
+            lang.tr(TextKey::FinishTask),
+            attrs! {At::Id=>"finish-task", At::Disabled=>true.as_at_value(), At::Title=>lang.tr(TextKey::OnBreak)},
+        ]
+    } else {
+        button![
+            lang.tr(TextKey::FinishTask),
+            attrs! {At::Id=>"finish-task"},
+            ev(Ev::Click, |_| Msg::FinishedTopTask)
+        ]
+    }
+}
+
+// Deletes `id` straight away with one-click delete, and otherwise asks for confirmation.
+fn view_delete_button(model: &Model, id: Uuid) -> Node<Msg> {
+    let lang = model.data.lang;
+    if model.data.one_click_delete {
+        button![
+            lang.tr(TextKey::Delete),
+            ev(Ev::Click, move |_| Msg::DeleteTask(id))
+        ]
+    } else if model.pending_delete == Some(id) {
+        span![
+            button![
+                lang.tr(TextKey::ConfirmDelete),
+                ev(Ev::Click, move |_| Msg::DeleteTask(id))
+            ],
+            button![
+                lang.tr(TextKey::Cancel),
+                ev(Ev::Click, |_| Msg::CancelDeleteTask)
+            ],
+        ]
+    } else {
+        button![
+            lang.tr(TextKey::Delete),
+            ev(Ev::Click, move |_| Msg::RequestDeleteTask(id))
+        ]
+    }
+}
+
+// The task's label, or a text input with Save and Cancel buttons while it's being renamed.
+fn view_task_name(model: &Model, wp: &Period, prefix: &str) -> Node<Msg> {
+    let id = wp.id;
+    let lang = model.data.lang;
+    match &model.editing_task {
+        Some((editing_id, name)) if *editing_id == id => span![
+            label![prefix],
+            input![
+                el_ref(&model.refs.task_name_editor),
+                attrs! {At::Value=>name},
+                input_ev(Ev::Input, Msg::EditTaskNameChanged),
+                keyboard_ev(Ev::KeyDown, |event| match event.key().as_str() {
+                    "Enter" => Some(Msg::CommitEditTask),
+                    "Escape" => Some(Msg::CancelEditTask),
+                    _ => None,
+                }),
+            ],
+            button![
+                lang.tr(TextKey::Save),
+                ev(Ev::Click, |_| Msg::CommitEditTask)
+            ],
+            button![
+                lang.tr(TextKey::Cancel),
+                ev(Ev::Click, |_| Msg::CancelEditTask)
+            ],
+        ],
+        _ => span![
+            label![format!("{}{}", prefix, task_label(wp, lang))],
+            button![
+                lang.tr(TextKey::Edit),
+                ev(Ev::Click, move |_| Msg::StartEditTask(id))
+            ],
+        ],
+    }
+}
+
+fn view_first_work_period(
+    wp: &Period,
+    name: Node<Msg>,
+    delete_button: Node<Msg>,
+    done_button: Node<Msg>,
+    pomodoro: Node<Msg>,
+    data: &Data,
+) -> Node<Msg> {
+    let id = wp.id;
+    let lang = data.lang;
+    li![
+        el_key(&id),
+        attrs! {At::AriaCurrent=>"true"},
+        div![
+            category_style(&wp.category),
+            priority_style(wp.priority),
+            name,
+            delete_button,
+            view_priority_select(
+                wp.priority,
+                move |priority| Msg::SetTaskPriority(id, priority),
+                lang
+            ),
+            view_postpone_button(id, lang),
+            view_task_timer(wp, data),
+            done_button,
+            button![
+                lang.tr(TextKey::MoveToBottom),
+                ev(Ev::Click, move |_| Msg::MoveTaskToBottom(id))
+            ],
+            pomodoro,
+            view_task_note(wp, lang),
+        ]
+    ]
+}
+
+// The note's first line, opening into an editor for the whole note.
+fn view_task_note(wp: &Period, lang: Lang) -> Node<Msg> {
+    let id = wp.id;
+    let summary = if wp.note.is_empty() {
+        lang.tr(TextKey::AddNote).to_owned()
+    } else {
+        note_preview(&wp.note, NOTE_PREVIEW_CHARS)
+    };
+    details![
+        summary![small![summary]],
+        textarea![
+            attrs! {At::Rows=>3, At::Cols=>40, At::Value=>wp.note},
+            input_ev(Ev::Input, move |note| Msg::TaskNoteChanged(id, note)),
+        ],
+    ]
+}
+
+fn view_work_period(
+    wp: &Period,
+    name: Node<Msg>,
+    delete_button: Node<Msg>,
+    narrow: bool,
+    lang: Lang,
+) -> Node<Msg> {
+    let id = wp.id;
+    li![
+        el_key(&id),
+        div![
+            category_style(&wp.category),
+            priority_style(wp.priority),
+            name,
+            delete_button,
+            view_priority_select(
+                wp.priority,
+                move |priority| Msg::SetTaskPriority(id, priority),
+                lang
+            ),
+            view_postpone_button(id, lang),
+            view_move_buttons(
+                narrow,
+                vec![
+                    button![
+                        lang.tr(TextKey::MoveToTop),
+                        ev(Ev::Click, move |_| Msg::MoveTaskToTop(id))
+                    ],
+                    button![
+                        lang.tr(TextKey::MoveUp),
+                        ev(Ev::Click, move |_| Msg::MoveTaskUp(id))
+                    ],
+                    button![
+                        lang.tr(TextKey::MoveDown),
+                        ev(Ev::Click, move |_| Msg::MoveTaskDown(id))
+                    ],
+                    button![
+                        lang.tr(TextKey::MoveToBottom),
+                        ev(Ev::Click, move |_| Msg::MoveTaskToBottom(id))
+                    ],
+                ],
+                lang,
+            ),
+            view_task_note(wp, lang),
+        ]
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::super::test_support::text_content;
+    use super::*;
+    use crate::model::DateFormat;
+
+    #[test]
+    fn first_task_is_the_current_task() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        let plan = model.data.current_plan_mut();
+        plan.push_back(Period::new("write".to_owned()));
+        plan.push_back(Period::new("read".to_owned()));
+        let text = text_content(&view_tasks(&model));
+        assert!(text.contains("CURRENT TASK: write"));
+        assert!(!text.contains("CURRENT TASK: read"));
+    }
+
+    #[test]
+    fn focus_mode_shows_the_current_task_until_the_queue_is_empty() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        let plan = model.data.current_plan_mut();
+        plan.push_back(Period::new("write".to_owned()));
+        plan.push_back(Period::new("read".to_owned()));
+        let text = text_content(&view_focus(&model));
+        assert_eq!(text, "write0/6DONE!Exit focus mode");
+
+        model.data.current_plan_mut().clear();
+        let date = model.data.current_date;
+        model
+            .data
+            .work_sleep_data
+            .get_mut_or_create(&date)
+            .actual_work_count = 2;
+        let text = text_content(&view_focus(&model));
+        assert_eq!(text, "Queue empty2/6Back to planning");
+    }
+
+    #[test]
+    fn quick_add_chips_mark_pinned_names() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        assert!(text_content(&view_quick_add_chips(&model)).is_empty());
+        model.data.record_task_use("read", model.today);
+        model.data.pinned_quick_tasks.insert("write".to_owned());
+        assert_eq!(
+            text_content(&view_quick_add_chips(&model)),
+            "+ writeUnpinRemove+ readPinRemove"
+        );
+    }
+
+    #[test]
+    fn finish_estimate_warns_past_the_target_bedtime() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        model.now = today.and_hms_opt(22, 0, 0).unwrap();
+        for _ in 0..3 {
+            model
+                .data
+                .current_plan_mut()
+                .push_back(Period::new("write".to_owned()));
+        }
+        assert_eq!(
+            text_content(&view_finish_estimate(&model)),
+            "3 tasks left at about 25 min each: done around 11:15 PM, \
+             15 min past your 11:00 PM bedtime target"
+        );
+        model.data.current_date = today.pred_opt().unwrap();
+        assert!(text_content(&view_finish_estimate(&model)).is_empty());
+    }
+
+    #[test]
+    fn long_task_notes_are_previewed() {
+        let mut wp = Period::new("write".to_owned());
+        assert_eq!(
+            text_content(&view_task_note(&wp, Lang::English)),
+            "Add note"
+        );
+        wp.note = "x".repeat(NOTE_PREVIEW_CHARS + 10);
+        let preview = format!("{}…", "x".repeat(NOTE_PREVIEW_CHARS));
+        assert_eq!(text_content(&view_task_note(&wp, Lang::English)), preview);
+    }
+
+    #[test]
+    fn category_color_is_stable() {
+        assert_eq!(category_color("thesis"), category_color("thesis"));
+        assert_ne!(category_color("thesis"), category_color("email"));
+    }
+
+    #[test]
+    fn upcoming_tasks_are_listed_by_date() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        model.data.date_format = DateFormat::WeekdayFirst;
+        for (day, name) in [(13, "renew passport"), (6, "call bank"), (6, "read")] {
+            model
+                .data
+                .plan_mut(NaiveDate::from_ymd_opt(2021, 5, day).unwrap())
+                .push_back(Period::new(name.to_owned()));
+        }
+        let text = text_content(&view_upcoming(&model));
+        assert!(text.starts_with("Upcoming (3)"));
+        let thursday = text.find("Thu May 6").unwrap();
+        assert!(thursday < text.find("call bank").unwrap());
+        assert!(text.find("read").unwrap() < text.find("Thu May 13").unwrap());
+
+        model.data.current_date = NaiveDate::from_ymd_opt(2021, 5, 6).unwrap();
+        assert!(text_content(&view_upcoming(&model)).starts_with("Upcoming (1)"));
+    }
+
+    #[test]
+    fn narrow_screens_fold_moves_into_a_menu() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        for name in ["write", "read"] {
+            model
+                .data
+                .plan_mut(today)
+                .push_back(Period::new(name.to_owned()));
+        }
+        model.viewport_width = Some(1200);
+        let text = text_content(&view_tasks(&model));
+        assert!(text.contains("Move up") && !text.contains('⋯'));
+
+        model.viewport_width = Some(400);
+        let text = text_content(&view_tasks(&model));
+        assert!(text.contains("⋯Move to topMove up"));
+    }
+
+    #[test]
+    fn tasks_are_labelled_in_the_language_chosen() {
+        let today = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let mut model = Model::on(today);
+        model.data.lang = Lang::Spanish;
+        let mut wp = Period::new("write".to_owned());
+        wp.postponed_from = today.pred_opt();
+        model.data.current_plan_mut().push_back(wp);
+        let text = text_content(&view_tasks(&model));
+        assert!(text.contains("TAREA ACTUAL: write (pospuesta del dom)"));
+        assert!(text.contains("¡HECHO!"));
+        assert!(!text.contains("Delete"));
+    }
+
+    #[test]
+    fn the_break_countdown_can_be_skipped() {
+        let mut model = Model::on(NaiveDate::from_ymd_opt(2021, 5, 3).unwrap());
+        assert_eq!(text_content(&view_break(&model)), "");
+        model.work_break = Some(crate::app::WorkBreak {
+            remaining_secs: 4 * 60 + 30,
+            _ticker: None,
+        });
+        assert_eq!(text_content(&view_break(&model)), "Break: 04:30 Skip break");
+    }
+}
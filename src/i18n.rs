@@ -0,0 +1,1409 @@
+// The languages the interface can be shown in, and its text in each of them. Like
+// `scoring`, this module is kept free of `seed` and `web_sys` so that it can be unit
+// tested natively.
+
+use std::fmt::{Display, Write};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Lang {
+    #[default]
+    English,
+    Spanish,
+}
+
+const ENGLISH_WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const SPANISH_WEEKDAYS: [&str; 7] = [
+    "lunes",
+    "martes",
+    "miércoles",
+    "jueves",
+    "viernes",
+    "sábado",
+    "domingo",
+];
+const ENGLISH_WEEKDAYS_SHORT: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const SPANISH_WEEKDAYS_SHORT: [&str; 7] = ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"];
+const ENGLISH_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const SPANISH_MONTHS: [&str; 12] = [
+    "enero",
+    "febrero",
+    "marzo",
+    "abril",
+    "mayo",
+    "junio",
+    "julio",
+    "agosto",
+    "septiembre",
+    "octubre",
+    "noviembre",
+    "diciembre",
+];
+const ENGLISH_MONTHS_SHORT: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const SPANISH_MONTHS_SHORT: [&str; 12] = [
+    "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+];
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::English, Lang::Spanish];
+
+    // Each language is named in itself, so that it can be found whichever one is showing.
+    pub fn label(self) -> &'static str {
+        match self {
+            Lang::English => "English",
+            Lang::Spanish => "Español",
+        }
+    }
+
+    pub fn tr(&self, key: TextKey) -> &'static str {
+        match self {
+            Lang::English => english(key),
+            Lang::Spanish => spanish(key),
+        }
+    }
+
+    // The text of `key` with each `{}` in it replaced by the next of `args`.
+    pub fn trf(&self, key: TextKey, args: &[&dyn Display]) -> String {
+        let mut args = args.iter();
+        let mut parts = self.tr(key).split("{}");
+        let mut text = parts.next().unwrap_or_default().to_owned();
+        for part in parts {
+            if let Some(arg) = args.next() {
+                let _ = write!(text, "{}", arg);
+            }
+            text.push_str(part);
+        }
+        text
+    }
+
+    // `n` put into `one` when it's 1 and into `other` otherwise, like "1 day" or "3 days".
+    pub fn count<N: Display + PartialEq + From<u8>>(
+        &self,
+        n: N,
+        one: TextKey,
+        other: TextKey,
+    ) -> String {
+        let key = if n == N::from(1) { one } else { other };
+        self.trf(key, &[&n])
+    }
+
+    pub fn weekday(&self, weekday: Weekday) -> &'static str {
+        let i = weekday.num_days_from_monday() as usize;
+        match self {
+            Lang::English => ENGLISH_WEEKDAYS[i],
+            Lang::Spanish => SPANISH_WEEKDAYS[i],
+        }
+    }
+
+    pub fn weekday_short(&self, weekday: Weekday) -> &'static str {
+        let i = weekday.num_days_from_monday() as usize;
+        match self {
+            Lang::English => ENGLISH_WEEKDAYS_SHORT[i],
+            Lang::Spanish => SPANISH_WEEKDAYS_SHORT[i],
+        }
+    }
+
+    // The name of the `month`th month of the year, counting from 1.
+    pub fn month(&self, month: u32) -> &'static str {
+        let i = month.clamp(1, 12) as usize - 1;
+        match self {
+            Lang::English => ENGLISH_MONTHS[i],
+            Lang::Spanish => SPANISH_MONTHS[i],
+        }
+    }
+
+    pub fn month_short(&self, month: u32) -> &'static str {
+        let i = month.clamp(1, 12) as usize - 1;
+        match self {
+            Lang::English => ENGLISH_MONTHS_SHORT[i],
+            Lang::Spanish => SPANISH_MONTHS_SHORT[i],
+        }
+    }
+
+    // Like "May 2021".
+    pub fn month_year(&self, date: NaiveDate) -> String {
+        self.trf(
+            TextKey::MonthYear,
+            &[&self.month(date.month()), &date.year()],
+        )
+    }
+
+    // Like "Monday, March 4", for reading out rather than for showing.
+    pub fn long_date(&self, date: NaiveDate) -> String {
+        let weekday = self.weekday(date.weekday());
+        let month = self.month(date.month());
+        match self {
+            Lang::English => format!("{}, {} {}", weekday, month, date.day()),
+            Lang::Spanish => format!("{}, {} de {}", weekday, date.day(), month),
+        }
+    }
+
+    // Like "March 4, Monday".
+    pub fn month_day_weekday(&self, date: NaiveDate) -> String {
+        let weekday = self.weekday(date.weekday());
+        let month = self.month(date.month());
+        match self {
+            Lang::English => format!("{} {}, {}", month, date.day(), weekday),
+            Lang::Spanish => format!("{} de {}, {}", date.day(), month, weekday),
+        }
+    }
+}
+
+// Every piece of text the interface shows. Texts with `{}` in them are filled in with
+// `Lang::trf`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextKey {
+    // Shared
+    Undo,
+    Redo,
+    Dismiss,
+    Cancel,
+    Close,
+    Delete,
+    Remove,
+    Save,
+    Edit,
+    Today,
+    Tomorrow,
+    NextDay,
+    NoData,
+    DateNoData,
+    DayCountOne,
+    DayCountOther,
+    WeekCountOne,
+    WeekCountOther,
+    Quantity,
+    TaskNamePlaceholder,
+    TargetWorkPeriods,
+    TargetBedtime,
+    WorkPointsRange,
+    HalflifeMinutes,
+    TotalScore,
+    AverageScore,
+
+    // Header and banners
+    AllDataDeleted,
+    DemoData,
+    BackToMyData,
+    PomodoroFinished,
+    TargetReached,
+    Tracking,
+    History,
+    Settings,
+    ThemeButton,
+    ThemeLight,
+    ThemeDark,
+    ThemeSystem,
+    UnsavedChanges,
+    UntilTargetBedtime,
+    PastTargetBedtime,
+    PageNotFound,
+    BackToTracking,
+    KeyboardShortcuts,
+    ShortcutFinish,
+    ShortcutNewTask,
+    ShortcutWeeks,
+    ShortcutUndo,
+    ShortcutRedo,
+    ShortcutFocus,
+    ShortcutHelp,
+
+    // Data settings
+    Data,
+    Export,
+    Import,
+    ImportMerge,
+    MergePreview,
+    MergeSummary,
+    MergeRules,
+    MergeGoals,
+    KeepLocalGoals,
+    UseImportedGoals,
+    Merge,
+    DataMerged,
+    SleepCsvTitle,
+    SleepCsvHelp,
+    SleepCsvOverwrite,
+    Archive,
+    ArchiveHelp,
+    ArchiveBefore,
+    ArchiveAndDownload,
+    DaysArchivedBefore,
+    ArchiveOldWeeks,
+    WeekArchiveHelp,
+    WeekArchiveDays,
+    WeeksToCompact,
+    NoWeeksToArchive,
+
+    // Display and sync settings
+    Display,
+    Language,
+    Dates,
+    Times,
+    Clock12,
+    Clock24,
+    Sync,
+    SyncHelp,
+    SyncUrl,
+    BearerToken,
+    SyncEvery,
+    Syncing,
+    SyncNow,
+    LastSynced,
+    DangerZone,
+    DeleteAllData,
+    DeleteAllDataConfirm,
+
+    // Tracking page
+    PlanningAhead,
+    DayReviewTitle,
+    DayReviewSummary,
+    PlanTomorrow,
+    RecordBedtimeNow,
+    ScoreCalculation,
+    ScoreFormula,
+    ScoreFormulaCapped,
+    NoDayData,
+    NoBedtimeData,
+    BedtimeWhenDayComes,
+    ActualBedtime,
+    RecordBedtime,
+    ReplaceBedtime,
+    Replace,
+    Keep,
+    GoingToBedNow,
+    WokeUpThisMorning,
+    WokeUpOn,
+    RecordWakeTime,
+    SleptFor,
+    HowDidTheDayFeel,
+    MoodOutOf,
+    BedtimePreview,
+
+    // Tasks
+    RecurringTasks,
+    RecurringTasksHelp,
+    EveryDay,
+    NewRecurringTask,
+    AddRecurringTask,
+    RecurringDailyHelp,
+    Focus,
+    FocusTitle,
+    SortByPriority,
+    SortByPriorityTitle,
+    OneClickDelete,
+    GroupRepeatedTasks,
+    PomodoroLength,
+    BreaksEnabled,
+    BreakLength,
+    TasksPostponedOne,
+    TasksPostponedOther,
+    NewTask,
+    Category,
+    CategoryPlaceholder,
+    Notes,
+    NotesPlaceholder,
+    CountsAs,
+    WeightTitle,
+    On,
+    AddNewTask,
+    Clear,
+    ClearTitle,
+    CancelAddingSeveral,
+    AddSeveralTasks,
+    CopyUnfinished,
+    QuantityCapped,
+    Upcoming,
+    OpenDay,
+    BulkAddLabel,
+    BulkAddPlaceholder,
+    AddTheseTasks,
+    ExitFocusMode,
+    QueueEmpty,
+    BackToPlanning,
+    BlocksFrom,
+    BlockMinutes,
+    ExportIcs,
+    ExportIcsTitle,
+    TasksLeftOne,
+    TasksLeftOther,
+    FinishEstimate,
+    PastBedtimeTarget,
+    Pin,
+    Unpin,
+    StopSuggesting,
+    CurrentTask,
+    TaskListEmpty,
+    MoveToTop,
+    MoveUp,
+    MoveDown,
+    MoveToBottom,
+    DeleteAll,
+    MoreActions,
+    TimingSince,
+    Start,
+    Postpone,
+    PostponeTitle,
+    TaskCountsAs,
+    PostponedFrom,
+    Priority,
+    PriorityHigh,
+    PriorityNormal,
+    PriorityLow,
+    PomodoroLabel,
+    BreakLabel,
+    SkipBreak,
+    OnBreak,
+    Pause,
+    Resume,
+    StartPomodoro,
+    FinishTask,
+    DayNotYet,
+    ConfirmDelete,
+    AddNote,
+
+    // History
+    Week,
+    Month,
+    Year,
+    AllTime,
+    ConsistencyOverTheLast,
+    ConsistencyDays,
+    ConsistencyStats,
+    NotEnoughData,
+    Badges,
+    EarnedOn,
+    NothingRecorded,
+    ScoreOnDate,
+    DaysTracked,
+    WorkPeriodsCompleted,
+    AverageScoreRow,
+    BestDay,
+    WorstDay,
+    LongestStreak,
+    AverageBedtimeRow,
+    DaysScored,
+    ArchivedNotIncluded,
+    MinAfterTheTarget,
+    MinBeforeTheTarget,
+    OnTheTarget,
+    MonthYear,
+    PreviousMonth,
+    NextMonth,
+    ArchivedLower,
+    PreviousWeek,
+    NextWeek,
+    CloseReview,
+    ReviewThisWeek,
+    CopyWeekReport,
+    Copied,
+    CopyFailed,
+    WeekNumber,
+    WeekNumbers,
+    WeekReview,
+    TotalScoreAndWork,
+    AverageScoreSuffix,
+    ReviewNotes,
+    ReviewNotesFor,
+    WeekColumn,
+    WorkOutOf,
+    AverageSleep,
+    AverageMood,
+    ArchivedWeek,
+    WorkDone,
+    NightsWithBedtime,
+    AverageBedtime,
+    EarliestLatest,
+    NightsNearTarget,
+    NoPriorWeek,
+    ComparedWithWeekBefore,
+    ThisWeek,
+    LastWeek,
+    Change,
+    TotalScoreRow,
+    WorkPeriods,
+    AverageBedtimeDeviation,
+    SelectDateScore,
+    SelectDateNoData,
+    Streak,
+    CurrentDay,
+    Score,
+    Archived,
+    NoDataDay,
+    Details,
+    HideDetails,
+    DetailsOf,
+    TasksPlanned,
+    DayWasArchived,
+    NothingToRecordYet,
+    NoDataForDay,
+    CreateEntry,
+    DayGoals,
+    WeekendGoalsSuffix,
+    CustomGoalsSuffix,
+    EditDayGoals,
+    WorkCompleted,
+    Edited,
+    TimeTracked,
+    Bedtime,
+    WokeUp,
+    Slept,
+    Mood,
+    BreakdownOnTarget,
+    BreakdownPastTarget,
+    BreakdownBeforeTarget,
+    Breakdown,
+
+    // Goals
+    GoalsFor,
+    ResetDayGoals,
+    Goals,
+    GoalsHelp,
+    Balance,
+    TargetWorkPeriodsValue,
+    CapWorkScore,
+    TargetReachedSound,
+    PenalizeBedtimes,
+    EarlyOrLate,
+    LateOnly,
+    HalflifeValue,
+    DayBoundary,
+    NewDaysStartAt,
+    DayBoundaryHelp,
+    WeekendGoals,
+    UseWeekendGoals,
+    WeekendGoalsHelp,
+    MinutesLatePoints,
+    OnboardingWorkCount,
+    OnboardingBedtime,
+    AfterMidnight,
+    OnboardingBalance,
+    Welcome,
+    OnboardingStep,
+    Back,
+    Next,
+    FinishOnboarding,
+    SkipOnboarding,
+    Weeks,
+    HistoryWeeksAre,
+    RollingWeeks,
+    CalendarWeeksFrom,
+    NoNotifications,
+    NotificationsBlocked,
+    BedtimeReminder,
+    RemindMe,
+    MinutesBeforeBedtime,
+
+    // Badges
+    BadgeEarned,
+    BadgePerfectDay,
+    BadgePerfectDayCriteria,
+    BadgeWeekStreak,
+    BadgeWeekStreakCriteria,
+    BadgeFiftyWorkPeriods,
+    BadgeFiftyWorkPeriodsCriteria,
+    BadgeSteadyBedtimes,
+    BadgeSteadyBedtimesCriteria,
+
+    // Notices and messages
+    TargetReachedTitle,
+    UnsavedChangesPrompt,
+    AddedToPlanFor,
+    OnlySomeAdded,
+    BreakOver,
+    FinishNotYet,
+    WorkDoneOf,
+    FinishedNext,
+    FinishedNoneLeft,
+    AlreadyNightOf,
+    BedtimeNotYet,
+    WakeTimeNotYet,
+    SleepTooLongAfter,
+    InvalidWakeTime,
+    InvalidBedtime,
+    BedtimeRecorded,
+    DataDeleted,
+    DataRestored,
+    ArchiveSaveFailed,
+    NoWeeksArchived,
+    ArchivedOneWeek,
+    ArchivedWeeks,
+    SleepCsvImported,
+    SyncedCopyNewer,
+    SyncUnreachable,
+    SyncServerAnswered,
+    SyncedDataUnreadable,
+    SyncFailed,
+    TaskNoLongerPlanned,
+    DeletedTask,
+    DeletedTasks,
+    InvalidDataFile,
+    FileUnreadable,
+    BedtimeReminderTitle,
+    BedtimeReminderBody,
+    QuantityTooLow,
+    InvalidQuantity,
+    InvalidWeight,
+    ReportDate,
+    ReportWork,
+    ReportBedtime,
+    ReportScore,
+    ReportWeek,
+    MissingTaskError,
+    NotYetError,
+    OutOfRangeError,
+    SleepTooLongError,
+    EmptyNameError,
+}
+
+fn english(key: TextKey) -> &'static str {
+    match key {
+        TextKey::Undo => "Undo",
+        TextKey::Redo => "Redo",
+        TextKey::Dismiss => "Dismiss",
+        TextKey::Cancel => "Cancel",
+        TextKey::Close => "Close",
+        TextKey::Delete => "Delete",
+        TextKey::Remove => "Remove",
+        TextKey::Save => "Save",
+        TextKey::Edit => "Edit",
+        TextKey::Today => "Today",
+        TextKey::Tomorrow => "Tomorrow?",
+        TextKey::NextDay => " (next day)",
+        TextKey::NoData => "no data",
+        TextKey::DateNoData => "{}: no data",
+        TextKey::DayCountOne => "{} day",
+        TextKey::DayCountOther => "{} days",
+        TextKey::WeekCountOne => "{} week",
+        TextKey::WeekCountOther => "{} weeks",
+        TextKey::Quantity => "Quantity",
+        TextKey::TaskNamePlaceholder => "Name of task",
+        TextKey::TargetWorkPeriods => "Target work periods: ",
+        TextKey::TargetBedtime => "Target bedtime: ",
+        TextKey::WorkPointsRange => "Work points (0-100): ",
+        TextKey::HalflifeMinutes => "Bedtime points half-life (minutes): ",
+        TextKey::TotalScore => "Total score: {}",
+        TextKey::AverageScore => "Average score: {}",
+        TextKey::AllDataDeleted => "All data was deleted. ",
+        TextKey::DemoData => "This is demo data; nothing you change is saved. ",
+        TextKey::BackToMyData => "Back to my data",
+        TextKey::PomodoroFinished => "Pomodoro finished!",
+        TextKey::TargetReached => "You reached today's work target. Well done!",
+        TextKey::Tracking => "Tracking",
+        TextKey::History => "History",
+        TextKey::Settings => "Settings",
+        TextKey::ThemeButton => "Theme: {}",
+        TextKey::ThemeLight => "Light",
+        TextKey::ThemeDark => "Dark",
+        TextKey::ThemeSystem => "System",
+        TextKey::UnsavedChanges => "Unsaved changes",
+        TextKey::UntilTargetBedtime => "{} until target bedtime",
+        TextKey::PastTargetBedtime => "{} past target bedtime",
+        TextKey::PageNotFound => "Page not found",
+        TextKey::BackToTracking => "Back to tracking",
+        TextKey::KeyboardShortcuts => "Keyboard shortcuts",
+        TextKey::ShortcutFinish => "d: finish the current task",
+        TextKey::ShortcutNewTask => "n: focus the new task name",
+        TextKey::ShortcutWeeks => "[ / ]: previous / next week",
+        TextKey::ShortcutUndo => "Ctrl+Z: undo",
+        TextKey::ShortcutRedo => "Ctrl+Shift+Z or Ctrl+Y: redo",
+        TextKey::ShortcutFocus => "Escape: leave focus mode",
+        TextKey::ShortcutHelp => "?: show or hide this help",
+        TextKey::Data => "Data",
+        TextKey::Export => "Export",
+        TextKey::Import => "Import: ",
+        TextKey::ImportMerge => "Merge with the data on this device instead of replacing it",
+        TextKey::MergePreview => "Merge preview",
+        TextKey::MergeSummary => "{} days added, {} days merged, {} conflicts resolved toward import, {} tasks added",
+        TextKey::MergeRules => "Days on both keep the copy with more work done, filling in a missing bedtime from the other.",
+        TextKey::MergeGoals => "Goals",
+        TextKey::KeepLocalGoals => "Keep the goals on this device",
+        TextKey::UseImportedGoals => "Use the imported goals",
+        TextKey::Merge => "Merge",
+        TextKey::DataMerged => "Merged the imported data: {}",
+        TextKey::SleepCsvTitle => "Bedtimes from a sleep tracker",
+        TextKey::SleepCsvHelp => "Reads a CSV file with a date, sleep start and sleep end on each row.",
+        TextKey::SleepCsvOverwrite => "Overwrite bedtimes I already recorded",
+        TextKey::Archive => "Archive",
+        TextKey::ArchiveHelp => "Downloads the days before the chosen date, then removes them from the app.",
+        TextKey::ArchiveBefore => "Archive days before: ",
+        TextKey::ArchiveAndDownload => "Archive and download",
+        TextKey::DaysArchivedBefore => "{} days before {} have been archived.",
+        TextKey::ArchiveOldWeeks => "Archive old weeks",
+        TextKey::WeekArchiveHelp => "Keeps only each week's summary, removing its days from the app.",
+        TextKey::WeekArchiveDays => "Weeks that ended more than this many days ago: ",
+        TextKey::WeeksToCompact => "{} from {} to {} will be compacted, removing {}.",
+        TextKey::NoWeeksToArchive => "No weeks are old enough to archive.",
+        TextKey::Display => "Display",
+        TextKey::Language => "Language: ",
+        TextKey::Dates => "Dates: ",
+        TextKey::Times => "Times: ",
+        TextKey::Clock12 => "12-hour",
+        TextKey::Clock24 => "24-hour",
+        TextKey::Sync => "Sync",
+        TextKey::SyncHelp => "Keeps a copy of the data at a URL that answers GET and PUT with JSON. The newest copy wins.",
+        TextKey::SyncUrl => "Sync URL: ",
+        TextKey::BearerToken => "Bearer token: ",
+        TextKey::SyncEvery => "Sync after this many changes (0 for never): ",
+        TextKey::Syncing => "Syncing…",
+        TextKey::SyncNow => "Sync now",
+        TextKey::LastSynced => "Last synced at {}",
+        TextKey::DangerZone => "Danger zone",
+        TextKey::DeleteAllData => "Delete all data",
+        TextKey::DeleteAllDataConfirm => "This deletes every task, goal and day of history. Type DELETE to confirm.",
+        TextKey::PlanningAhead => "Planning ahead: tasks can be added and arranged, but nothing can be recorded until this day comes.",
+        TextKey::DayReviewTitle => "That's everything planned for today",
+        TextKey::DayReviewSummary => "Work done: {} of {} · Score so far: {}",
+        TextKey::PlanTomorrow => "Plan tomorrow",
+        TextKey::RecordBedtimeNow => "Record bedtime now",
+        TextKey::ScoreCalculation => "Score Calculation",
+        TextKey::ScoreFormula => "Score = Work points * Work periods completed / Target work periods + Sleep points * (1/2)^(|Actual bedtime - Target bedtime|/Bedtime points half-life)",
+        TextKey::ScoreFormulaCapped => "Score = min(Work points * Work periods completed / Target work periods, Work points) + Sleep points * (1/2)^(|Actual bedtime - Target bedtime|/Bedtime points half-life)",
+        TextKey::NoDayData => "(no data)",
+        TextKey::NoBedtimeData => "(no bedtime data)",
+        TextKey::BedtimeWhenDayComes => "Bedtime can be recorded once this day comes.",
+        TextKey::ActualBedtime => "Actual bedtime: ",
+        TextKey::RecordBedtime => "Record bedtime",
+        TextKey::ReplaceBedtime => "Replace the bedtime of {} with {}? ",
+        TextKey::Replace => "Replace",
+        TextKey::Keep => "Keep",
+        TextKey::GoingToBedNow => "I'm going to bed now",
+        TextKey::WokeUpThisMorning => "Woke up this morning at: ",
+        TextKey::WokeUpOn => "Woke up on {} at: ",
+        TextKey::RecordWakeTime => "Record wake time",
+        TextKey::SleptFor => " Slept {}",
+        TextKey::HowDidTheDayFeel => "How did the day feel? ",
+        TextKey::MoodOutOf => "{} of {}",
+        TextKey::BedtimePreview => "If you go to bed now ({}): score {} · at target ({}): {} · 30 min later: {} · 60 min later: {}",
+        TextKey::RecurringTasks => "Recurring tasks",
+        TextKey::RecurringTasksHelp => "These are added to the plan at the start of each day they are due.",
+        TextKey::EveryDay => "every day",
+        TextKey::NewRecurringTask => "New recurring task: ",
+        TextKey::AddRecurringTask => "Add recurring task",
+        TextKey::RecurringDailyHelp => "Leave every day unchecked to add the task daily.",
+        TextKey::Focus => "Focus",
+        TextKey::FocusTitle => "Show only the current task",
+        TextKey::SortByPriority => "Sort by priority",
+        TextKey::SortByPriorityTitle => "Move high-priority tasks up and low-priority ones down",
+        TextKey::OneClickDelete => "One-click delete",
+        TextKey::GroupRepeatedTasks => "Group repeated tasks",
+        TextKey::PomodoroLength => "Pomodoro length (minutes)",
+        TextKey::BreaksEnabled => "Take a break after each task",
+        TextKey::BreakLength => "Break length (minutes)",
+        TextKey::TasksPostponedOne => "{} task postponed to tomorrow",
+        TextKey::TasksPostponedOther => "{} tasks postponed to tomorrow",
+        TextKey::NewTask => "New task: ",
+        TextKey::Category => "Category",
+        TextKey::CategoryPlaceholder => "Category (optional)",
+        TextKey::Notes => "Notes",
+        TextKey::NotesPlaceholder => "Notes (optional)",
+        TextKey::CountsAs => "Counts as: ",
+        TextKey::WeightTitle => "How many work periods finishing this task counts as",
+        TextKey::On => "On: ",
+        TextKey::AddNewTask => "Add new task",
+        TextKey::Clear => "Clear",
+        TextKey::ClearTitle => "Empty the new task form (Esc)",
+        TextKey::CancelAddingSeveral => "Cancel adding several",
+        TextKey::AddSeveralTasks => "Add several tasks",
+        TextKey::CopyUnfinished => "Copy unfinished tasks from yesterday",
+        TextKey::QuantityCapped => "Quantity is capped at {}",
+        TextKey::Upcoming => "Upcoming ({})",
+        TextKey::OpenDay => "Open day",
+        TextKey::BulkAddLabel => "Tasks to add, one per line",
+        TextKey::BulkAddPlaceholder => "One task per line, such as \"flashcards x3\"",
+        TextKey::AddTheseTasks => "Add these tasks",
+        TextKey::ExitFocusMode => "Exit focus mode",
+        TextKey::QueueEmpty => "Queue empty",
+        TextKey::BackToPlanning => "Back to planning",
+        TextKey::BlocksFrom => "Blocks from ",
+        TextKey::BlockMinutes => " of (minutes) ",
+        TextKey::ExportIcs => "Export .ics",
+        TextKey::ExportIcsTitle => "Download the plan and target bedtime for a calendar app",
+        TextKey::TasksLeftOne => "{} task left",
+        TextKey::TasksLeftOther => "{} tasks left",
+        TextKey::FinishEstimate => "{} at about {} min each: done around {}",
+        TextKey::PastBedtimeTarget => "{}, {} min past your {} bedtime target",
+        TextKey::Pin => "Pin",
+        TextKey::Unpin => "Unpin",
+        TextKey::StopSuggesting => "Stop suggesting this task until it is used again",
+        TextKey::CurrentTask => "CURRENT TASK: ",
+        TextKey::TaskListEmpty => "(task list is empty)",
+        TextKey::MoveToTop => "Move to top",
+        TextKey::MoveUp => "Move up",
+        TextKey::MoveDown => "Move down",
+        TextKey::MoveToBottom => "Move to bottom",
+        TextKey::DeleteAll => "Delete all",
+        TextKey::MoreActions => "More actions",
+        TextKey::TimingSince => "(timing since {}) ",
+        TextKey::Start => "Start",
+        TextKey::Postpone => "Postpone",
+        TextKey::PostponeTitle => "Move this task to the end of tomorrow's plan",
+        TextKey::TaskCountsAs => "{} (counts as {})",
+        TextKey::PostponedFrom => "{} (postponed from {})",
+        TextKey::Priority => "Priority",
+        TextKey::PriorityHigh => "High",
+        TextKey::PriorityNormal => "Normal",
+        TextKey::PriorityLow => "Low",
+        TextKey::PomodoroLabel => " Pomodoro: ",
+        TextKey::BreakLabel => "Break: ",
+        TextKey::SkipBreak => "Skip break",
+        TextKey::OnBreak => "Take the break first, or skip it",
+        TextKey::Pause => "Pause",
+        TextKey::Resume => "Resume",
+        TextKey::StartPomodoro => "Start pomodoro",
+        TextKey::FinishTask => "DONE!",
+        TextKey::DayNotYet => "This day hasn't come yet",
+        TextKey::ConfirmDelete => "Confirm delete",
+        TextKey::AddNote => "Add note",
+        TextKey::Week => "Week",
+        TextKey::Month => "Month",
+        TextKey::Year => "Year",
+        TextKey::AllTime => "All time",
+        TextKey::ConsistencyOverTheLast => "Bedtime consistency over the last ",
+        TextKey::ConsistencyDays => " days: ",
+        TextKey::ConsistencyStats => "±{} min around {} ({} nights)",
+        TextKey::NotEnoughData => "not enough data",
+        TextKey::Badges => "Badges",
+        TextKey::EarnedOn => " (earned on {})",
+        TextKey::NothingRecorded => "Nothing has been recorded yet.",
+        TextKey::ScoreOnDate => "{} on {}",
+        TextKey::DaysTracked => "Days tracked",
+        TextKey::WorkPeriodsCompleted => "Work periods completed",
+        TextKey::AverageScoreRow => "Average score",
+        TextKey::BestDay => "Best day",
+        TextKey::WorstDay => "Worst day",
+        TextKey::LongestStreak => "Longest streak",
+        TextKey::AverageBedtimeRow => "Average bedtime",
+        TextKey::DaysScored => "Scores and bedtimes are over the {} of {} days with a bedtime recorded.",
+        TextKey::ArchivedNotIncluded => "Days archived before {} aren't included.",
+        TextKey::MinAfterTheTarget => "{} min after the target",
+        TextKey::MinBeforeTheTarget => "{} min before the target",
+        TextKey::OnTheTarget => "on the target",
+        TextKey::MonthYear => "{} {}",
+        TextKey::PreviousMonth => "Previous Month",
+        TextKey::NextMonth => "Next Month",
+        TextKey::ArchivedLower => "archived",
+        TextKey::PreviousWeek => "Previous Week",
+        TextKey::NextWeek => "Next Week",
+        TextKey::CloseReview => "Close review",
+        TextKey::ReviewThisWeek => "Review this week",
+        TextKey::CopyWeekReport => "Copy week report",
+        TextKey::Copied => " Copied!",
+        TextKey::CopyFailed => "Couldn't copy to the clipboard; copy the report below instead.",
+        TextKey::WeekNumber => "Week {}",
+        TextKey::WeekNumbers => "Weeks {}–{}",
+        TextKey::WeekReview => "Week review",
+        TextKey::TotalScoreAndWork => "Total score: {} · Work: {}/{}",
+        TextKey::AverageScoreSuffix => " · Average score: {}",
+        TextKey::ReviewNotes => "Review notes",
+        TextKey::ReviewNotesFor => "Review notes for the week from {}",
+        TextKey::WeekColumn => "WEEK",
+        TextKey::WorkOutOf => "Work: {}/{}",
+        TextKey::AverageSleep => "Average sleep: {}",
+        TextKey::AverageMood => "Average mood: {}",
+        TextKey::ArchivedWeek => "Archived week: {} – {}",
+        TextKey::WorkDone => "Work done: {}",
+        TextKey::NightsWithBedtime => "Nights with a bedtime: {}",
+        TextKey::AverageBedtime => "Average bedtime: {}",
+        TextKey::EarliestLatest => "Earliest: {}, latest: {}",
+        TextKey::NightsNearTarget => "Nights within a half-life of the target: {}",
+        TextKey::NoPriorWeek => "Compared with the week before: no prior data",
+        TextKey::ComparedWithWeekBefore => "Compared with the week before",
+        TextKey::ThisWeek => "This week",
+        TextKey::LastWeek => "Last week",
+        TextKey::Change => "Change",
+        TextKey::TotalScoreRow => "Total score",
+        TextKey::WorkPeriods => "Work periods",
+        TextKey::AverageBedtimeDeviation => "Average bedtime deviation (min)",
+        TextKey::SelectDateScore => "Select {}, score {}",
+        TextKey::SelectDateNoData => "Select {}, no data",
+        TextKey::Streak => "Streak: {} (longest: {})",
+        TextKey::CurrentDay => "CURRENT DAY",
+        TextKey::Score => "Score: {}",
+        TextKey::Archived => "Archived",
+        TextKey::NoDataDay => "No data",
+        TextKey::Details => "Details",
+        TextKey::HideDetails => "Hide details",
+        TextKey::DetailsOf => "Details of {}",
+        TextKey::TasksPlanned => "Tasks Planned: {}",
+        TextKey::DayWasArchived => "This day was archived.",
+        TextKey::NothingToRecordYet => "Nothing can be recorded on this day yet.",
+        TextKey::NoDataForDay => "No data for this day. ",
+        TextKey::CreateEntry => "Create an entry",
+        TextKey::DayGoals => "Goals: {} work periods, bedtime {}, {} points for work",
+        TextKey::WeekendGoalsSuffix => " (weekend goals)",
+        TextKey::CustomGoalsSuffix => " (custom goals)",
+        TextKey::EditDayGoals => "Edit goals for this day",
+        TextKey::WorkCompleted => "Work Completed: {}",
+        TextKey::Edited => " (edited)",
+        TextKey::TimeTracked => "Time Tracked: {} min",
+        TextKey::Bedtime => "Bedtime: ",
+        TextKey::WokeUp => "Woke up: {}",
+        TextKey::Slept => "Slept: {}",
+        TextKey::Mood => "Mood: {} {}",
+        TextKey::BreakdownOnTarget => " · on target",
+        TextKey::BreakdownPastTarget => " · {} min past target",
+        TextKey::BreakdownBeforeTarget => " · {} min before target",
+        TextKey::Breakdown => "Work {}/{} · Sleep {}/{}{}",
+        TextKey::GoalsFor => "Goals for {}",
+        TextKey::ResetDayGoals => "Reset to default goals",
+        TextKey::Goals => "Goals",
+        TextKey::GoalsHelp => "These defaults apply to days recorded from now on.",
+        TextKey::Balance => "Work/sleep balance: {} points for work, {} points for sleep",
+        TextKey::TargetWorkPeriodsValue => "Target work periods: {}",
+        TextKey::CapWorkScore => "No extra points for work beyond the target",
+        TextKey::TargetReachedSound => "Play a sound when I reach the work target",
+        TextKey::PenalizeBedtimes => "Penalize bedtimes that are: ",
+        TextKey::EarlyOrLate => "early or late",
+        TextKey::LateOnly => "late only",
+        TextKey::HalflifeValue => "Bedtime points half-life: {} minutes",
+        TextKey::DayBoundary => "Day boundary",
+        TextKey::NewDaysStartAt => "New days start at ",
+        TextKey::DayBoundaryHelp => "Work finished before then counts towards the previous day.",
+        TextKey::WeekendGoals => "Weekend goals",
+        TextKey::UseWeekendGoals => "Use different goals on Saturdays and Sundays",
+        TextKey::WeekendGoalsHelp => "Weekend days recorded from now on start with these goals.",
+        TextKey::MinutesLatePoints => "{} min late: {} points",
+        TextKey::OnboardingWorkCount => "How many work periods do you aim to finish each day? ",
+        TextKey::OnboardingBedtime => "When do you want to go to bed? ",
+        TextKey::AfterMidnight => "That's after midnight, on the next day",
+        TextKey::OnboardingBalance => "Of a day's 100 points, {} go to work and {} to sleep.",
+        TextKey::Welcome => "Welcome to slorz",
+        TextKey::OnboardingStep => "Step {} of 3: pick your daily goals. You can change them later in the settings.",
+        TextKey::Back => "Back",
+        TextKey::Next => "Next",
+        TextKey::FinishOnboarding => "Done",
+        TextKey::SkipOnboarding => "Skip and use the defaults",
+        TextKey::Weeks => "Weeks",
+        TextKey::HistoryWeeksAre => "History weeks are ",
+        TextKey::RollingWeeks => "the last 7 days",
+        TextKey::CalendarWeeksFrom => "calendar weeks from {}",
+        TextKey::NoNotifications => "Bedtime reminders need a browser that supports notifications.",
+        TextKey::NotificationsBlocked => "Notifications are blocked for this site. Allow them in your browser settings to get bedtime reminders.",
+        TextKey::BedtimeReminder => "Bedtime reminder",
+        TextKey::RemindMe => "Remind me ",
+        TextKey::MinutesBeforeBedtime => " minutes before my target bedtime",
+        TextKey::BadgeEarned => "Badge earned: {}!",
+        TextKey::BadgePerfectDay => "First perfect day",
+        TextKey::BadgePerfectDayCriteria => "Score 100 or more in a day",
+        TextKey::BadgeWeekStreak => "7-day streak",
+        TextKey::BadgeWeekStreakCriteria => "Meet both targets 7 days in a row",
+        TextKey::BadgeFiftyWorkPeriods => "50 work periods",
+        TextKey::BadgeFiftyWorkPeriodsCriteria => "Complete 50 work periods in all",
+        TextKey::BadgeSteadyBedtimes => "Steady bedtimes",
+        TextKey::BadgeSteadyBedtimesCriteria => "Go to bed within 10 minutes of the target 5 nights running",
+        TextKey::TargetReachedTitle => "Work target reached!",
+        TextKey::UnsavedChangesPrompt => "You have unsaved changes.",
+        TextKey::AddedToPlanFor => "Added to the plan for {}",
+        TextKey::OnlySomeAdded => "Only {} were added; that's the most at once",
+        TextKey::BreakOver => "Break's over",
+        TextKey::FinishNotYet => "Tasks can't be finished on a day that hasn't come yet",
+        TextKey::WorkDoneOf => "{} of {} done",
+        TextKey::FinishedNext => "Finished {}, {}. Next: {}",
+        TextKey::FinishedNoneLeft => "Finished {}, {}. No tasks left",
+        TextKey::AlreadyNightOf => "It's already the night of {}; a later day cutoff in settings keeps bedtimes after midnight on the day before",
+        TextKey::BedtimeNotYet => "Bedtime can't be recorded on a day that hasn't come yet",
+        TextKey::WakeTimeNotYet => "Wake time can't be recorded for a night that hasn't come yet",
+        TextKey::SleepTooLongAfter => "Waking up at {} isn't within {} hours after going to bed at {}",
+        TextKey::InvalidWakeTime => "\"{}\" is not a valid wake time (expected a time like {})",
+        TextKey::InvalidBedtime => "\"{}\" is not a valid bedtime (expected a time like {})",
+        TextKey::BedtimeRecorded => "Bedtime recorded at {}. The day's score: {}",
+        TextKey::DataDeleted => "All data was deleted",
+        TextKey::DataRestored => "Your data was restored",
+        TextKey::ArchiveSaveFailed => "Couldn't save the archived days: {}",
+        TextKey::NoWeeksArchived => "No weeks were old enough to archive",
+        TextKey::ArchivedOneWeek => "Archived {} week",
+        TextKey::ArchivedWeeks => "Archived {} weeks",
+        TextKey::SleepCsvImported => "Imported {} bedtimes, skipped {}, couldn't read {} rows",
+        TextKey::SyncedCopyNewer => "The synced copy was newer, so it replaced the data here",
+        TextKey::SyncUnreachable => "Couldn't reach the sync server",
+        TextKey::SyncServerAnswered => "The sync server answered {} {}",
+        TextKey::SyncedDataUnreadable => "Couldn't read the synced data: {}",
+        TextKey::SyncFailed => "Couldn't sync: {}",
+        TextKey::TaskNoLongerPlanned => "That task is no longer planned for this day",
+        TextKey::DeletedTask => "Deleted \"{}\"",
+        TextKey::DeletedTasks => "Deleted {} tasks",
+        TextKey::InvalidDataFile => "Invalid data file: {}",
+        TextKey::FileUnreadable => "Could not read the selected file",
+        TextKey::BedtimeReminderTitle => "Time to wind down",
+        TextKey::BedtimeReminderBody => "Your target bedtime is in {} minutes or less.",
+        TextKey::QuantityTooLow => "Quantity must be at least 1",
+        TextKey::InvalidQuantity => "\"{}\" is not a valid quantity",
+        TextKey::InvalidWeight => "\"{}\" is not a valid weight",
+        TextKey::ReportDate => "Date",
+        TextKey::ReportWork => "Work",
+        TextKey::ReportBedtime => "Bedtime",
+        TextKey::ReportScore => "Score",
+        TextKey::ReportWeek => "Week",
+        TextKey::MissingTaskError => "That task is not planned",
+        TextKey::NotYetError => "{} hasn't come yet",
+        TextKey::OutOfRangeError => "The value is out of range",
+        TextKey::SleepTooLongError => "The wake time isn't within {} hours after the bedtime",
+        TextKey::EmptyNameError => "The name is empty",
+    }
+}
+
+fn spanish(key: TextKey) -> &'static str {
+    match key {
+        TextKey::Undo => "Deshacer",
+        TextKey::Redo => "Rehacer",
+        TextKey::Dismiss => "Descartar",
+        TextKey::Cancel => "Cancelar",
+        TextKey::Close => "Cerrar",
+        TextKey::Delete => "Borrar",
+        TextKey::Remove => "Quitar",
+        TextKey::Save => "Guardar",
+        TextKey::Edit => "Editar",
+        TextKey::Today => "Hoy",
+        TextKey::Tomorrow => "¿Mañana?",
+        TextKey::NextDay => " (día siguiente)",
+        TextKey::NoData => "sin datos",
+        TextKey::DateNoData => "{}: sin datos",
+        TextKey::DayCountOne => "{} día",
+        TextKey::DayCountOther => "{} días",
+        TextKey::WeekCountOne => "{} semana",
+        TextKey::WeekCountOther => "{} semanas",
+        TextKey::Quantity => "Cantidad",
+        TextKey::TaskNamePlaceholder => "Nombre de la tarea",
+        TextKey::TargetWorkPeriods => "Periodos de trabajo objetivo: ",
+        TextKey::TargetBedtime => "Hora objetivo de dormir: ",
+        TextKey::WorkPointsRange => "Puntos de trabajo (0-100): ",
+        TextKey::HalflifeMinutes => "Vida media de los puntos de sueño (minutos): ",
+        TextKey::TotalScore => "Puntuación total: {}",
+        TextKey::AverageScore => "Puntuación media: {}",
+        TextKey::AllDataDeleted => "Se borraron todos los datos. ",
+        TextKey::DemoData => "Estos son datos de demostración; no se guarda nada de lo que cambies. ",
+        TextKey::BackToMyData => "Volver a mis datos",
+        TextKey::PomodoroFinished => "¡Pomodoro terminado!",
+        TextKey::TargetReached => "Alcanzaste la meta de trabajo de hoy. ¡Bien hecho!",
+        TextKey::Tracking => "Seguimiento",
+        TextKey::History => "Historial",
+        TextKey::Settings => "Ajustes",
+        TextKey::ThemeButton => "Tema: {}",
+        TextKey::ThemeLight => "Claro",
+        TextKey::ThemeDark => "Oscuro",
+        TextKey::ThemeSystem => "Sistema",
+        TextKey::UnsavedChanges => "Cambios sin guardar",
+        TextKey::UntilTargetBedtime => "{} para la hora objetivo de dormir",
+        TextKey::PastTargetBedtime => "{} después de la hora objetivo de dormir",
+        TextKey::PageNotFound => "Página no encontrada",
+        TextKey::BackToTracking => "Volver al seguimiento",
+        TextKey::KeyboardShortcuts => "Atajos de teclado",
+        TextKey::ShortcutFinish => "d: terminar la tarea actual",
+        TextKey::ShortcutNewTask => "n: ir al nombre de la nueva tarea",
+        TextKey::ShortcutWeeks => "[ / ]: semana anterior / siguiente",
+        TextKey::ShortcutUndo => "Ctrl+Z: deshacer",
+        TextKey::ShortcutRedo => "Ctrl+Shift+Z o Ctrl+Y: rehacer",
+        TextKey::ShortcutFocus => "Escape: salir del modo concentración",
+        TextKey::ShortcutHelp => "?: mostrar u ocultar esta ayuda",
+        TextKey::Data => "Datos",
+        TextKey::Export => "Exportar",
+        TextKey::Import => "Importar: ",
+        TextKey::ImportMerge => "Combinar con los datos de este dispositivo en lugar de reemplazarlos",
+        TextKey::MergePreview => "Vista previa de la combinación",
+        TextKey::MergeSummary => "{} días añadidos, {} días combinados, {} conflictos resueltos a favor de la importación, {} tareas añadidas",
+        TextKey::MergeRules => "Los días presentes en ambos conservan la copia con más trabajo hecho y completan la hora de dormir que falte con la otra.",
+        TextKey::MergeGoals => "Objetivos",
+        TextKey::KeepLocalGoals => "Conservar los objetivos de este dispositivo",
+        TextKey::UseImportedGoals => "Usar los objetivos importados",
+        TextKey::Merge => "Combinar",
+        TextKey::DataMerged => "Datos importados combinados: {}",
+        TextKey::SleepCsvTitle => "Horas de dormir de un monitor de sueño",
+        TextKey::SleepCsvHelp => "Lee un archivo CSV con una fecha, el inicio y el fin del sueño en cada fila.",
+        TextKey::SleepCsvOverwrite => "Sobrescribir las horas de dormir que ya registré",
+        TextKey::Archive => "Archivo",
+        TextKey::ArchiveHelp => "Descarga los días anteriores a la fecha elegida y luego los quita de la aplicación.",
+        TextKey::ArchiveBefore => "Archivar los días antes del: ",
+        TextKey::ArchiveAndDownload => "Archivar y descargar",
+        TextKey::DaysArchivedBefore => "{} días antes del {} ya están archivados.",
+        TextKey::ArchiveOldWeeks => "Archivar semanas antiguas",
+        TextKey::WeekArchiveHelp => "Conserva solo el resumen de cada semana y quita sus días de la aplicación.",
+        TextKey::WeekArchiveDays => "Semanas que terminaron hace más de estos días: ",
+        TextKey::WeeksToCompact => "Se compactarán {} del {} al {}, quitando {}.",
+        TextKey::NoWeeksToArchive => "Ninguna semana es lo bastante antigua para archivarla.",
+        TextKey::Display => "Pantalla",
+        TextKey::Language => "Idioma: ",
+        TextKey::Dates => "Fechas: ",
+        TextKey::Times => "Horas: ",
+        TextKey::Clock12 => "12 horas",
+        TextKey::Clock24 => "24 horas",
+        TextKey::Sync => "Sincronización",
+        TextKey::SyncHelp => "Guarda una copia de los datos en una URL que responde a GET y PUT con JSON. Gana la copia más reciente.",
+        TextKey::SyncUrl => "URL de sincronización: ",
+        TextKey::BearerToken => "Token de portador: ",
+        TextKey::SyncEvery => "Sincronizar tras este número de cambios (0 para nunca): ",
+        TextKey::Syncing => "Sincronizando…",
+        TextKey::SyncNow => "Sincronizar ahora",
+        TextKey::LastSynced => "Última sincronización a las {}",
+        TextKey::DangerZone => "Zona de peligro",
+        TextKey::DeleteAllData => "Borrar todos los datos",
+        TextKey::DeleteAllDataConfirm => "Esto borra todas las tareas, metas y días del historial. Escribe DELETE para confirmar.",
+        TextKey::PlanningAhead => "Planificando: se pueden añadir y ordenar tareas, pero no se puede registrar nada hasta que llegue este día.",
+        TextKey::DayReviewTitle => "Eso es todo lo planeado para hoy",
+        TextKey::DayReviewSummary => "Trabajo hecho: {} de {} · Puntuación hasta ahora: {}",
+        TextKey::PlanTomorrow => "Planear mañana",
+        TextKey::RecordBedtimeNow => "Registrar la hora de dormir ahora",
+        TextKey::ScoreCalculation => "Cálculo de la puntuación",
+        TextKey::ScoreFormula => "Puntuación = Puntos de trabajo * Periodos de trabajo completados / Periodos de trabajo objetivo + Puntos de sueño * (1/2)^(|Hora real de dormir - Hora objetivo de dormir|/Vida media de los puntos de sueño)",
+        TextKey::ScoreFormulaCapped => "Puntuación = mín(Puntos de trabajo * Periodos de trabajo completados / Periodos de trabajo objetivo, Puntos de trabajo) + Puntos de sueño * (1/2)^(|Hora real de dormir - Hora objetivo de dormir|/Vida media de los puntos de sueño)",
+        TextKey::NoDayData => "(sin datos)",
+        TextKey::NoBedtimeData => "(sin datos de la hora de dormir)",
+        TextKey::BedtimeWhenDayComes => "La hora de dormir se puede registrar cuando llegue este día.",
+        TextKey::ActualBedtime => "Hora real de dormir: ",
+        TextKey::RecordBedtime => "Registrar la hora de dormir",
+        TextKey::ReplaceBedtime => "¿Cambiar la hora de dormir de {} por {}? ",
+        TextKey::Replace => "Cambiar",
+        TextKey::Keep => "Mantener",
+        TextKey::GoingToBedNow => "Me voy a dormir ahora",
+        TextKey::WokeUpThisMorning => "Me desperté esta mañana a las: ",
+        TextKey::WokeUpOn => "Me desperté el {} a las: ",
+        TextKey::RecordWakeTime => "Registrar la hora de despertar",
+        TextKey::SleptFor => " Dormí {}",
+        TextKey::HowDidTheDayFeel => "¿Cómo fue el día? ",
+        TextKey::MoodOutOf => "{} de {}",
+        TextKey::BedtimePreview => "Si te vas a dormir ahora ({}): puntuación {} · a la hora objetivo ({}): {} · 30 min más tarde: {} · 60 min más tarde: {}",
+        TextKey::RecurringTasks => "Tareas recurrentes",
+        TextKey::RecurringTasksHelp => "Se añaden al plan al comienzo de cada día que les toca.",
+        TextKey::EveryDay => "todos los días",
+        TextKey::NewRecurringTask => "Nueva tarea recurrente: ",
+        TextKey::AddRecurringTask => "Añadir tarea recurrente",
+        TextKey::RecurringDailyHelp => "Deja todos los días sin marcar para añadir la tarea a diario.",
+        TextKey::Focus => "Concentración",
+        TextKey::FocusTitle => "Mostrar solo la tarea actual",
+        TextKey::SortByPriority => "Ordenar por prioridad",
+        TextKey::SortByPriorityTitle => "Sube las tareas de prioridad alta y baja las de prioridad baja",
+        TextKey::OneClickDelete => "Borrar con un clic",
+        TextKey::GroupRepeatedTasks => "Agrupar tareas repetidas",
+        TextKey::PomodoroLength => "Duración del pomodoro (minutos)",
+        TextKey::BreaksEnabled => "Tomar un descanso después de cada tarea",
+        TextKey::BreakLength => "Duración del descanso (minutos)",
+        TextKey::TasksPostponedOne => "{} tarea pospuesta para mañana",
+        TextKey::TasksPostponedOther => "{} tareas pospuestas para mañana",
+        TextKey::NewTask => "Nueva tarea: ",
+        TextKey::Category => "Categoría",
+        TextKey::CategoryPlaceholder => "Categoría (opcional)",
+        TextKey::Notes => "Notas",
+        TextKey::NotesPlaceholder => "Notas (opcional)",
+        TextKey::CountsAs => "Cuenta como: ",
+        TextKey::WeightTitle => "Cuántos periodos de trabajo cuenta terminar esta tarea",
+        TextKey::On => "El: ",
+        TextKey::AddNewTask => "Añadir nueva tarea",
+        TextKey::Clear => "Vaciar",
+        TextKey::ClearTitle => "Vaciar el formulario de nueva tarea (Esc)",
+        TextKey::CancelAddingSeveral => "Cancelar añadir varias",
+        TextKey::AddSeveralTasks => "Añadir varias tareas",
+        TextKey::CopyUnfinished => "Copiar las tareas sin terminar de ayer",
+        TextKey::QuantityCapped => "La cantidad máxima es {}",
+        TextKey::Upcoming => "Próximas ({})",
+        TextKey::OpenDay => "Abrir día",
+        TextKey::BulkAddLabel => "Tareas para añadir, una por línea",
+        TextKey::BulkAddPlaceholder => "Una tarea por línea, como \"tarjetas x3\"",
+        TextKey::AddTheseTasks => "Añadir estas tareas",
+        TextKey::ExitFocusMode => "Salir del modo concentración",
+        TextKey::QueueEmpty => "Cola vacía",
+        TextKey::BackToPlanning => "Volver a la planificación",
+        TextKey::BlocksFrom => "Bloques desde las ",
+        TextKey::BlockMinutes => " de (minutos) ",
+        TextKey::ExportIcs => "Exportar .ics",
+        TextKey::ExportIcsTitle => "Descargar el plan y la hora objetivo de dormir para una aplicación de calendario",
+        TextKey::TasksLeftOne => "Queda {} tarea",
+        TextKey::TasksLeftOther => "Quedan {} tareas",
+        TextKey::FinishEstimate => "{} a unos {} min cada una: terminadas hacia las {}",
+        TextKey::PastBedtimeTarget => "{}, {} min después de tu hora objetivo de dormir de las {}",
+        TextKey::Pin => "Fijar",
+        TextKey::Unpin => "Desfijar",
+        TextKey::StopSuggesting => "Dejar de sugerir esta tarea hasta que se vuelva a usar",
+        TextKey::CurrentTask => "TAREA ACTUAL: ",
+        TextKey::TaskListEmpty => "(la lista de tareas está vacía)",
+        TextKey::MoveToTop => "Mover al principio",
+        TextKey::MoveUp => "Subir",
+        TextKey::MoveDown => "Bajar",
+        TextKey::MoveToBottom => "Mover al final",
+        TextKey::DeleteAll => "Borrar todas",
+        TextKey::MoreActions => "Más acciones",
+        TextKey::TimingSince => "(cronometrando desde las {}) ",
+        TextKey::Start => "Empezar",
+        TextKey::Postpone => "Posponer",
+        TextKey::PostponeTitle => "Mover esta tarea al final del plan de mañana",
+        TextKey::TaskCountsAs => "{} (cuenta como {})",
+        TextKey::PostponedFrom => "{} (pospuesta del {})",
+        TextKey::Priority => "Prioridad",
+        TextKey::PriorityHigh => "Alta",
+        TextKey::PriorityNormal => "Normal",
+        TextKey::PriorityLow => "Baja",
+        TextKey::PomodoroLabel => " Pomodoro: ",
+        TextKey::BreakLabel => "Descanso: ",
+        TextKey::SkipBreak => "Saltar descanso",
+        TextKey::OnBreak => "Primero toma el descanso o sáltalo",
+        TextKey::Pause => "Pausar",
+        TextKey::Resume => "Reanudar",
+        TextKey::StartPomodoro => "Empezar pomodoro",
+        TextKey::FinishTask => "¡HECHO!",
+        TextKey::DayNotYet => "Este día aún no ha llegado",
+        TextKey::ConfirmDelete => "Confirmar borrado",
+        TextKey::AddNote => "Añadir nota",
+        TextKey::Week => "Semana",
+        TextKey::Month => "Mes",
+        TextKey::Year => "Año",
+        TextKey::AllTime => "Desde siempre",
+        TextKey::ConsistencyOverTheLast => "Regularidad de la hora de dormir en los últimos ",
+        TextKey::ConsistencyDays => " días: ",
+        TextKey::ConsistencyStats => "±{} min en torno a las {} ({} noches)",
+        TextKey::NotEnoughData => "no hay suficientes datos",
+        TextKey::Badges => "Insignias",
+        TextKey::EarnedOn => " (obtenida el {})",
+        TextKey::NothingRecorded => "Todavía no se ha registrado nada.",
+        TextKey::ScoreOnDate => "{} el {}",
+        TextKey::DaysTracked => "Días registrados",
+        TextKey::WorkPeriodsCompleted => "Periodos de trabajo completados",
+        TextKey::AverageScoreRow => "Puntuación media",
+        TextKey::BestDay => "Mejor día",
+        TextKey::WorstDay => "Peor día",
+        TextKey::LongestStreak => "Racha más larga",
+        TextKey::AverageBedtimeRow => "Hora media de dormir",
+        TextKey::DaysScored => "Las puntuaciones y horas de dormir abarcan {} de {} días con hora de dormir registrada.",
+        TextKey::ArchivedNotIncluded => "No se incluyen los días archivados antes del {}.",
+        TextKey::MinAfterTheTarget => "{} min después del objetivo",
+        TextKey::MinBeforeTheTarget => "{} min antes del objetivo",
+        TextKey::OnTheTarget => "a la hora objetivo",
+        TextKey::MonthYear => "{} de {}",
+        TextKey::PreviousMonth => "Mes anterior",
+        TextKey::NextMonth => "Mes siguiente",
+        TextKey::ArchivedLower => "archivado",
+        TextKey::PreviousWeek => "Semana anterior",
+        TextKey::NextWeek => "Semana siguiente",
+        TextKey::CloseReview => "Cerrar revisión",
+        TextKey::ReviewThisWeek => "Revisar esta semana",
+        TextKey::CopyWeekReport => "Copiar el informe semanal",
+        TextKey::Copied => " ¡Copiado!",
+        TextKey::CopyFailed => "No se pudo copiar al portapapeles; copia el informe de abajo.",
+        TextKey::WeekNumber => "Semana {}",
+        TextKey::WeekNumbers => "Semanas {}–{}",
+        TextKey::WeekReview => "Revisión semanal",
+        TextKey::TotalScoreAndWork => "Puntuación total: {} · Trabajo: {}/{}",
+        TextKey::AverageScoreSuffix => " · Puntuación media: {}",
+        TextKey::ReviewNotes => "Notas de la revisión",
+        TextKey::ReviewNotesFor => "Notas de la revisión de la semana del {}",
+        TextKey::WeekColumn => "SEMANA",
+        TextKey::WorkOutOf => "Trabajo: {}/{}",
+        TextKey::AverageSleep => "Sueño medio: {}",
+        TextKey::AverageMood => "Ánimo medio: {}",
+        TextKey::ArchivedWeek => "Semana archivada: {} – {}",
+        TextKey::WorkDone => "Trabajo hecho: {}",
+        TextKey::NightsWithBedtime => "Noches con hora de dormir: {}",
+        TextKey::AverageBedtime => "Hora media de dormir: {}",
+        TextKey::EarliestLatest => "La más temprana: {}, la más tardía: {}",
+        TextKey::NightsNearTarget => "Noches a menos de una vida media del objetivo: {}",
+        TextKey::NoPriorWeek => "Comparada con la semana anterior: sin datos previos",
+        TextKey::ComparedWithWeekBefore => "Comparada con la semana anterior",
+        TextKey::ThisWeek => "Esta semana",
+        TextKey::LastWeek => "La semana pasada",
+        TextKey::Change => "Cambio",
+        TextKey::TotalScoreRow => "Puntuación total",
+        TextKey::WorkPeriods => "Periodos de trabajo",
+        TextKey::AverageBedtimeDeviation => "Desviación media de la hora de dormir (min)",
+        TextKey::SelectDateScore => "Seleccionar {}, puntuación {}",
+        TextKey::SelectDateNoData => "Seleccionar {}, sin datos",
+        TextKey::Streak => "Racha: {} (la más larga: {})",
+        TextKey::CurrentDay => "DÍA ACTUAL",
+        TextKey::Score => "Puntuación: {}",
+        TextKey::Archived => "Archivado",
+        TextKey::NoDataDay => "Sin datos",
+        TextKey::Details => "Detalles",
+        TextKey::HideDetails => "Ocultar detalles",
+        TextKey::DetailsOf => "Detalles del {}",
+        TextKey::TasksPlanned => "Tareas planeadas: {}",
+        TextKey::DayWasArchived => "Este día se archivó.",
+        TextKey::NothingToRecordYet => "Todavía no se puede registrar nada en este día.",
+        TextKey::NoDataForDay => "No hay datos de este día. ",
+        TextKey::CreateEntry => "Crear una entrada",
+        TextKey::DayGoals => "Metas: {} periodos de trabajo, hora de dormir {}, {} puntos para el trabajo",
+        TextKey::WeekendGoalsSuffix => " (metas de fin de semana)",
+        TextKey::CustomGoalsSuffix => " (metas personalizadas)",
+        TextKey::EditDayGoals => "Editar las metas de este día",
+        TextKey::WorkCompleted => "Trabajo completado: {}",
+        TextKey::Edited => " (editado)",
+        TextKey::TimeTracked => "Tiempo registrado: {} min",
+        TextKey::Bedtime => "Hora de dormir: ",
+        TextKey::WokeUp => "Me desperté: {}",
+        TextKey::Slept => "Dormí: {}",
+        TextKey::Mood => "Ánimo: {} {}",
+        TextKey::BreakdownOnTarget => " · a la hora objetivo",
+        TextKey::BreakdownPastTarget => " · {} min después del objetivo",
+        TextKey::BreakdownBeforeTarget => " · {} min antes del objetivo",
+        TextKey::Breakdown => "Trabajo {}/{} · Sueño {}/{}{}",
+        TextKey::GoalsFor => "Metas del {}",
+        TextKey::ResetDayGoals => "Volver a las metas predeterminadas",
+        TextKey::Goals => "Metas",
+        TextKey::GoalsHelp => "Estos valores se aplican a los días registrados a partir de ahora.",
+        TextKey::Balance => "Equilibrio trabajo/sueño: {} puntos para el trabajo, {} puntos para el sueño",
+        TextKey::TargetWorkPeriodsValue => "Periodos de trabajo objetivo: {}",
+        TextKey::CapWorkScore => "Sin puntos extra por trabajar más allá del objetivo",
+        TextKey::TargetReachedSound => "Reproducir un sonido al alcanzar la meta de trabajo",
+        TextKey::PenalizeBedtimes => "Penalizar las horas de dormir que sean: ",
+        TextKey::EarlyOrLate => "tempranas o tardías",
+        TextKey::LateOnly => "solo tardías",
+        TextKey::HalflifeValue => "Vida media de los puntos de sueño: {} minutos",
+        TextKey::DayBoundary => "Cambio de día",
+        TextKey::NewDaysStartAt => "Los días nuevos empiezan a las ",
+        TextKey::DayBoundaryHelp => "El trabajo terminado antes cuenta para el día anterior.",
+        TextKey::WeekendGoals => "Metas de fin de semana",
+        TextKey::UseWeekendGoals => "Usar metas distintas los sábados y domingos",
+        TextKey::WeekendGoalsHelp => "Los días de fin de semana registrados a partir de ahora empiezan con estas metas.",
+        TextKey::MinutesLatePoints => "{} min tarde: {} puntos",
+        TextKey::OnboardingWorkCount => "¿Cuántos periodos de trabajo quieres terminar cada día? ",
+        TextKey::OnboardingBedtime => "¿A qué hora quieres irte a dormir? ",
+        TextKey::AfterMidnight => "Es después de medianoche, al día siguiente",
+        TextKey::OnboardingBalance => "De los 100 puntos del día, {} son para el trabajo y {} para el sueño.",
+        TextKey::Welcome => "Te damos la bienvenida a slorz",
+        TextKey::OnboardingStep => "Paso {} de 3: elige tus metas diarias. Puedes cambiarlas luego en los ajustes.",
+        TextKey::Back => "Atrás",
+        TextKey::Next => "Siguiente",
+        TextKey::FinishOnboarding => "Listo",
+        TextKey::SkipOnboarding => "Omitir y usar los valores predeterminados",
+        TextKey::Weeks => "Semanas",
+        TextKey::HistoryWeeksAre => "Las semanas del historial son ",
+        TextKey::RollingWeeks => "los últimos 7 días",
+        TextKey::CalendarWeeksFrom => "semanas naturales desde el {}",
+        TextKey::NoNotifications => "Los recordatorios para dormir necesitan un navegador compatible con notificaciones.",
+        TextKey::NotificationsBlocked => "Las notificaciones están bloqueadas para este sitio. Permítelas en los ajustes del navegador para recibir recordatorios para dormir.",
+        TextKey::BedtimeReminder => "Recordatorio para dormir",
+        TextKey::RemindMe => "Recuérdame ",
+        TextKey::MinutesBeforeBedtime => " minutos antes de mi hora objetivo de dormir",
+        TextKey::BadgeEarned => "¡Insignia obtenida: {}!",
+        TextKey::BadgePerfectDay => "Primer día perfecto",
+        TextKey::BadgePerfectDayCriteria => "Consigue 100 puntos o más en un día",
+        TextKey::BadgeWeekStreak => "Racha de 7 días",
+        TextKey::BadgeWeekStreakCriteria => "Cumple ambas metas 7 días seguidos",
+        TextKey::BadgeFiftyWorkPeriods => "50 periodos de trabajo",
+        TextKey::BadgeFiftyWorkPeriodsCriteria => "Completa 50 periodos de trabajo en total",
+        TextKey::BadgeSteadyBedtimes => "Horas de dormir estables",
+        TextKey::BadgeSteadyBedtimesCriteria => "Acuéstate a menos de 10 minutos del objetivo 5 noches seguidas",
+        TextKey::TargetReachedTitle => "¡Objetivo de trabajo alcanzado!",
+        TextKey::UnsavedChangesPrompt => "Tienes cambios sin guardar.",
+        TextKey::AddedToPlanFor => "Añadido al plan del {}",
+        TextKey::OnlySomeAdded => "Solo se añadieron {}; es el máximo de una vez",
+        TextKey::BreakOver => "Se acabó el descanso",
+        TextKey::FinishNotYet => "No se pueden terminar tareas de un día que aún no ha llegado",
+        TextKey::WorkDoneOf => "{} de {} hechos",
+        TextKey::FinishedNext => "Terminada {}, {}. Siguiente: {}",
+        TextKey::FinishedNoneLeft => "Terminada {}, {}. No quedan tareas",
+        TextKey::AlreadyNightOf => "Ya es la noche del {}; un cambio de día más tardío en los ajustes mantiene las horas de dormir después de medianoche en el día anterior",
+        TextKey::BedtimeNotYet => "No se puede registrar la hora de dormir de un día que aún no ha llegado",
+        TextKey::WakeTimeNotYet => "No se puede registrar la hora de despertar de una noche que aún no ha llegado",
+        TextKey::SleepTooLongAfter => "Despertar a las {} no está dentro de las {} horas después de acostarse a las {}",
+        TextKey::InvalidWakeTime => "\"{}\" no es una hora de despertar válida (se esperaba una hora como {})",
+        TextKey::InvalidBedtime => "\"{}\" no es una hora de dormir válida (se esperaba una hora como {})",
+        TextKey::BedtimeRecorded => "Hora de dormir registrada a las {}. Puntuación del día: {}",
+        TextKey::DataDeleted => "Se borraron todos los datos",
+        TextKey::DataRestored => "Se restauraron tus datos",
+        TextKey::ArchiveSaveFailed => "No se pudieron guardar los días archivados: {}",
+        TextKey::NoWeeksArchived => "Ninguna semana era lo bastante antigua para archivarla",
+        TextKey::ArchivedOneWeek => "Se archivó {} semana",
+        TextKey::ArchivedWeeks => "Se archivaron {} semanas",
+        TextKey::SleepCsvImported => "Se importaron {} horas de dormir, se omitieron {} y no se pudieron leer {} filas",
+        TextKey::SyncedCopyNewer => "La copia sincronizada era más reciente, así que reemplazó los datos de aquí",
+        TextKey::SyncUnreachable => "No se pudo contactar con el servidor de sincronización",
+        TextKey::SyncServerAnswered => "El servidor de sincronización respondió {} {}",
+        TextKey::SyncedDataUnreadable => "No se pudieron leer los datos sincronizados: {}",
+        TextKey::SyncFailed => "No se pudo sincronizar: {}",
+        TextKey::TaskNoLongerPlanned => "Esa tarea ya no está planificada para este día",
+        TextKey::DeletedTask => "Se borró \"{}\"",
+        TextKey::DeletedTasks => "Se borraron {} tareas",
+        TextKey::InvalidDataFile => "Archivo de datos no válido: {}",
+        TextKey::FileUnreadable => "No se pudo leer el archivo seleccionado",
+        TextKey::BedtimeReminderTitle => "Hora de relajarse",
+        TextKey::BedtimeReminderBody => "Tu hora objetivo de dormir es dentro de {} minutos o menos.",
+        TextKey::QuantityTooLow => "La cantidad debe ser al menos 1",
+        TextKey::InvalidQuantity => "\"{}\" no es una cantidad válida",
+        TextKey::InvalidWeight => "\"{}\" no es un peso válido",
+        TextKey::ReportDate => "Fecha",
+        TextKey::ReportWork => "Trabajo",
+        TextKey::ReportBedtime => "Hora de dormir",
+        TextKey::ReportScore => "Puntuación",
+        TextKey::ReportWeek => "Semana",
+        TextKey::MissingTaskError => "Esa tarea no está planificada",
+        TextKey::NotYetError => "El {} aún no ha llegado",
+        TextKey::OutOfRangeError => "El valor está fuera de rango",
+        TextKey::SleepTooLongError => "La hora de despertar no está dentro de las {} horas después de la hora de dormir",
+        TextKey::EmptyNameError => "El nombre está vacío",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn arguments_fill_the_placeholders_in_order() {
+        assert_eq!(
+            Lang::English.trf(TextKey::DayReviewSummary, &[&3, &"6", &35]),
+            "Work done: 3 of 6 · Score so far: 35"
+        );
+        assert_eq!(
+            Lang::Spanish.trf(TextKey::WeekNumbers, &[&17, &18]),
+            "Semanas 17–18"
+        );
+    }
+
+    #[test]
+    fn counts_pick_the_singular_for_one() {
+        let count = |n: usize| Lang::English.count(n, TextKey::DayCountOne, TextKey::DayCountOther);
+        assert_eq!(count(1), "1 day");
+        assert_eq!(count(0), "0 days");
+        assert_eq!(
+            Lang::Spanish.count(2, TextKey::WeekCountOne, TextKey::WeekCountOther),
+            "2 semanas"
+        );
+    }
+
+    #[test]
+    fn dates_are_written_the_way_each_language_writes_them() {
+        let date = ymd(2024, 3, 4);
+        assert_eq!(Lang::English.long_date(date), "Monday, March 4");
+        assert_eq!(Lang::Spanish.long_date(date), "lunes, 4 de marzo");
+        assert_eq!(Lang::English.month_day_weekday(date), "March 4, Monday");
+        assert_eq!(Lang::Spanish.month_year(date), "marzo de 2024");
+        assert_eq!(Lang::Spanish.weekday_short(Weekday::Wed), "mié");
+    }
+
+    #[test]
+    fn every_language_has_every_text() {
+        for lang in Lang::ALL {
+            assert!(!lang.tr(TextKey::AddNewTask).is_empty());
+        }
+        assert_ne!(
+            Lang::English.tr(TextKey::AddNewTask),
+            Lang::Spanish.tr(TextKey::AddNewTask)
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// Writing a day's plan as an iCalendar (RFC 5545) file for calendar apps. Like `scoring`,
+// this module is kept free of `seed` and `web_sys` so that it can be unit tested natively.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::model::{Bedtime, Period};
+
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+// Content lines longer than this many bytes are folded.
+const MAX_LINE_BYTES: usize = 75;
+
+// A calendar with the `periods` planned for `date` laid out back to back from `start`,
+// `minutes` each, and the target bedtime repeating daily from that night. Times are
+// floating, so they stay at the same clock time wherever the calendar is opened.
+// `now` is when the file is made.
+pub fn plan_calendar<'a>(
+    date: NaiveDate,
+    periods: impl IntoIterator<Item = &'a Period>,
+    start: NaiveTime,
+    minutes: i64,
+    target_bedtime: &Bedtime,
+    now: NaiveDateTime,
+) -> String {
+    let stamp = now.format(DATE_TIME_FORMAT).to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//slorz//slorz//EN".to_owned(),
+    ];
+    let mut block_start = date.and_time(start);
+    for period in periods {
+        let block_end = block_start + Duration::minutes(minutes);
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:{}@slorz", period.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", block_start.format(DATE_TIME_FORMAT)));
+        lines.push(format!("DTEND:{}", block_end.format(DATE_TIME_FORMAT)));
+        lines.push(format!("SUMMARY:{}", escape_text(&period.name)));
+        if !period.note.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&period.note)));
+        }
+        lines.push("END:VEVENT".to_owned());
+        block_start = block_end;
+    }
+    let night = if target_bedtime.next_day {
+        date.succ_opt().unwrap_or(date)
+    } else {
+        date
+    };
+    lines.push("BEGIN:VEVENT".to_owned());
+    lines.push(format!("UID:bedtime-{}@slorz", date));
+    lines.push(format!("DTSTAMP:{}", stamp));
+    lines.push(format!(
+        "DTSTART:{}",
+        night.and_time(target_bedtime.time).format(DATE_TIME_FORMAT)
+    ));
+    lines.push("RRULE:FREQ=DAILY".to_owned());
+    lines.push("SUMMARY:Bedtime".to_owned());
+    lines.push("END:VEVENT".to_owned());
+    lines.push("END:VCALENDAR".to_owned());
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+// Escapes a TEXT value, which can't hold bare commas, semicolons or line breaks.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Splits `line` into lines of at most `MAX_LINE_BYTES` bytes, each after the first
+// starting with a space. Characters are never split.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_bytes = 0;
+    for c in line.chars() {
+        if line_bytes + c.len_utf8() > MAX_LINE_BYTES {
+            folded.push_str("\r\n ");
+            line_bytes = 1;
+        }
+        folded.push(c);
+        line_bytes += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn hm(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn now() -> NaiveDateTime {
+        ymd(2024, 3, 4).and_time(hm(8, 0))
+    }
+
+    #[test]
+    fn bedtimes_after_midnight_start_on_the_next_day() {
+        let bedtime = Bedtime {
+            time: hm(0, 30),
+            next_day: true,
+        };
+        let ics = plan_calendar(ymd(2024, 3, 31), &[], hm(9, 0), 25, &bedtime, now());
+        assert_eq!(
+            ics,
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             PRODID:-//slorz//slorz//EN\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:bedtime-2024-03-31@slorz\r\n\
+             DTSTAMP:20240304T080000\r\n\
+             DTSTART:20240401T003000\r\n\
+             RRULE:FREQ=DAILY\r\n\
+             SUMMARY:Bedtime\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n"
+        );
+    }
+
+    #[test]
+    fn periods_are_laid_out_back_to_back() {
+        let mut write = Period::new("write, edit; repeat".to_owned());
+        write.note = "chapter 1\nchapter 2".to_owned();
+        let read = Period::new("read".to_owned());
+        let bedtime = Bedtime {
+            time: hm(23, 0),
+            next_day: false,
+        };
+        let ics = plan_calendar(
+            ymd(2024, 3, 4),
+            &[write.clone(), read.clone()],
+            hm(23, 10),
+            30,
+            &bedtime,
+            now(),
+        );
+        let expected = format!(
+            "BEGIN:VEVENT\r\n\
+             UID:{}@slorz\r\n\
+             DTSTAMP:20240304T080000\r\n\
+             DTSTART:20240304T231000\r\n\
+             DTEND:20240304T234000\r\n\
+             SUMMARY:write\\, edit\\; repeat\r\n\
+             DESCRIPTION:chapter 1\\nchapter 2\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:{}@slorz\r\n\
+             DTSTAMP:20240304T080000\r\n\
+             DTSTART:20240304T234000\r\n\
+             DTEND:20240305T001000\r\n\
+             SUMMARY:read\r\n\
+             END:VEVENT\r\n",
+            write.id, read.id
+        );
+        assert!(ics.contains(&expected));
+        assert!(ics.contains("DTSTART:20240304T230000\r\n"));
+    }
+
+    #[test]
+    fn long_lines_are_folded_between_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold_line(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_BYTES));
+        assert_eq!(lines.concat().replacen(' ', "", 1), line);
+    }
+}
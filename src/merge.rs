@@ -0,0 +1,258 @@
+// Merging an exported copy of the data into the data on this device, so that importing
+// doesn't lose what was done here since the export. Like `scoring`, this module is kept
+// free of `seed` and `web_sys` so that it can be unit tested natively.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeSet;
+
+use uuid::Uuid;
+
+use crate::i18n::{Lang, TextKey};
+use crate::model::Data;
+use crate::scoring::WorkSleep;
+
+// Whose goals are kept by a merge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GoalsSource {
+    Local,
+    Imported,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergeSummary {
+    // Days only the imported copy had.
+    pub days_added: usize,
+    // Days both copies had with differences, and how many of those the imported one won.
+    pub days_merged: usize,
+    pub toward_import: usize,
+    pub tasks_added: usize,
+}
+
+impl MergeSummary {
+    pub fn describe(&self, lang: Lang) -> String {
+        lang.trf(
+            TextKey::MergeSummary,
+            &[
+                &self.days_added,
+                &self.days_merged,
+                &self.toward_import,
+                &self.tasks_added,
+            ],
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DayMerge {
+    Same,
+    TowardLocal,
+    TowardImport,
+}
+
+// Merges `imported` into `local`. Days both have go through `merge_day`, planned tasks
+// are added unless a task with the same id was planned or finished here already, and
+// everything else but the goals stays as it is here. Days archived here are left out.
+pub fn merge_data(local: &mut Data, mut imported: Data, goals: GoalsSource) -> MergeSummary {
+    imported.migrate(local.current_date);
+    let mut summary = MergeSummary::default();
+    for (date, day) in std::mem::take(imported.work_sleep_data.days_mut()) {
+        if local.is_archived(date) {
+            continue;
+        }
+        match local.work_sleep_data.days_mut().entry(date) {
+            Entry::Vacant(entry) => {
+                entry.insert(day);
+                summary.days_added += 1;
+            }
+            Entry::Occupied(mut entry) => match merge_day(entry.get_mut(), day) {
+                DayMerge::Same => {}
+                DayMerge::TowardLocal => summary.days_merged += 1,
+                DayMerge::TowardImport => {
+                    summary.days_merged += 1;
+                    summary.toward_import += 1;
+                }
+            },
+        }
+    }
+    let planned = local
+        .planned_work_periods
+        .values()
+        .flatten()
+        .map(|wp| wp.id);
+    let completed = local
+        .work_sleep_data
+        .days()
+        .values()
+        .flat_map(|ws| ws.completed.iter())
+        .map(|cp| cp.id);
+    let mut known: BTreeSet<Uuid> = planned.chain(completed).collect();
+    for (date, periods) in imported.planned_work_periods {
+        for period in periods {
+            if known.insert(period.id) {
+                local.plan_mut(date).push_back(period);
+                summary.tasks_added += 1;
+            }
+        }
+    }
+    if goals == GoalsSource::Imported {
+        local.default_work_sleep_goals = imported.default_work_sleep_goals;
+        local.weekend_goals = imported.weekend_goals;
+    }
+    summary
+}
+
+// Keeps whichever copy of a day has more work done, this device's on a tie, filling in
+// a bedtime, wake time or mood that only the other copy has.
+pub fn merge_day(local: &mut WorkSleep, imported: WorkSleep) -> DayMerge {
+    if *local == imported {
+        return DayMerge::Same;
+    }
+    let toward_import = imported.actual_work_count > local.actual_work_count;
+    let other = if toward_import {
+        std::mem::replace(local, imported)
+    } else {
+        imported
+    };
+    local.actual_bedtime = local.actual_bedtime.take().or(other.actual_bedtime);
+    local.wake_time = local.wake_time.take().or(other.wake_time);
+    local.mood = local.mood.or(other.mood);
+    if toward_import {
+        DayMerge::TowardImport
+    } else {
+        DayMerge::TowardLocal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{NaiveDate, NaiveTime};
+
+    use crate::model::Period;
+    use crate::scoring::Bedtime;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn bedtime(h: u32, m: u32) -> Bedtime {
+        Bedtime {
+            time: NaiveTime::from_hms_opt(h, m, 0).unwrap(),
+            next_day: false,
+        }
+    }
+
+    fn day(work: i64, bedtime: Option<Bedtime>) -> WorkSleep {
+        let mut ws = WorkSleep::new();
+        ws.actual_work_count = work;
+        ws.actual_bedtime = bedtime;
+        ws
+    }
+
+    #[test]
+    fn the_copy_with_more_work_wins() {
+        let mut local = day(2, Some(bedtime(23, 0)));
+        assert_eq!(
+            merge_day(&mut local, day(3, Some(bedtime(22, 30)))),
+            DayMerge::TowardImport
+        );
+        assert_eq!(local, day(3, Some(bedtime(22, 30))));
+
+        assert_eq!(
+            merge_day(&mut local, day(1, Some(bedtime(21, 0)))),
+            DayMerge::TowardLocal
+        );
+        assert_eq!(local, day(3, Some(bedtime(22, 30))));
+    }
+
+    #[test]
+    fn ties_keep_the_copy_here() {
+        let mut local = day(2, Some(bedtime(23, 0)));
+        assert_eq!(
+            merge_day(&mut local, day(2, Some(bedtime(22, 0)))),
+            DayMerge::TowardLocal
+        );
+        assert_eq!(local.actual_bedtime, Some(bedtime(23, 0)));
+        assert_eq!(
+            merge_day(&mut local, day(2, Some(bedtime(23, 0)))),
+            DayMerge::Same
+        );
+    }
+
+    #[test]
+    fn a_present_bedtime_beats_a_missing_one() {
+        let mut local = day(1, Some(bedtime(23, 0)));
+        let mut imported = day(4, None);
+        imported.mood = Some(4);
+        assert_eq!(merge_day(&mut local, imported), DayMerge::TowardImport);
+        assert_eq!(local.actual_work_count, 4);
+        assert_eq!(local.actual_bedtime, Some(bedtime(23, 0)));
+        assert_eq!(local.mood, Some(4));
+
+        let mut local = day(5, None);
+        merge_day(&mut local, day(0, Some(bedtime(22, 15))));
+        assert_eq!(local, day(5, Some(bedtime(22, 15))));
+    }
+
+    #[test]
+    fn merging_adds_missing_days_and_tasks() {
+        let today = ymd(2024, 3, 6);
+        let mut local = Data::new(today);
+        local
+            .work_sleep_data
+            .days_mut()
+            .insert(ymd(2024, 3, 4), day(2, None));
+        local
+            .work_sleep_data
+            .days_mut()
+            .insert(ymd(2024, 3, 5), day(1, None));
+        let shared = Period::new("write".to_owned());
+        local.plan_mut(today).push_back(shared.clone());
+        local.work_sleep_data.archived_before = Some(ymd(2024, 3, 1));
+
+        let mut imported = Data::new(today);
+        let data = imported.work_sleep_data.days_mut();
+        data.insert(ymd(2024, 2, 28), day(6, None));
+        data.insert(ymd(2024, 3, 3), day(1, None));
+        data.insert(ymd(2024, 3, 4), day(3, None));
+        data.insert(ymd(2024, 3, 5), day(1, None));
+        let read = Period::new("read".to_owned());
+        imported.plan_mut(today).push_back(shared);
+        imported.plan_mut(today).push_back(read.clone());
+        imported.default_work_sleep_goals.target_work_count = 9;
+
+        let summary = merge_data(&mut local, imported, GoalsSource::Local);
+        assert_eq!(
+            summary,
+            MergeSummary {
+                days_added: 1,
+                days_merged: 1,
+                toward_import: 1,
+                tasks_added: 1,
+            }
+        );
+        let days: Vec<_> = local.work_sleep_data.days().keys().copied().collect();
+        assert_eq!(days, [ymd(2024, 3, 3), ymd(2024, 3, 4), ymd(2024, 3, 5)]);
+        assert_eq!(
+            local.work_sleep_data.days()[&ymd(2024, 3, 4)].actual_work_count,
+            3
+        );
+        let plan: Vec<_> = local.plan(today).iter().map(|wp| wp.id).collect();
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[1], read.id);
+        assert_ne!(local.default_work_sleep_goals.target_work_count, 9);
+    }
+
+    #[test]
+    fn the_imported_goals_can_be_kept() {
+        let today = ymd(2024, 3, 6);
+        let mut local = Data::new(today);
+        let mut imported = Data::new(today);
+        imported.default_work_sleep_goals.target_work_count = 9;
+        imported.weekend_goals = Some(imported.default_work_sleep_goals.clone());
+        merge_data(&mut local, imported, GoalsSource::Imported);
+        assert_eq!(local.default_work_sleep_goals.target_work_count, 9);
+        assert!(local.weekend_goals.is_some());
+    }
+}
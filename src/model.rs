@@ -0,0 +1,2832 @@
+// The app's persisted data and the pure helpers that work on it. Like `scoring`, this
+// module is kept free of `seed` and `web_sys` so that it can be unit tested natively.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::ops::Range;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::achievements::Achievement;
+use crate::i18n::{Lang, TextKey};
+
+pub use crate::scoring::{
+    format_work_count, sleep_points, Bedtime, BedtimePenaltyMode, CompletedPeriod,
+    ConsistencyStats, RangeSummary, ScoreBreakdown, WeekArchive, WeekSummary, WorkProgress,
+    WorkSleep, WorkSleepGoals, MAX_SLEEP_MINUTES, MIN_CONSISTENCY_NIGHTS, MOOD_EMOJIS,
+};
+
+pub const MAX_TASK_QUANTITY: i64 = 100;
+pub const MIN_TASK_WEIGHT: f64 = 0.1;
+pub const MAX_TASK_WEIGHT: f64 = 5.0;
+const TAB_TITLE_TASK_CHARS: usize = 40;
+pub const MAX_TARGET_WORK_COUNT: i64 = 20;
+pub const MIN_BEDTIME_PTS_HALFLIFE: i64 = 5;
+pub const MAX_BEDTIME_PTS_HALFLIFE: i64 = 180;
+const DEFAULT_POMODORO_MINUTES: i64 = 25;
+pub const MAX_POMODORO_MINUTES: i64 = 120;
+const DEFAULT_BREAK_MINUTES: i64 = 5;
+pub const MAX_BREAK_MINUTES: i64 = 60;
+pub const MAX_CALENDAR_BLOCK_MINUTES: i64 = 8 * 60;
+const DEFAULT_BEDTIME_REMINDER_LEAD_MINUTES: i64 = 30;
+pub const MAX_BEDTIME_REMINDER_LEAD_MINUTES: i64 = 180;
+pub const MAX_DAY_CUTOFF_HOUR: i64 = 6;
+const DEFAULT_CONSISTENCY_DAYS: u32 = 14;
+pub const MAX_CONSISTENCY_DAYS: i64 = 365;
+pub const QUICK_ADD_CHIPS: usize = 5;
+// Days after which a task name's past uses count half as much towards its quick-add rank.
+const QUICK_ADD_HALFLIFE_DAYS: f64 = 14.0;
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+// Where the app gets the time from. Tests fix it so that they don't depend on when or in
+// which time zone they run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Clock {
+    // The browser's local time.
+    Real,
+    #[cfg(test)]
+    Fixed(NaiveDateTime),
+}
+
+impl Clock {
+    pub fn now(self) -> NaiveDateTime {
+        match self {
+            Clock::Real => chrono::offset::Local::now().naive_local(),
+            #[cfg(test)]
+            Clock::Fixed(now) => now,
+        }
+    }
+
+    pub fn today(self, day_cutoff_hour: u32) -> NaiveDate {
+        effective_date(self.now(), day_cutoff_hour)
+    }
+}
+
+#[cfg(test)]
+impl Clock {
+    pub fn at_noon(date: NaiveDate) -> Self {
+        Clock::Fixed(date.and_hms_opt(12, 0, 0).expect("noon is a valid time"))
+    }
+}
+
+// The date that `now` counts towards, when days start at `day_cutoff_hour` rather
+// than at midnight.
+fn effective_date(now: NaiveDateTime, day_cutoff_hour: u32) -> NaiveDate {
+    now.checked_sub_signed(Duration::hours(day_cutoff_hour.into()))
+        .unwrap_or(now)
+        .date()
+}
+
+// The date that `now` counts towards, and a bedtime at `now` for that date's night.
+// Until `day_cutoff_hour` it's still the day before, so a bedtime after midnight is on
+// the next day.
+pub fn bedtime_now(now: NaiveDateTime, day_cutoff_hour: u32) -> Option<(NaiveDate, Bedtime)> {
+    let date = effective_date(now, day_cutoff_hour);
+    Some((date, Bedtime::at(date, now)?))
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Data {
+    // Bumped on every change, so that the newest copy wins when tabs sync.
+    #[serde(default)]
+    pub revision: u64,
+    #[serde(default)]
+    pub group_repeated_tasks: bool,
+    pub current_date: NaiveDate,
+    #[serde(default, rename = "planned_work_periods_by_date")]
+    pub planned_work_periods: BTreeMap<NaiveDate, VecDeque<Period>>,
+    // Older versions kept one queue for every date; `open_on` moves it to today.
+    #[serde(default, rename = "planned_work_periods", skip_serializing)]
+    legacy_planned_work_periods: VecDeque<Period>,
+    pub default_work_sleep_goals: WorkSleepGoals,
+    // Given to Saturdays and Sundays as their own goals when they're created, if set.
+    #[serde(default)]
+    pub weekend_goals: Option<WorkSleepGoals>,
+    pub work_sleep_data: WorkSleepData,
+    #[serde(default = "default_pomodoro_minutes")]
+    pub pomodoro_minutes: i64,
+    // Locks the next task for a break after each one is finished.
+    #[serde(default)]
+    pub breaks_enabled: bool,
+    #[serde(default = "default_break_minutes")]
+    pub break_minutes: i64,
+    #[serde(default)]
+    pub recurring_tasks: Vec<RecurringTask>,
+    // Dates from today onwards whose recurring tasks were already added.
+    #[serde(default)]
+    recurring_tasks_added: BTreeSet<NaiveDate>,
+    #[serde(default)]
+    pub bedtime_reminder_enabled: bool,
+    #[serde(default = "default_bedtime_reminder_lead_minutes")]
+    pub bedtime_reminder_lead_minutes: i64,
+    // Skips the confirmation step when deleting tasks.
+    #[serde(default)]
+    pub one_click_delete: bool,
+    // Hour at which a new day starts, so that work past midnight counts towards the day before.
+    #[serde(default)]
+    pub day_cutoff_hour: u32,
+    // The date whose bedtime was last reminded about, to remind at most once per date.
+    #[serde(default)]
+    pub last_bedtime_reminder: Option<NaiveDate>,
+    #[serde(default)]
+    pub week_mode: WeekMode,
+    // How often each task name was added or finished, for ranking the quick-add chips.
+    #[serde(default)]
+    pub task_frequency: BTreeMap<String, u64>,
+    #[serde(default)]
+    task_last_used: BTreeMap<String, NaiveDate>,
+    // Task names always shown as quick-add chips, whatever their rank.
+    #[serde(default)]
+    pub pinned_quick_tasks: BTreeSet<String>,
+    // Whether the first-run setup was completed or skipped. Data saved before the setup
+    // existed belongs to someone who already picked their goals.
+    #[serde(default = "default_onboarded")]
+    pub onboarded: bool,
+    // Plays a chime when the day's work target is reached.
+    #[serde(default = "default_target_reached_sound")]
+    pub target_reached_sound: bool,
+    // How many days back the bedtime consistency looks.
+    #[serde(default = "default_consistency_days")]
+    pub consistency_days: u32,
+    #[serde(default)]
+    pub theme: Theme,
+    // Review notes by the start date of the week they were written for, as the week was
+    // aligned then.
+    #[serde(default)]
+    pub weekly_notes: BTreeMap<NaiveDate, String>,
+    // Each badge at most once, in the order they were earned.
+    #[serde(default)]
+    pub achievements: Vec<Achievement>,
+    // Compacted weeks, oldest first. Their days were dropped from `work_sleep_data`.
+    #[serde(default)]
+    pub week_archives: Vec<WeekArchive>,
+    #[serde(default)]
+    pub date_format: DateFormat,
+    #[serde(default)]
+    pub clock_format: ClockFormat,
+    #[serde(default)]
+    pub lang: Lang,
+}
+
+fn default_pomodoro_minutes() -> i64 {
+    DEFAULT_POMODORO_MINUTES
+}
+
+fn default_break_minutes() -> i64 {
+    DEFAULT_BREAK_MINUTES
+}
+
+fn default_bedtime_reminder_lead_minutes() -> i64 {
+    DEFAULT_BEDTIME_REMINDER_LEAD_MINUTES
+}
+
+fn default_onboarded() -> bool {
+    true
+}
+
+fn default_target_reached_sound() -> bool {
+    true
+}
+
+fn default_consistency_days() -> u32 {
+    DEFAULT_CONSISTENCY_DAYS
+}
+
+impl Data {
+    pub fn new(current_date: NaiveDate) -> Self {
+        Self {
+            revision: 0,
+            group_repeated_tasks: false,
+            current_date,
+            planned_work_periods: BTreeMap::new(),
+            legacy_planned_work_periods: VecDeque::new(),
+            default_work_sleep_goals: WorkSleepGoals::default(),
+            weekend_goals: None,
+            work_sleep_data: WorkSleepData::new(shift_days(current_date, -6)),
+            pomodoro_minutes: DEFAULT_POMODORO_MINUTES,
+            breaks_enabled: false,
+            break_minutes: DEFAULT_BREAK_MINUTES,
+            recurring_tasks: Vec::new(),
+            recurring_tasks_added: BTreeSet::new(),
+            bedtime_reminder_enabled: false,
+            bedtime_reminder_lead_minutes: DEFAULT_BEDTIME_REMINDER_LEAD_MINUTES,
+            one_click_delete: false,
+            day_cutoff_hour: 0,
+            last_bedtime_reminder: None,
+            week_mode: WeekMode::Rolling,
+            task_frequency: BTreeMap::new(),
+            task_last_used: BTreeMap::new(),
+            pinned_quick_tasks: BTreeSet::new(),
+            onboarded: false,
+            target_reached_sound: true,
+            consistency_days: DEFAULT_CONSISTENCY_DAYS,
+            theme: Theme::System,
+            weekly_notes: BTreeMap::new(),
+            achievements: Vec::new(),
+            week_archives: Vec::new(),
+            date_format: DateFormat::Iso,
+            clock_format: ClockFormat::TwelveHour,
+            lang: Lang::English,
+        }
+    }
+
+    // `date` the way the settings show dates, in the language chosen.
+    pub fn fmt_date(&self, date: NaiveDate) -> String {
+        self.date_format.format_in(date, self.lang)
+    }
+
+    // `time` the way the settings show times of day.
+    pub fn fmt_time(&self, time: NaiveTime) -> String {
+        self.clock_format.format(time)
+    }
+
+    pub fn fmt_bedtime(&self, bedtime: &Bedtime) -> String {
+        format_bedtime(bedtime, self.clock_format, self.lang)
+    }
+
+    // The data of `date`, created with the weekend goals on a weekend. Days that already
+    // exist keep their goals.
+    pub fn day_mut(&mut self, date: NaiveDate) -> &mut WorkSleep {
+        let preset = self
+            .weekend_goals
+            .as_ref()
+            .filter(|_| is_weekend(date) && !self.work_sleep_data.days().contains_key(&date))
+            .cloned();
+        let ws = self.work_sleep_data.get_mut_or_create(&date);
+        if let Some(goals) = preset {
+            ws.goals = Some(goals);
+            ws.weekend_preset = true;
+        }
+        ws
+    }
+
+    // Records bedtimes read from a sleep tracker on the nights that have come and weren't
+    // archived, keeping bedtimes already recorded unless `overwrite`. Returns how many were
+    // recorded and how many were skipped.
+    pub fn import_bedtimes(
+        &mut self,
+        bedtimes: Vec<(NaiveDate, Bedtime)>,
+        overwrite: bool,
+        today: NaiveDate,
+    ) -> (usize, usize) {
+        let mut imported = 0;
+        let mut skipped = 0;
+        for (night, bedtime) in bedtimes {
+            if night > today || self.work_sleep_data.is_archived(night) {
+                skipped += 1;
+                continue;
+            }
+            let ws = self.day_mut(night);
+            if ws.actual_bedtime.is_some() && !overwrite {
+                skipped += 1;
+            } else {
+                ws.actual_bedtime = Some(bedtime);
+                imported += 1;
+            }
+        }
+        (imported, skipped)
+    }
+
+    // The date whose bedtime is due a reminder at `now`, if any: today or, for a target
+    // bedtime after midnight, the day before, with no bedtime recorded yet and no
+    // reminder sent for it.
+    pub fn bedtime_reminder_date(&self, now: NaiveDateTime, today: NaiveDate) -> Option<NaiveDate> {
+        [Some(today), today.pred_opt()]
+            .iter()
+            .flatten()
+            .copied()
+            .find(|date| {
+                let ws = self.work_sleep_data.get(date);
+                self.last_bedtime_reminder != Some(*date)
+                    && ws.and_then(|ws| ws.actual_bedtime.as_ref()).is_none()
+                    && bedtime_reminder_due(
+                        now,
+                        *date,
+                        &self.goals_on(*date).target_bedtime,
+                        self.bedtime_reminder_lead_minutes,
+                    )
+            })
+    }
+
+    // The goals `date` is held to, including those it would get from being created.
+    pub fn goals_on(&self, date: NaiveDate) -> &WorkSleepGoals {
+        let defaults = &self.default_work_sleep_goals;
+        match self.work_sleep_data.get(&date) {
+            Some(ws) => ws.effective_goals(defaults),
+            None if is_weekend(date) => self.weekend_goals.as_ref().unwrap_or(defaults),
+            None => defaults,
+        }
+    }
+
+    // Points freshly loaded data at `today`, migrating anything stored in an older shape.
+    pub fn open_on(&mut self, today: NaiveDate) {
+        let last_date = self.current_date;
+        self.current_date = today;
+        self.migrate(today);
+        self.carry_over_unfinished_tasks(last_date, today);
+        self.work_sleep_data.set_week_start(&today, self.week_mode);
+        self.add_recurring_tasks(today);
+    }
+
+    // Moves what's stored in an older shape to the current one, with a legacy task queue
+    // going to `today`.
+    pub fn migrate(&mut self, today: NaiveDate) {
+        if !self.legacy_planned_work_periods.is_empty() {
+            let legacy = std::mem::take(&mut self.legacy_planned_work_periods);
+            self.plan_mut(today).extend(legacy);
+        }
+        for ws in self.work_sleep_data.days_mut().values_mut() {
+            ws.migrate_legacy_goals(&self.default_work_sleep_goals);
+        }
+    }
+
+    // Every category used by a planned or completed task, for suggesting in the new-task form.
+    pub fn used_categories(&self) -> BTreeSet<&str> {
+        let planned = self
+            .planned_work_periods
+            .values()
+            .flatten()
+            .map(|wp| &wp.category);
+        let completed = self
+            .work_sleep_data
+            .days()
+            .values()
+            .flat_map(|ws| ws.completed.iter())
+            .map(|cp| &cp.category);
+        planned
+            .chain(completed)
+            .filter_map(|category| category.as_deref())
+            .collect()
+    }
+
+    pub fn plan(&self, date: NaiveDate) -> &VecDeque<Period> {
+        const EMPTY: &VecDeque<Period> = &VecDeque::new();
+        self.planned_work_periods.get(&date).unwrap_or(EMPTY)
+    }
+
+    pub fn plan_mut(&mut self, date: NaiveDate) -> &mut VecDeque<Period> {
+        self.planned_work_periods.entry(date).or_default()
+    }
+
+    // The plans of the dates after `date` that have tasks, soonest first.
+    pub fn upcoming_plans(
+        &self,
+        date: NaiveDate,
+    ) -> impl Iterator<Item = (NaiveDate, &VecDeque<Period>)> {
+        date.succ_opt()
+            .map(|next| self.planned_work_periods.range(next..))
+            .into_iter()
+            .flatten()
+            .filter(|(_, plan)| !plan.is_empty())
+            .map(|(date, plan)| (*date, plan))
+    }
+
+    // Whether any date's plan has the period with `id`.
+    pub fn has_planned_task(&self, id: Uuid) -> bool {
+        self.planned_task(id).is_some()
+    }
+
+    // The period with `id`, from whichever date's plan has it.
+    pub fn planned_task(&self, id: Uuid) -> Option<&Period> {
+        self.planned_work_periods
+            .values()
+            .flatten()
+            .find(|wp| wp.id == id)
+    }
+
+    pub fn planned_task_mut(&mut self, id: Uuid) -> Option<&mut Period> {
+        self.planned_work_periods
+            .values_mut()
+            .flatten()
+            .find(|wp| wp.id == id)
+    }
+
+    pub fn current_plan(&self) -> &VecDeque<Period> {
+        self.plan(self.current_date)
+    }
+
+    pub fn current_plan_mut(&mut self) -> &mut VecDeque<Period> {
+        self.plan_mut(self.current_date)
+    }
+
+    pub fn record_task_use(&mut self, name: &str, today: NaiveDate) {
+        *self.task_frequency.entry(name.to_owned()).or_default() += 1;
+        self.task_last_used.insert(name.to_owned(), today);
+    }
+
+    // Notes that are only whitespace are dropped rather than saved.
+    pub fn set_weekly_note(&mut self, week_start: NaiveDate, note: String) {
+        if note.trim().is_empty() {
+            self.weekly_notes.remove(&week_start);
+        } else {
+            self.weekly_notes.insert(week_start, note);
+        }
+    }
+
+    // The notes of the weeks that started from `start` through `end`. Weeks aligned
+    // differently since can leave more than one in a week, or start one midweek.
+    pub fn weekly_notes_in(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = (NaiveDate, &str)> {
+        self.weekly_notes
+            .range(start..=end)
+            .map(|(date, note)| (*date, note.as_str()))
+    }
+
+    // The weeks that ended more than `after_days` days before `today` and still have days
+    // on record, oldest first, summarized as they would be archived. Weeks line up with
+    // the current week; any overlapping a week archived before are left alone.
+    pub fn weeks_to_archive(&self, today: NaiveDate, after_days: u32) -> Vec<WeekArchive> {
+        let first = match self.work_sleep_data.days().keys().next() {
+            Some(first) => *first,
+            None => return Vec::new(),
+        };
+        let last_end = shift_days(today, -i64::from(after_days) - 1);
+        let defaults = &self.default_work_sleep_goals;
+        let mut archives = Vec::new();
+        let mut start = match self.week_mode {
+            WeekMode::Rolling => shift_days(today, -6),
+            WeekMode::Calendar(_) => self.week_mode.align(today),
+        };
+        while let Some(previous) = start.checked_sub_signed(Duration::days(7)) {
+            start = previous;
+            let end = shift_days(start, 6);
+            if end < first {
+                break;
+            }
+            if end > last_end || self.archives_in(start, end).next().is_some() {
+                continue;
+            }
+            let summary = self.work_sleep_data.summarize_week(start, defaults);
+            if summary.days_with_data > 0 {
+                archives.push(WeekArchive::new(start, &summary));
+            }
+        }
+        archives.reverse();
+        archives
+    }
+
+    // Archives the weeks from `weeks_to_archive`, dropping their days. Returns how many
+    // weeks were archived.
+    pub fn archive_weeks(&mut self, today: NaiveDate, after_days: u32) -> usize {
+        let archives = self.weeks_to_archive(today, after_days);
+        self.work_sleep_data
+            .days_mut()
+            .retain(|date, _| !archives.iter().any(|archive| archive.contains(*date)));
+        let archived = archives.len();
+        self.week_archives.extend(archives);
+        self.week_archives.sort_by_key(|archive| archive.start);
+        archived
+    }
+
+    // Whether the day was archived, on its own or as part of a week.
+    pub fn is_archived(&self, date: NaiveDate) -> bool {
+        self.work_sleep_data.is_archived(date)
+            || self
+                .week_archives
+                .iter()
+                .any(|archive| archive.contains(date))
+    }
+
+    // The archived weeks overlapping the days from `start` to `end`.
+    pub fn archives_in(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = &WeekArchive> {
+        self.week_archives
+            .iter()
+            .filter(move |archive| archive.start <= end && start <= archive.end())
+    }
+
+    // Drops a task name from the quick-add chips until it is used again.
+    pub fn forget_quick_task(&mut self, name: &str) {
+        self.task_frequency.remove(name);
+        self.task_last_used.remove(name);
+        self.pinned_quick_tasks.remove(name);
+    }
+
+    // The task names to offer as quick-add chips: pinned names first, then the most
+    // used ones, with uses counting for less the longer ago the name was last used.
+    pub fn quick_add_tasks(&self, today: NaiveDate) -> Vec<&str> {
+        let rank = |name: &str| {
+            let count = self.task_frequency.get(name).copied().unwrap_or(0) as f64;
+            let days_ago = self
+                .task_last_used
+                .get(name)
+                .map_or(0, |last_used| (today - *last_used).num_days().max(0));
+            count * 0.5_f64.powf(days_ago as f64 / QUICK_ADD_HALFLIFE_DAYS)
+        };
+        let mut ranked: Vec<&str> = self
+            .task_frequency
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !self.pinned_quick_tasks.contains(*name))
+            .collect();
+        ranked.sort_by(|a, b| rank(b).partial_cmp(&rank(a)).unwrap_or(Ordering::Equal));
+        let mut chips: Vec<&str> = self.pinned_quick_tasks.iter().map(String::as_str).collect();
+        let free = QUICK_ADD_CHIPS.saturating_sub(chips.len());
+        chips.extend(ranked.into_iter().take(free));
+        chips
+    }
+
+    // Moves task `id` from the current date's plan to the end of the next day's,
+    // returning where it was in the current plan.
+    pub fn postpone_task(&mut self, id: Uuid, now: NaiveTime) -> Option<usize> {
+        let date = self.current_date;
+        let tomorrow = date.succ_opt()?;
+        let plan = self.planned_work_periods.get_mut(&date)?;
+        let index = find_task(plan, id)?;
+        let mut period = plan.remove(index)?;
+        period.stop_timer(now);
+        period.postponed_from = Some(date);
+        self.plan_mut(tomorrow).push_back(period);
+        Some(index)
+    }
+
+    // Moves the tasks still planned on `from`, if it's before `today`, to the front of
+    // today's plan, so that unfinished work follows the date forward.
+    pub fn carry_over_unfinished_tasks(&mut self, from: NaiveDate, today: NaiveDate) {
+        if from >= today {
+            return;
+        }
+        if let Some(mut unfinished) = self.planned_work_periods.remove(&from) {
+            if unfinished.is_empty() {
+                return;
+            }
+            let plan = self.plan_mut(today);
+            unfinished.append(plan);
+            *plan = unfinished;
+        }
+    }
+
+    // How many tasks were postponed from the current date to the next day.
+    pub fn postponed_count(&self) -> usize {
+        let date = self.current_date;
+        date.succ_opt().map_or(0, |tomorrow| {
+            self.plan(tomorrow)
+                .iter()
+                .filter(|wp| wp.postponed_from == Some(date))
+                .count()
+        })
+    }
+
+    // Appends the recurring tasks scheduled for `current_date` to the plan, at most
+    // once per date. Past dates are left alone, so revisiting them adds nothing.
+    pub fn add_recurring_tasks(&mut self, today: NaiveDate) {
+        let date = self.current_date;
+        self.recurring_tasks_added.retain(|added| *added >= today);
+        if date < today || !self.recurring_tasks_added.insert(date) {
+            return;
+        }
+        for task in self.recurring_tasks.iter().filter(|task| task.is_due(date)) {
+            for _ in 0..task.quantity {
+                self.planned_work_periods
+                    .entry(date)
+                    .or_default()
+                    .push_back(Period::new(task.name.clone()));
+            }
+        }
+    }
+}
+
+// A template for tasks that are planned again every day, or only on some weekdays.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecurringTask {
+    pub id: Uuid,
+    pub name: String,
+    pub quantity: i64,
+    // Indexed from Monday; `None` means every day.
+    pub weekdays: Option<[bool; 7]>,
+}
+
+impl RecurringTask {
+    fn is_due(&self, date: NaiveDate) -> bool {
+        match self.weekdays {
+            Some(weekdays) => weekdays[date.weekday().num_days_from_monday() as usize],
+            None => true,
+        }
+    }
+}
+
+// The parts of `Data` that a change around `date` can touch: the plans and days from the
+// day before to the day after, and the default goals. Missing or empty plans and days
+// are kept as `None`.
+#[derive(Debug, PartialEq)]
+pub struct DataSnapshot {
+    date: NaiveDate,
+    plans: Vec<(NaiveDate, Option<VecDeque<Period>>)>,
+    days: Vec<(NaiveDate, Option<WorkSleep>)>,
+    default_goals: WorkSleepGoals,
+}
+
+impl DataSnapshot {
+    pub fn take(data: &Data, date: NaiveDate) -> Self {
+        let dates = [shift_days(date, -1), date, shift_days(date, 1)];
+        Self {
+            date,
+            plans: dates
+                .iter()
+                .map(|&d| {
+                    let plan = data.planned_work_periods.get(&d);
+                    (d, plan.filter(|plan| !plan.is_empty()).cloned())
+                })
+                .collect(),
+            days: dates
+                .iter()
+                .map(|&d| {
+                    let day = data.work_sleep_data.get(&d);
+                    (d, day.filter(|ws| **ws != WorkSleep::new()).cloned())
+                })
+                .collect(),
+            default_goals: data.default_work_sleep_goals.clone(),
+        }
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    // Puts these parts back into `data`, returning the ones they replaced. Celebrating
+    // the work target isn't taken back, so that redoing the last task doesn't repeat it.
+    pub fn restore(self, data: &mut Data) -> Self {
+        let replaced = Self::take(data, self.date);
+        for (date, plan) in self.plans {
+            match plan {
+                Some(plan) => data.planned_work_periods.insert(date, plan),
+                None => data.planned_work_periods.remove(&date),
+            };
+        }
+        for (date, day) in self.days {
+            if day.is_none() && !data.work_sleep_data.days().contains_key(&date) {
+                continue;
+            }
+            let ws = data.work_sleep_data.get_mut_or_create(&date);
+            let celebrated = ws.target_celebrated;
+            *ws = day.unwrap_or_default();
+            ws.target_celebrated |= celebrated;
+        }
+        data.default_work_sleep_goals = self.default_goals;
+        replaced
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Period {
+    pub id: Uuid,
+    pub name: String,
+    // Time already spent on this period in earlier, stopped timing sessions.
+    #[serde(default)]
+    pub tracked_minutes: i64,
+    // In-progress timings are dropped when the app restarts.
+    #[serde(skip)]
+    pub started_at: Option<NaiveTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+    // How much finishing this period counts towards the work target.
+    #[serde(default = "default_weight", skip_serializing_if = "is_default_weight")]
+    pub weight: f64,
+    // The date this period was planned for before being postponed to the day after.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postponed_from: Option<NaiveDate>,
+}
+
+impl Period {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            tracked_minutes: 0,
+            started_at: None,
+            category: None,
+            note: String::new(),
+            priority: Priority::Normal,
+            weight: 1.0,
+            postponed_from: None,
+        }
+    }
+
+    // Total tracked minutes as of `now`, or `None` if the period was never timed.
+    pub fn elapsed_minutes(&self, now: NaiveTime) -> Option<i64> {
+        match self.started_at {
+            Some(started_at) => Some(self.tracked_minutes + minutes_between(started_at, now)),
+            None if self.tracked_minutes > 0 => Some(self.tracked_minutes),
+            None => None,
+        }
+    }
+
+    pub fn stop_timer(&mut self, now: NaiveTime) {
+        if let Some(started_at) = self.started_at.take() {
+            self.tracked_minutes += minutes_between(started_at, now);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorkSleepData {
+    pub week_start: NaiveDate,
+    // Private so that every change goes through an accessor that marks cached scores stale.
+    data: BTreeMap<NaiveDate, WorkSleep>,
+    // Days before this date were archived and may have been dropped from `data`.
+    #[serde(default)]
+    pub archived_before: Option<NaiveDate>,
+    #[serde(default)]
+    pub archived_days: usize,
+    #[serde(skip)]
+    scores: ScoreCache,
+}
+
+// Day scores worked out since the days last changed, so that the views drawn from one
+// state score each day once.
+#[derive(Clone, Debug, Default)]
+struct ScoreCache {
+    // Bumped whenever a day or the goals may have changed, leaving older scores stale.
+    revision: u64,
+    // The revision and default goals the scores were worked out for.
+    scores: RefCell<BTreeMap<NaiveDate, (u64, WorkSleepGoals, i64)>>,
+}
+
+impl WorkSleepData {
+    pub fn new(week_start: NaiveDate) -> Self {
+        Self {
+            week_start,
+            data: BTreeMap::new(),
+            archived_before: None,
+            archived_days: 0,
+            scores: ScoreCache::default(),
+        }
+    }
+    // Marks every cached score stale. Every accessor handing out a day to change calls it.
+    fn invalidate_scores(&mut self) {
+        self.scores.revision += 1;
+    }
+    // The days with data, by date.
+    pub fn days(&self) -> &BTreeMap<NaiveDate, WorkSleep> {
+        &self.data
+    }
+    // For changing several days at once.
+    pub fn days_mut(&mut self) -> &mut BTreeMap<NaiveDate, WorkSleep> {
+        self.invalidate_scores();
+        &mut self.data
+    }
+    pub fn get(&self, date: &NaiveDate) -> Option<&WorkSleep> {
+        self.data.get(date)
+    }
+    pub fn get_mut(&mut self, date: &NaiveDate) -> Option<&mut WorkSleep> {
+        self.invalidate_scores();
+        self.data.get_mut(date)
+    }
+    // `ws.calc_score(defaults)`, which should be the day at `date`, remembered until the
+    // next change.
+    pub fn score(&self, date: NaiveDate, ws: &WorkSleep, defaults: &WorkSleepGoals) -> i64 {
+        let revision = self.scores.revision;
+        let mut scores = self.scores.scores.borrow_mut();
+        match scores.get(&date) {
+            Some((cached, goals, score)) if *cached == revision && goals == defaults => *score,
+            _ => {
+                let score = ws.calc_score(defaults);
+                scores.insert(date, (revision, defaults.clone(), score));
+                score
+            }
+        }
+    }
+    // The days with data from `start` to `end`, oldest first.
+    pub fn days_in(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = (NaiveDate, &WorkSleep)> {
+        (start <= end)
+            .then(|| self.data.range(start..=end))
+            .into_iter()
+            .flatten()
+            .map(|(date, ws)| (*date, ws))
+    }
+    // Every date from `start` to `end`, with its day if it has data.
+    pub fn dates_in(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = (NaiveDate, Option<&WorkSleep>)> {
+        let mut days = self.days_in(start, end).peekable();
+        std::iter::successors(Some(start), NaiveDate::succ_opt)
+            .take_while(move |date| *date <= end)
+            .map(move |date| {
+                (
+                    date,
+                    days.next_if(|(day, _)| *day == date).map(|(_, ws)| ws),
+                )
+            })
+    }
+    // Like `days_in`, along with each day's cached score.
+    pub fn scored_days_in<'a>(
+        &'a self,
+        start: NaiveDate,
+        end: NaiveDate,
+        defaults: &'a WorkSleepGoals,
+    ) -> impl Iterator<Item = (&'a WorkSleep, i64)> {
+        self.days_in(start, end)
+            .map(move |(date, ws)| (ws, self.score(date, ws, defaults)))
+    }
+    // Removes and returns the days before `cutoff`; `cutoff` itself is kept. A week
+    // showing archived days is moved to start at `cutoff`.
+    pub fn archive_before(&mut self, cutoff: NaiveDate) -> Vec<(NaiveDate, WorkSleep)> {
+        self.invalidate_scores();
+        let kept = self.data.split_off(&cutoff);
+        let archived: Vec<_> = std::mem::replace(&mut self.data, kept)
+            .into_iter()
+            .collect();
+        self.archived_days += archived.len();
+        self.archived_before = self.archived_before.max(Some(cutoff));
+        self.week_start = self.week_start.max(cutoff);
+        archived
+    }
+    pub fn is_archived(&self, date: NaiveDate) -> bool {
+        self.archived_before.is_some_and(|cutoff| date < cutoff)
+    }
+    pub fn get_mut_or_create(&mut self, date: &NaiveDate) -> &mut WorkSleep {
+        self.invalidate_scores();
+        self.data.entry(*date).or_default()
+    }
+    // Stops short of seven days only if the week runs past the last representable date.
+    pub fn get_current_week(&self) -> Vec<(NaiveDate, Option<&WorkSleep>)> {
+        self.dates_in(self.week_start, self.last_week_day())
+            .collect()
+    }
+    pub fn week_summary(&self, defaults: &WorkSleepGoals) -> WeekSummary {
+        self.summarize_week(self.week_start, defaults)
+    }
+    // The seven days from `start`. Shared by the week shown and the weeks archived, so an
+    // archived week keeps the numbers it was shown with.
+    pub fn summarize_week(&self, start: NaiveDate, defaults: &WorkSleepGoals) -> WeekSummary {
+        let end = start
+            .checked_add_signed(Duration::days(6))
+            .unwrap_or(chrono::naive::MAX_DATE);
+        WeekSummary::from_days(self.scored_days_in(start, end, defaults), defaults)
+    }
+    // Like `week_end`, but the last representable date for a week running past it.
+    fn last_week_day(&self) -> NaiveDate {
+        self.week_start
+            .checked_add_signed(Duration::days(6))
+            .unwrap_or(chrono::naive::MAX_DATE)
+    }
+    // The `days` days from `start`, whichever of them have data.
+    pub fn summarize_range(
+        &self,
+        start: NaiveDate,
+        days: u32,
+        defaults: &WorkSleepGoals,
+    ) -> RangeSummary {
+        let end = match days.checked_sub(1) {
+            Some(last) => shift_days(start, i64::from(last)),
+            None => return RangeSummary::default(),
+        };
+        RangeSummary::from_days(self.scored_days_in(start, end, defaults), defaults)
+    }
+    // Every day of the given month, padded with `None` so that the result splits into
+    // whole Monday-to-Sunday weeks.
+    // An invalid month is empty.
+    pub fn get_month(&self, year: i32, month: u32) -> Vec<Option<(NaiveDate, Option<&WorkSleep>)>> {
+        let first = match NaiveDate::from_ymd_opt(year, month, 1) {
+            Some(first) => first,
+            None => return Vec::new(),
+        };
+        let last = add_months(first, 1)
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(chrono::naive::MAX_DATE);
+        let mut days = vec![None; first.weekday().num_days_from_monday() as usize];
+        days.extend(self.dates_in(first, last).map(Some));
+        while days.len() % 7 != 0 {
+            days.push(None);
+        }
+        days
+    }
+    // How consistent the recorded bedtimes were over the `days` days ending at `end`.
+    pub fn bedtime_consistency(&self, end: NaiveDate, days: u32) -> Option<ConsistencyStats> {
+        let start = shift_days(end, 1 - i64::from(days.max(1)));
+        let bedtimes: Vec<Bedtime> = self
+            .data
+            .range(start..=end)
+            .filter_map(|(_, ws)| ws.actual_bedtime.clone())
+            .collect();
+        ConsistencyStats::of(&bedtimes)
+    }
+    // The streak of consecutive days that met their targets and ends at `up_to`, and the
+    // longest such streak on record. `up_to` only breaks the current streak once it has
+    // been recorded, so that an unfinished day doesn't reset it.
+    pub fn streaks(&self, up_to: NaiveDate, defaults: &WorkSleepGoals) -> Streaks {
+        let mut current = 0;
+        let mut expected = match self.data.get(&up_to) {
+            Some(ws) if ws.met_targets(defaults) => Some(up_to),
+            _ => up_to.pred_opt(),
+        };
+        if let Some(start) = expected {
+            for (date, ws) in self.data.range(..=start).rev() {
+                if Some(*date) != expected || !ws.met_targets(defaults) {
+                    break;
+                }
+                current += 1;
+                expected = date.pred_opt();
+            }
+        }
+
+        let longest = self.longest_run(|ws| ws.met_targets(defaults));
+        Streaks { current, longest }
+    }
+    // Each date whose day passes `qualifies`, oldest first, with how many consecutive
+    // dates up to it passed.
+    pub fn runs<'a>(
+        &'a self,
+        qualifies: impl Fn(&WorkSleep) -> bool + 'a,
+    ) -> impl Iterator<Item = (NaiveDate, i64)> + 'a {
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        self.data.iter().filter_map(move |(date, ws)| {
+            if !qualifies(ws) {
+                previous = None;
+                return None;
+            }
+            run = if previous.is_some() && previous == date.pred_opt() {
+                run + 1
+            } else {
+                1
+            };
+            previous = Some(*date);
+            Some((*date, run))
+        })
+    }
+    fn longest_run(&self, qualifies: impl Fn(&WorkSleep) -> bool) -> i64 {
+        self.runs(qualifies).map(|(_, run)| run).max().unwrap_or(0)
+    }
+    // Statistics over every day on record.
+    pub fn all_time_stats(&self, defaults: &WorkSleepGoals) -> AllTimeStats {
+        let mut stats = AllTimeStats {
+            longest_streak: self.longest_run(|ws| ws.met_targets(defaults)),
+            ..AllTimeStats::default()
+        };
+        let mut total_score = 0;
+        let mut total_deviation = 0;
+        for (date, ws) in &self.data {
+            stats.days_tracked += 1;
+            stats.work_completed += ws.weighted_work_count();
+            let deviation = match ws.bedtime_deviation(defaults) {
+                Some(deviation) => deviation,
+                None => continue,
+            };
+            let score = self.score(*date, ws, defaults);
+            stats.days_scored += 1;
+            total_score += score;
+            total_deviation += deviation;
+            if stats.best_day.filter(|&(_, best)| best >= score).is_none() {
+                stats.best_day = Some((*date, score));
+            }
+            if stats
+                .worst_day
+                .filter(|&(_, worst)| worst <= score)
+                .is_none()
+            {
+                stats.worst_day = Some((*date, score));
+            }
+        }
+        if stats.days_scored > 0 {
+            let days = stats.days_scored as f64;
+            stats.average_score = Some(total_score as f64 / days);
+            stats.average_bedtime_deviation = Some(total_deviation as f64 / days);
+        }
+        stats
+    }
+    pub fn week_end(&self) -> NaiveDate {
+        shift_days(self.week_start, 6)
+    }
+    // Shows the week containing `current_date`, which for rolling weeks always ends on it
+    // so that the selected day stays in the last column.
+    pub fn set_week_start(&mut self, current_date: &NaiveDate, mode: WeekMode) {
+        self.week_start = match mode {
+            WeekMode::Rolling => shift_days(*current_date, -6),
+            WeekMode::Calendar(_) => mode.align(*current_date),
+        };
+    }
+    // The mean length of the `recent` latest timed periods, or `None` if none were timed.
+    pub fn average_period_minutes(&self, recent: usize) -> Option<i64> {
+        let durations: Vec<i64> = self
+            .data
+            .values()
+            .rev()
+            .flat_map(|ws| ws.completed.iter().rev())
+            .filter_map(|cp| cp.duration_minutes)
+            .take(recent)
+            .collect();
+        if durations.is_empty() {
+            None
+        } else {
+            Some(durations.iter().sum::<i64>() / durations.len() as i64)
+        }
+    }
+    // Steps the shown week by `weeks`, keeping calendar weeks aligned to their first day.
+    pub fn shift_weeks(&mut self, weeks: i64, mode: WeekMode) {
+        self.week_start = mode.align(shift_days(self.week_start, 7 * weeks));
+    }
+}
+
+// Sorting by priority puts `High` first, as the variants are ordered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Low];
+
+    fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+
+    pub fn label(self) -> TextKey {
+        match self {
+            Priority::High => TextKey::PriorityHigh,
+            Priority::Normal => TextKey::PriorityNormal,
+            Priority::Low => TextKey::PriorityLow,
+        }
+    }
+}
+
+// Moves higher-priority periods ahead, keeping the order within each priority.
+pub fn sort_by_priority(periods: &mut VecDeque<Period>) {
+    periods.make_contiguous().sort_by_key(|wp| wp.priority);
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    Light,
+    Dark,
+    // Follows the browser's `prefers-color-scheme`.
+    #[default]
+    System,
+}
+
+impl Theme {
+    pub fn is_dark(self, prefers_dark: bool) -> bool {
+        match self {
+            Theme::Light => false,
+            Theme::Dark => true,
+            Theme::System => prefers_dark,
+        }
+    }
+
+    // The theme the header button switches to.
+    pub fn next(self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::System,
+            Theme::System => Theme::Light,
+        }
+    }
+
+    pub fn label(self) -> TextKey {
+        match self {
+            Theme::Light => TextKey::ThemeLight,
+            Theme::Dark => TextKey::ThemeDark,
+            Theme::System => TextKey::ThemeSystem,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum DateFormat {
+    // 2024-03-04
+    #[default]
+    Iso,
+    // Mar 4
+    MonthDay,
+    // 4 Mar
+    DayMonth,
+    // Mon Mar 4
+    WeekdayFirst,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 4] = [
+        DateFormat::Iso,
+        DateFormat::MonthDay,
+        DateFormat::DayMonth,
+        DateFormat::WeekdayFirst,
+    ];
+
+    // `date` with its month and weekday named in `lang`.
+    pub fn format_in(self, date: NaiveDate, lang: Lang) -> String {
+        let month = lang.month_short(date.month());
+        match self {
+            DateFormat::Iso => date.format("%Y-%m-%d").to_string(),
+            DateFormat::MonthDay => format!("{} {}", month, date.day()),
+            DateFormat::DayMonth => format!("{} {}", date.day(), month),
+            DateFormat::WeekdayFirst => format!(
+                "{} {} {}",
+                lang.weekday_short(date.weekday()),
+                month,
+                date.day()
+            ),
+        }
+    }
+
+    // The format as it writes an example date.
+    pub fn label(self, lang: Lang) -> String {
+        NaiveDate::from_ymd_opt(2024, 3, 4)
+            .map_or_else(String::new, |example| self.format_in(example, lang))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ClockFormat {
+    #[default]
+    TwelveHour,
+    TwentyFourHour,
+}
+
+impl ClockFormat {
+    pub const ALL: [ClockFormat; 2] = [ClockFormat::TwelveHour, ClockFormat::TwentyFourHour];
+
+    pub fn format(self, time: NaiveTime) -> String {
+        let format = match self {
+            ClockFormat::TwelveHour => "%I:%M %p",
+            ClockFormat::TwentyFourHour => "%H:%M",
+        };
+        time.format(format).to_string()
+    }
+
+    pub fn label(self) -> TextKey {
+        match self {
+            ClockFormat::TwelveHour => TextKey::Clock12,
+            ClockFormat::TwentyFourHour => TextKey::Clock24,
+        }
+    }
+
+    // A time to show as an example of what to type in.
+    pub fn example(self) -> &'static str {
+        match self {
+            ClockFormat::TwelveHour => "11:00 PM",
+            ClockFormat::TwentyFourHour => "23:00",
+        }
+    }
+}
+
+// Reads a time of day typed in either clock format, such as "23:00", "11:00 PM", "11pm"
+// or "11.30 p.m.".
+pub fn parse_time(s: &str) -> Option<NaiveTime> {
+    let s: String = s
+        .to_ascii_lowercase()
+        .replace("a.m.", "am")
+        .replace("p.m.", "pm")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let (digits, pm) = if let Some(digits) = s.strip_suffix("am") {
+        (digits, Some(false))
+    } else if let Some(digits) = s.strip_suffix("pm") {
+        (digits, Some(true))
+    } else {
+        (s.as_str(), None)
+    };
+    let number = |s: &str| {
+        Some(s)
+            .filter(|s| (1..=2).contains(&s.len()) && s.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|s| s.parse::<u32>().ok())
+    };
+    let (hour, minute) = match digits.split_once([':', '.']) {
+        Some((hour, minute)) if minute.len() == 2 => (number(hour)?, number(minute)?),
+        Some(_) => return None,
+        None => (number(digits)?, 0),
+    };
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+// How the history splits days into weeks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum WeekMode {
+    // The seven days up to and including the current date.
+    #[default]
+    Rolling,
+    // Weeks that start on the given weekday.
+    Calendar(Weekday),
+}
+
+impl WeekMode {
+    // The start of the calendar week containing `date`; rolling weeks can start anywhere.
+    pub fn align(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            WeekMode::Rolling => date,
+            WeekMode::Calendar(first_day) => {
+                let days_since_first = (date.weekday().num_days_from_monday() + 7
+                    - first_day.num_days_from_monday())
+                    % 7;
+                shift_days(date, -i64::from(days_since_first))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Streaks {
+    pub current: i64,
+    pub longest: i64,
+}
+
+// Statistics over the days still on record, so archived days that were dropped don't
+// count. Days with work but no bedtime yet, such as today, count towards the totals but
+// aren't scored.
+#[derive(Debug, Default, PartialEq)]
+pub struct AllTimeStats {
+    // Every recorded day, with or without a bedtime.
+    pub days_tracked: i64,
+    // Over every recorded day, with or without a bedtime, weighted.
+    pub work_completed: f64,
+    // The days with a bedtime, which the score and bedtime statistics are taken over. A
+    // day without one earns no sleep points and would otherwise always be the worst.
+    pub days_scored: i64,
+    pub average_score: Option<f64>,
+    // The earliest of the days with the highest or lowest score, and that score.
+    pub best_day: Option<(NaiveDate, i64)>,
+    pub worst_day: Option<(NaiveDate, i64)>,
+    // Like `Streaks::longest`; a day without a bedtime doesn't meet its targets.
+    pub longest_streak: i64,
+    // Minutes after (positive) or before (negative) the target.
+    pub average_bedtime_deviation: Option<f64>,
+}
+
+// Formats a number of seconds as `mm:ss`.
+pub fn format_countdown(secs: i64) -> String {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+// Whether `now` falls within `lead_minutes` before the target bedtime for `date`.
+pub fn bedtime_reminder_due(
+    now: NaiveDateTime,
+    date: NaiveDate,
+    target: &Bedtime,
+    lead_minutes: i64,
+) -> bool {
+    let target = if target.next_day {
+        date.succ_opt().map(|date| date.and_time(target.time))
+    } else {
+        Some(date.and_time(target.time))
+    };
+    target
+        .and_then(|target| {
+            let start = target.checked_sub_signed(Duration::minutes(lead_minutes))?;
+            Some(start <= now && now < target)
+        })
+        .unwrap_or(false)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BedtimeCountdown {
+    // Minutes left until the target bedtime, rounded up.
+    Left(i64),
+    // Whole minutes since the target bedtime went by.
+    Past(i64),
+}
+
+// How far `now` is from `target` on the night of `date`. A target after midnight is only
+// past once that time has come on the day after `date`.
+pub fn bedtime_countdown(
+    now: NaiveDateTime,
+    target: &Bedtime,
+    date: NaiveDate,
+) -> Option<BedtimeCountdown> {
+    let target = date.and_hms_opt(0, 0, 0)? + Duration::minutes(target.minutes_since_midnight());
+    let seconds = (target - now).num_seconds();
+    Some(if seconds > 0 {
+        BedtimeCountdown::Left((seconds + 59) / 60)
+    } else {
+        BedtimeCountdown::Past(-seconds / 60)
+    })
+}
+
+pub fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+// Steps `date` by `days`, leaving it unchanged if that would leave the supported range.
+pub fn shift_days(date: NaiveDate, days: i64) -> NaiveDate {
+    date.checked_add_signed(Duration::days(days))
+        .unwrap_or(date)
+}
+
+// The first day of the month `months` months after the one containing `date`, or `None`
+// past the supported range.
+pub fn add_months(date: NaiveDate, months: i32) -> Option<NaiveDate> {
+    let index = (date.year() * 12 + date.month0() as i32).checked_add(months)?;
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+}
+
+// When `remaining` periods of `period_minutes` each would be done if started at `now`.
+pub fn estimate_finish(remaining: usize, period_minutes: i64, now: NaiveDateTime) -> NaiveDateTime {
+    now + Duration::minutes(remaining as i64 * period_minutes)
+}
+
+// Minutes by which finishing at `finish` would run past `target` on the night of `date`,
+// or `None` if it wouldn't.
+pub fn bedtime_overshoot(date: NaiveDate, target: &Bedtime, finish: NaiveDateTime) -> Option<i64> {
+    let target = date.and_hms_opt(0, 0, 0)? + Duration::minutes(target.minutes_since_midnight());
+    let minutes = (finish - target).num_minutes();
+    Some(minutes).filter(|minutes| *minutes > 0)
+}
+
+// Minutes from `start` to `end`, assuming `end` is within a day after `start`.
+fn minutes_between(start: NaiveTime, end: NaiveTime) -> i64 {
+    let minutes = (end - start).num_minutes();
+    if minutes < 0 {
+        minutes + 24 * 60
+    } else {
+        minutes
+    }
+}
+
+// Parses a task quantity, which must be a positive integer; values above `max` are capped.
+// Errors are worded in `lang`.
+pub fn parse_quantity(s: &str, max: i64, lang: Lang) -> Result<i64, String> {
+    match s.trim().parse::<i64>() {
+        Ok(quantity) if quantity > 0 => Ok(quantity.min(max)),
+        Ok(_) => Err(lang.tr(TextKey::QuantityTooLow).to_owned()),
+        Err(_) => Err(lang.trf(TextKey::InvalidQuantity, &[&s])),
+    }
+}
+
+// Parses a task weight to one decimal; values outside MIN_TASK_WEIGHT..=MAX_TASK_WEIGHT
+// are clamped into it.
+pub fn parse_weight(s: &str, lang: Lang) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(weight) if weight.is_finite() => {
+            Ok(((weight * 10.0).round() / 10.0).clamp(MIN_TASK_WEIGHT, MAX_TASK_WEIGHT))
+        }
+        _ => Err(lang.trf(TextKey::InvalidWeight, &[&s])),
+    }
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+fn is_default_weight(weight: &f64) -> bool {
+    *weight == 1.0
+}
+
+// Reads one task per non-empty line, each optionally ending in a quantity such as
+// "flashcards x3" or "flashcards *3". Quantities are clamped to 1..=MAX_TASK_QUANTITY.
+pub fn parse_bulk_tasks(text: &str) -> Vec<(String, i64)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| split_quantity_suffix(line).unwrap_or_else(|| (line.to_owned(), 1)))
+        .map(|(name, quantity)| (normalize_task_name(&name), quantity))
+        .collect()
+}
+
+// Trims the name and collapses runs of whitespace inside it, so that names typed with
+// stray spaces count as the same task.
+pub fn normalize_task_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn split_quantity_suffix(line: &str) -> Option<(String, i64)> {
+    let digits_start = line.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let digits = &line[digits_start..];
+    let rest = &line[..digits_start];
+    let name = if let Some(name) = rest.strip_suffix('*') {
+        name
+    } else {
+        // Unlike `*`, an `x` needs a space before it so that names like "box2" stay whole.
+        rest.strip_suffix(|c| c == 'x' || c == 'X')
+            .filter(|name| name.ends_with(char::is_whitespace))?
+    };
+    let name = name.trim_end();
+    if digits.is_empty() || name.is_empty() {
+        return None;
+    }
+    // Anything too long to parse is absurdly large anyway.
+    let quantity = digits.parse::<i64>().unwrap_or(MAX_TASK_QUANTITY);
+    Some((name.to_owned(), quantity.clamp(1, MAX_TASK_QUANTITY)))
+}
+
+// Splits the queue into runs of consecutive periods with the same name.
+pub fn group_consecutive(periods: &VecDeque<Period>) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while start < periods.len() {
+        let end = group_containing(periods, start).end;
+        groups.push(start..end);
+        start = end;
+    }
+    groups
+}
+
+// Returns the run of same-named periods that contains `index`.
+pub fn group_containing(periods: &VecDeque<Period>, index: usize) -> Range<usize> {
+    let name = &periods[index].name;
+    let mut start = index;
+    while start > 0 && periods[start - 1].name == *name {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < periods.len() && periods[end].name == *name {
+        end += 1;
+    }
+    start..end
+}
+
+pub fn find_task(periods: &VecDeque<Period>, id: Uuid) -> Option<usize> {
+    periods.iter().position(|wp| wp.id == id)
+}
+
+// Removes the periods with the given ids, returning them along with the index the first one had.
+pub fn remove_tasks(periods: &mut VecDeque<Period>, ids: &[Uuid]) -> Option<(usize, Vec<Period>)> {
+    let start = periods.iter().position(|wp| ids.contains(&wp.id))?;
+    let (removed, kept): (VecDeque<Period>, _) =
+        periods.drain(..).partition(|wp| ids.contains(&wp.id));
+    *periods = kept;
+    Some((start, removed.into()))
+}
+
+pub fn insert_tasks(periods: &mut VecDeque<Period>, index: usize, tasks: Vec<Period>) {
+    for (offset, period) in tasks.into_iter().enumerate() {
+        periods.insert(index + offset, period);
+    }
+}
+
+// Parses an integer goal value, rejecting anything outside `min..=max`.
+pub fn parse_in_range(s: &str, min: i64, max: i64) -> Option<i64> {
+    s.trim()
+        .parse()
+        .ok()
+        .filter(|value| (min..=max).contains(value))
+}
+
+// A Markdown table of the week, for pasting into a journal.
+pub fn week_report(
+    week: &[(NaiveDate, Option<&WorkSleep>)],
+    summary: &WeekSummary,
+    defaults: &WorkSleepGoals,
+    date_format: DateFormat,
+    clock_format: ClockFormat,
+    lang: Lang,
+) -> String {
+    let mut report = format!(
+        "| {} | {} | {} | {} |\n| --- | --- | --- | --- |\n",
+        lang.tr(TextKey::ReportDate),
+        lang.tr(TextKey::ReportWork),
+        lang.tr(TextKey::ReportBedtime),
+        lang.tr(TextKey::ReportScore)
+    );
+    for (date, ws) in week {
+        let date = date_format.format_in(*date, lang);
+        match ws {
+            Some(ws) => report.push_str(&format!(
+                "| {} | {}/{} | {} | {} |\n",
+                date,
+                format_work_count(ws.weighted_work_count()),
+                ws.effective_goals(defaults).target_work_count,
+                ws.actual_bedtime
+                    .as_ref()
+                    .map_or("—".to_owned(), |bedtime| format_bedtime(
+                        bedtime,
+                        clock_format,
+                        lang
+                    )),
+                ws.calc_score(defaults)
+            )),
+            None => report.push_str(&format!("| {} | — | — | — |\n", date)),
+        }
+    }
+    report.push_str(&format!(
+        "| **{}** | {}/{} | {} | {} |\n",
+        lang.tr(TextKey::ReportWeek),
+        format_work_count(summary.work_completed),
+        summary.work_target,
+        summary
+            .average_bedtime
+            .as_ref()
+            .map_or("—".to_owned(), |bedtime| format_bedtime(
+                bedtime,
+                clock_format,
+                lang
+            )),
+        summary.total_score
+    ));
+    report
+}
+
+// The first line of `note`, cut to `max_chars` characters with an ellipsis if it was longer.
+pub fn note_preview(note: &str, max_chars: usize) -> String {
+    let first_line = note.lines().next().unwrap_or("");
+    if first_line.chars().count() <= max_chars && first_line.len() == note.trim_end().len() {
+        first_line.to_owned()
+    } else {
+        let cut: String = first_line.chars().take(max_chars).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+// The browser tab title while `task` is the next one to work on.
+pub fn tab_title(task: Option<&str>) -> String {
+    match task {
+        Some(name) => format!("▶ {} — slorz", note_preview(name, TAB_TITLE_TASK_CHARS)),
+        None => "slorz".to_owned(),
+    }
+}
+
+// Like "2h 14m", or "37m" under an hour.
+pub fn format_hours_minutes(minutes: i64) -> String {
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+pub fn format_sleep(minutes: i64) -> String {
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+pub fn format_bedtime(bedtime: &Bedtime, clock: ClockFormat, lang: Lang) -> String {
+    format!(
+        "{}{}",
+        clock.format(bedtime.time),
+        if bedtime.next_day {
+            lang.tr(TextKey::NextDay)
+        } else {
+            ""
+        }
+    )
+}
+
+const DEMO_TASKS: [(&str, Option<&str>); 5] = [
+    ("Thesis chapter", Some("thesis")),
+    ("Literature review", Some("thesis")),
+    ("Email", Some("admin")),
+    ("Exercise", None),
+    ("Reading", None),
+];
+
+impl Data {
+    // `days` days of made-up history up to `today`, plus a few tasks planned for today,
+    // for showing the app off. The same seed always gives the same data.
+    pub fn demo(seed: u64, days: usize, today: NaiveDate) -> Self {
+        let mut rng = DemoRng(seed);
+        let mut data = Data::new(today);
+        data.onboarded = true;
+        let target = data.default_work_sleep_goals.target_work_count;
+        let usual_bedtime = data.default_work_sleep_goals.target_bedtime.clone();
+        for days_ago in (1..=days as i64).rev() {
+            let date = shift_days(today, -days_ago);
+            // Some days were never opened at all.
+            if rng.below(10) == 0 {
+                continue;
+            }
+            let ws = data.work_sleep_data.get_mut_or_create(&date);
+            ws.actual_work_count = (target + rng.below(6) as i64 - 3).max(0);
+            // ...and some nights' bedtimes were forgotten.
+            if rng.below(7) != 0 {
+                ws.actual_bedtime = usual_bedtime.plus_minutes(rng.below(151) as i64 - 60);
+            }
+            let count = ws.actual_work_count;
+            for i in 0..count {
+                let (name, category) = DEMO_TASKS[rng.below(DEMO_TASKS.len() as u64) as usize];
+                ws.completed.push(CompletedPeriod {
+                    id: rng.uuid(),
+                    name: name.to_owned(),
+                    completed_at: usual_bedtime.time - Duration::minutes(45 * (count - i)),
+                    duration_minutes: Some(20 + rng.below(30) as i64),
+                    category: category.map(str::to_owned),
+                    note: String::new(),
+                });
+            }
+        }
+        for _ in 0..4 {
+            let (name, category) = DEMO_TASKS[rng.below(DEMO_TASKS.len() as u64) as usize];
+            let period = Period {
+                id: rng.uuid(),
+                category: category.map(str::to_owned),
+                ..Period::new(name.to_owned())
+            };
+            data.current_plan_mut().push_back(period);
+        }
+        data.work_sleep_data.set_week_start(&today, data.week_mode);
+        data
+    }
+}
+
+// A small SplitMix64 generator; demo data only needs to look random, not be random.
+struct DemoRng(u64);
+
+impl DemoRng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // A number in `0..n`, with a bias too small to matter here.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn uuid(&mut self) -> Uuid {
+        Uuid::from_u128(u128::from(self.next()) << 64 | u128::from(self.next()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn hm(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn quick_add_tasks_favour_recent_and_pinned_names() {
+        let today = ymd(2021, 5, 31);
+        let mut data = Data::new(today);
+        for _ in 0..4 {
+            data.record_task_use("old", ymd(2021, 4, 1));
+        }
+        data.record_task_use("recent", today);
+        data.record_task_use("recent", today);
+        for name in &["a", "b", "c", "d"] {
+            data.record_task_use(name, ymd(2021, 5, 30));
+        }
+        assert_eq!(data.quick_add_tasks(today)[0], "recent");
+        assert_eq!(data.quick_add_tasks(today).len(), QUICK_ADD_CHIPS);
+        assert!(!data.quick_add_tasks(today).contains(&"old"));
+
+        data.pinned_quick_tasks.insert("old".to_owned());
+        assert_eq!(data.quick_add_tasks(today)[0], "old");
+        assert_eq!(data.quick_add_tasks(today).len(), QUICK_ADD_CHIPS);
+
+        data.forget_quick_task("old");
+        data.forget_quick_task("recent");
+        assert_eq!(data.quick_add_tasks(today), vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn postponed_tasks_move_to_the_end_of_the_next_day() {
+        let today = ymd(2021, 5, 3);
+        let tomorrow = ymd(2021, 5, 4);
+        let mut data = Data::new(today);
+        data.plan_mut(tomorrow)
+            .push_back(Period::new("planned".to_owned()));
+        let mut timed = Period::new("postponed".to_owned());
+        timed.started_at = Some(hm(9, 0));
+        let id = timed.id;
+        data.current_plan_mut()
+            .push_back(Period::new("kept".to_owned()));
+        data.current_plan_mut().push_back(timed);
+
+        assert_eq!(data.postpone_task(id, hm(9, 20)), Some(1));
+        assert_eq!(data.postpone_task(id, hm(9, 20)), None);
+        assert_eq!(data.current_plan().len(), 1);
+        assert_eq!(data.postponed_count(), 1);
+        let moved = &data.plan(tomorrow)[1];
+        assert_eq!(moved.id, id);
+        assert_eq!(moved.postponed_from, Some(today));
+        assert_eq!(moved.started_at, None);
+        assert_eq!(moved.tracked_minutes, 20);
+    }
+
+    #[test]
+    fn demo_data_depends_only_on_the_seed() {
+        let today = ymd(2021, 5, 31);
+        let json = |seed| serde_json::to_string(&Data::demo(seed, 28, today)).unwrap();
+        assert_eq!(json(7), json(7));
+        assert_ne!(json(7), json(8));
+
+        let data = Data::demo(7, 28, today);
+        let days = data.work_sleep_data.days();
+        assert!(days.len() > 20 && days.len() <= 28);
+        assert!(days.keys().all(|date| *date < today));
+        assert!(days.values().any(|ws| ws.actual_bedtime.is_none()));
+        assert!(days
+            .values()
+            .all(|ws| ws.completed.len() as i64 == ws.actual_work_count));
+        assert_eq!(data.current_plan().len(), 4);
+    }
+
+    #[test]
+    fn recurring_tasks_are_added_once_per_date() {
+        let today = ymd(2021, 5, 3);
+        let mut data = Data::new(today);
+        data.recurring_tasks.push(RecurringTask {
+            id: Uuid::new_v4(),
+            name: "exercise".to_owned(),
+            quantity: 2,
+            weekdays: None,
+        });
+        data.add_recurring_tasks(today);
+        data.add_recurring_tasks(today);
+        assert_eq!(data.current_plan().len(), 2);
+
+        data.current_plan_mut().clear();
+        data.current_date = today - Duration::days(1);
+        data.add_recurring_tasks(today);
+        assert!(data.current_plan().is_empty());
+        data.current_date = today;
+        data.add_recurring_tasks(today);
+        assert!(data.current_plan().is_empty());
+    }
+
+    #[test]
+    fn open_on_moves_the_legacy_queue_to_today() {
+        let today = ymd(2021, 5, 3);
+        let mut data = Data::new(today - Duration::days(2));
+        data.legacy_planned_work_periods
+            .extend(vec![period("a"), period("b")]);
+        data.open_on(today);
+        assert_eq!(data.current_date, today);
+        assert!(data.legacy_planned_work_periods.is_empty());
+        assert_eq!(data.plan(today).len(), 2);
+        assert!(data.plan(today - Duration::days(1)).is_empty());
+    }
+
+    #[test]
+    fn open_on_carries_unfinished_tasks_over_to_today() {
+        let today = ymd(2021, 5, 3);
+        let last_date = today - Duration::days(2);
+        let mut data = Data::new(last_date);
+        data.plan_mut(last_date).push_back(period("a"));
+        data.plan_mut(today).push_back(period("b"));
+        data.open_on(today);
+        let names: Vec<_> = data.plan(today).iter().map(|wp| wp.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert!(!data.planned_work_periods.contains_key(&last_date));
+    }
+
+    #[test]
+    fn parse_bulk_tasks_reads_one_task_per_line() {
+        let tasks = parse_bulk_tasks("  flashcards x3 \n\n\t\nread\r\nexercise*2\n   \n");
+        assert_eq!(
+            tasks,
+            vec![
+                ("flashcards".to_owned(), 3),
+                ("read".to_owned(), 1),
+                ("exercise".to_owned(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_bulk_tasks_keeps_lines_without_a_quantity_whole() {
+        let tasks = parse_bulk_tasks("box2\nx3\nchapter 4\nredo x\nslides X2");
+        assert_eq!(
+            tasks,
+            vec![
+                ("box2".to_owned(), 1),
+                ("x3".to_owned(), 1),
+                ("chapter 4".to_owned(), 1),
+                ("redo x".to_owned(), 1),
+                ("slides".to_owned(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn task_names_are_trimmed_and_collapsed() {
+        assert_eq!(normalize_task_name("  read  paper "), "read paper");
+        assert_eq!(normalize_task_name("read\t paper"), "read paper");
+        assert_eq!(normalize_task_name(" \n "), "");
+        assert_eq!(parse_bulk_tasks("read   paper x2")[0].0, "read paper");
+    }
+
+    #[test]
+    fn parse_bulk_tasks_clamps_absurd_quantities() {
+        let tasks = parse_bulk_tasks("a x0\nb x1000\nc *99999999999999999999999");
+        assert_eq!(
+            tasks,
+            vec![
+                ("a".to_owned(), 1),
+                ("b".to_owned(), MAX_TASK_QUANTITY),
+                ("c".to_owned(), MAX_TASK_QUANTITY),
+            ]
+        );
+    }
+
+    #[test]
+    fn data_saved_before_onboarding_existed_skips_it() {
+        let mut json = serde_json::to_value(Data::new(ymd(2021, 5, 3))).unwrap();
+        json.as_object_mut().unwrap().remove("onboarded");
+        let data: Data = serde_json::from_value(json).unwrap();
+        assert!(data.onboarded);
+        assert!(!Data::new(ymd(2021, 5, 3)).onboarded);
+    }
+
+    #[test]
+    fn old_weeks_are_archived_as_their_summaries() {
+        let today = ymd(2021, 3, 17);
+        let mut data = Data::new(today);
+        data.week_mode = WeekMode::Calendar(Weekday::Mon);
+        for (day, work) in [
+            (ymd(2021, 2, 23), 1),
+            (ymd(2021, 3, 2), 3),
+            (ymd(2021, 3, 6), 4),
+        ] {
+            let ws = data.work_sleep_data.get_mut_or_create(&day);
+            ws.actual_work_count = work;
+            ws.actual_bedtime = Some(Bedtime {
+                time: NaiveTime::from_hms_opt(23, 0, 0).unwrap(),
+                next_day: false,
+            });
+        }
+        data.work_sleep_data.get_mut_or_create(&ymd(2021, 3, 10));
+        let defaults = data.default_work_sleep_goals.clone();
+        let live = data
+            .work_sleep_data
+            .summarize_week(ymd(2021, 3, 1), &defaults);
+
+        // The week of the 8th ended only three days ago.
+        let preview = data.weeks_to_archive(today, 7);
+        let starts: Vec<_> = preview.iter().map(|archive| archive.start).collect();
+        assert_eq!(starts, vec![ymd(2021, 2, 22), ymd(2021, 3, 1)]);
+        assert_eq!(preview[1], WeekArchive::new(ymd(2021, 3, 1), &live));
+        assert_eq!(preview[1].work_done, 7.0);
+        assert_eq!(preview[1].nights_with_bedtime, 2);
+
+        assert_eq!(data.archive_weeks(today, 7), 2);
+        assert_eq!(data.week_archives, preview);
+        assert_eq!(
+            data.work_sleep_data
+                .days()
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![ymd(2021, 3, 10)]
+        );
+        assert!(data.weeks_to_archive(today, 7).is_empty());
+        let shown: Vec<_> = data
+            .archives_in(ymd(2021, 3, 5), ymd(2021, 3, 11))
+            .collect();
+        assert_eq!(shown, vec![&preview[1]]);
+    }
+
+    #[test]
+    fn archive_before_keeps_the_cutoff_date() {
+        let mut data = WorkSleepData::new(ymd(2021, 1, 1));
+        for day in 1..=4 {
+            data.get_mut_or_create(&ymd(2021, 1, day));
+        }
+        let archived = data.archive_before(ymd(2021, 1, 3));
+        let dates: Vec<_> = archived.iter().map(|(date, _)| *date).collect();
+        assert_eq!(dates, vec![ymd(2021, 1, 1), ymd(2021, 1, 2)]);
+        assert_eq!(
+            data.data.keys().copied().collect::<Vec<_>>(),
+            vec![ymd(2021, 1, 3), ymd(2021, 1, 4)]
+        );
+        assert!(data.is_archived(ymd(2021, 1, 2)));
+        assert!(!data.is_archived(ymd(2021, 1, 3)));
+
+        // An earlier cutoff archives nothing more and keeps the later one.
+        assert!(data.archive_before(ymd(2021, 1, 2)).is_empty());
+        assert_eq!(data.archived_before, Some(ymd(2021, 1, 3)));
+        assert_eq!(data.archived_days, 2);
+    }
+
+    #[test]
+    fn archive_before_moves_the_week_out_of_the_archived_range() {
+        let mut data = WorkSleepData::new(ymd(2021, 1, 1));
+        data.archive_before(ymd(2021, 1, 5));
+        assert_eq!(data.week_start, ymd(2021, 1, 5));
+
+        data.week_start = ymd(2021, 2, 1);
+        data.archive_before(ymd(2021, 1, 20));
+        assert_eq!(data.week_start, ymd(2021, 2, 1));
+    }
+
+    #[test]
+    fn all_time_stats_score_only_days_with_a_bedtime() {
+        let defaults = WorkSleepGoals::default();
+        let mut data = WorkSleepData::new(ymd(2021, 3, 1));
+        let mut day = |day: u32, work: i64, bedtime: Option<(u32, u32)>| {
+            let ws = data.get_mut_or_create(&ymd(2021, 3, day));
+            ws.actual_work_count = work;
+            ws.actual_bedtime = bedtime.map(|(hour, minute)| Bedtime {
+                time: hm(hour, minute),
+                next_day: false,
+            });
+        };
+        day(1, 6, Some((23, 0)));
+        day(2, 6, Some((23, 30)));
+        day(3, 3, Some((22, 0)));
+        // Missing 4th, then a full day and one with work but no bedtime.
+        day(5, 6, Some((23, 0)));
+        day(6, 4, None);
+
+        let stats = data.all_time_stats(&defaults);
+        assert_eq!(stats.days_tracked, 5);
+        assert_eq!(stats.work_completed, 25.0);
+        assert_eq!(stats.days_scored, 4);
+        assert_eq!(stats.best_day, Some((ymd(2021, 3, 1), 100)));
+        assert_eq!(stats.worst_day, Some((ymd(2021, 3, 3), 43)));
+        assert_eq!(
+            stats.average_score,
+            Some((100 + 85 + 43 + 100) as f64 / 4.0)
+        );
+        assert_eq!(stats.longest_streak, 2);
+        assert_eq!(stats.average_bedtime_deviation, Some(-7.5));
+    }
+
+    #[test]
+    fn all_time_stats_of_days_without_bedtimes_have_no_scores() {
+        let mut data = WorkSleepData::new(ymd(2021, 3, 1));
+        assert_eq!(
+            data.all_time_stats(&WorkSleepGoals::default()),
+            AllTimeStats::default()
+        );
+        data.get_mut_or_create(&ymd(2021, 3, 1)).actual_work_count = 2;
+        let stats = data.all_time_stats(&WorkSleepGoals::default());
+        assert_eq!((stats.days_tracked, stats.work_completed), (1, 2.0));
+        assert_eq!(stats.days_scored, 0);
+        assert_eq!(stats.average_score, None);
+        assert_eq!(stats.best_day, None);
+        assert_eq!(stats.average_bedtime_deviation, None);
+    }
+
+    #[test]
+    fn upcoming_plans_skip_the_date_and_empty_plans() {
+        let mut data = Data::new(ymd(2021, 5, 5));
+        for (day, name) in [
+            (4, "past"),
+            (5, "today"),
+            (13, "renew passport"),
+            (8, "read"),
+        ] {
+            data.plan_mut(ymd(2021, 5, day))
+                .push_back(Period::new(name.to_owned()));
+        }
+        data.plan_mut(ymd(2021, 5, 7));
+        let upcoming: Vec<(u32, Vec<&str>)> = data
+            .upcoming_plans(ymd(2021, 5, 5))
+            .map(|(date, plan)| (date.day(), plan.iter().map(|wp| wp.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            upcoming,
+            vec![(8, vec!["read"]), (13, vec!["renew passport"])]
+        );
+    }
+
+    #[test]
+    fn dates_and_times_follow_the_display_settings() {
+        let mut data = Data::new(ymd(2024, 3, 4));
+        let formatted: Vec<String> = DateFormat::ALL
+            .iter()
+            .map(|format| {
+                data.date_format = *format;
+                data.fmt_date(ymd(2024, 3, 4))
+            })
+            .collect();
+        assert_eq!(formatted, ["2024-03-04", "Mar 4", "4 Mar", "Mon Mar 4"]);
+
+        let bedtime = Bedtime {
+            time: hm(0, 5),
+            next_day: true,
+        };
+        assert_eq!(data.fmt_time(hm(23, 0)), "11:00 PM");
+        assert_eq!(data.fmt_bedtime(&bedtime), "12:05 AM (next day)");
+        data.clock_format = ClockFormat::TwentyFourHour;
+        assert_eq!(data.fmt_time(hm(23, 0)), "23:00");
+        assert_eq!(data.fmt_bedtime(&bedtime), "00:05 (next day)");
+
+        data.lang = Lang::Spanish;
+        assert_eq!(data.fmt_date(ymd(2024, 3, 4)), "lun mar 4");
+        assert_eq!(data.fmt_bedtime(&bedtime), "00:05 (día siguiente)");
+    }
+
+    #[test]
+    fn parses_times_in_common_formats() {
+        for (s, time) in [
+            ("23:00", hm(23, 0)),
+            ("7:05", hm(7, 5)),
+            ("11pm", hm(23, 0)),
+            ("11 PM", hm(23, 0)),
+            ("11:30 pm", hm(23, 30)),
+            ("11.30p.m.", hm(23, 30)),
+            ("12am", hm(0, 0)),
+            ("12:15 PM", hm(12, 15)),
+            ("8 a.m.", hm(8, 0)),
+            (" 22 ", hm(22, 0)),
+        ] {
+            assert_eq!(parse_time(s), Some(time), "{}", s);
+        }
+        for s in [
+            "", "pm", "13pm", "0am", "24:00", "11:3", "11:300", "+1:00", "noon",
+        ] {
+            assert_eq!(parse_time(s), None, "{}", s);
+        }
+    }
+
+    #[test]
+    fn weekly_notes_are_kept_by_the_start_they_were_written_for() {
+        let mut data = Data::new(ymd(2021, 5, 9));
+        data.set_weekly_note(ymd(2021, 5, 3), "Slept late on Friday".to_owned());
+        data.set_weekly_note(ymd(2021, 5, 5), "Rolling week".to_owned());
+        data.set_weekly_note(ymd(2021, 5, 10), " \n".to_owned());
+        assert_eq!(data.weekly_notes.len(), 2);
+        let notes: Vec<(NaiveDate, &str)> = data
+            .weekly_notes_in(ymd(2021, 5, 3), ymd(2021, 5, 9))
+            .collect();
+        assert_eq!(
+            notes,
+            vec![
+                (ymd(2021, 5, 3), "Slept late on Friday"),
+                (ymd(2021, 5, 5), "Rolling week"),
+            ]
+        );
+
+        data.set_weekly_note(ymd(2021, 5, 5), String::new());
+        assert!(!data.weekly_notes.contains_key(&ymd(2021, 5, 5)));
+    }
+
+    #[test]
+    fn is_weekend_only_on_saturday_and_sunday() {
+        // 2021-05-03 was a Monday.
+        let weekends: Vec<bool> = (3..=9).map(|day| is_weekend(ymd(2021, 5, day))).collect();
+        assert_eq!(
+            weekends,
+            vec![false, false, false, false, false, true, true]
+        );
+    }
+
+    fn data_with_weekend_goals() -> Data {
+        // 2021-05-07 was a Friday.
+        let mut data = Data::new(ymd(2021, 5, 7));
+        let mut weekend = data.default_work_sleep_goals.clone();
+        weekend.target_work_count = 2;
+        weekend.target_bedtime.time = hm(23, 45);
+        data.weekend_goals = Some(weekend);
+        data
+    }
+
+    #[test]
+    fn weekend_goals_start_on_saturday() {
+        let mut data = data_with_weekend_goals();
+        let friday = data.day_mut(ymd(2021, 5, 7));
+        assert_eq!(friday.goals, None);
+        assert!(!friday.weekend_preset);
+
+        let saturday = data.day_mut(ymd(2021, 5, 8)).clone();
+        assert_eq!(saturday.goals, data.weekend_goals);
+        assert!(saturday.weekend_preset);
+        assert_eq!(data.goals_on(ymd(2021, 5, 9)).target_work_count, 2);
+        assert_eq!(
+            data.goals_on(ymd(2021, 5, 10)).target_work_count,
+            data.default_work_sleep_goals.target_work_count
+        );
+    }
+
+    #[test]
+    fn weekend_days_use_the_defaults_without_weekend_goals() {
+        let mut data = Data::new(ymd(2021, 5, 8));
+        let saturday = data.day_mut(ymd(2021, 5, 8));
+        assert_eq!(saturday.goals, None);
+        assert!(!saturday.weekend_preset);
+        assert_eq!(
+            data.goals_on(ymd(2021, 5, 9)),
+            &data.default_work_sleep_goals
+        );
+    }
+
+    #[test]
+    fn existing_days_keep_their_goals_when_weekend_goals_change() {
+        let mut data = data_with_weekend_goals();
+        data.day_mut(ymd(2021, 5, 8));
+        data.default_work_sleep_goals.target_work_count = 5;
+        data.weekend_goals = None;
+        data.day_mut(ymd(2021, 5, 8)).actual_work_count = 1;
+        let saturday = &data.work_sleep_data.days()[&ymd(2021, 5, 8)];
+        assert!(saturday.weekend_preset);
+        assert_eq!(data.goals_on(ymd(2021, 5, 8)).target_work_count, 2);
+    }
+
+    #[test]
+    fn rolling_weeks_end_on_the_current_date_wherever_the_data_is() {
+        let week_start = |data: &mut WorkSleepData, date| {
+            data.set_week_start(&date, WeekMode::Rolling);
+            data.week_start
+        };
+        let mut data = WorkSleepData::new(ymd(2021, 1, 1));
+        assert_eq!(week_start(&mut data, ymd(2021, 5, 10)), ymd(2021, 5, 4));
+
+        for day in 5..=15 {
+            data.get_mut_or_create(&ymd(2021, 5, day));
+        }
+        // After, before and in the middle of the recorded days.
+        assert_eq!(week_start(&mut data, ymd(2021, 5, 20)), ymd(2021, 5, 14));
+        assert_eq!(week_start(&mut data, ymd(2021, 5, 1)), ymd(2021, 4, 25));
+        assert_eq!(week_start(&mut data, ymd(2021, 5, 10)), ymd(2021, 5, 4));
+    }
+
+    #[test]
+    fn calendar_weeks_snap_back_across_year_boundaries() {
+        let monday = WeekMode::Calendar(Weekday::Mon);
+        let sunday = WeekMode::Calendar(Weekday::Sun);
+        // 2021-01-01 was a Friday, and 2020-12-28 the Monday before it.
+        assert_eq!(monday.align(ymd(2021, 1, 1)), ymd(2020, 12, 28));
+        assert_eq!(monday.align(ymd(2020, 12, 28)), ymd(2020, 12, 28));
+        assert_eq!(sunday.align(ymd(2021, 1, 2)), ymd(2020, 12, 27));
+        assert_eq!(sunday.align(ymd(2021, 1, 3)), ymd(2021, 1, 3));
+        assert_eq!(WeekMode::Rolling.align(ymd(2021, 1, 1)), ymd(2021, 1, 1));
+
+        let mut data = WorkSleepData::new(ymd(2021, 1, 1));
+        data.set_week_start(&ymd(2021, 1, 1), monday);
+        assert_eq!(data.week_start, ymd(2020, 12, 28));
+        data.shift_weeks(1, monday);
+        assert_eq!(data.week_start, ymd(2021, 1, 4));
+        // A week start left unaligned by the month view snaps to the week it steps into.
+        data.week_start = ymd(2021, 1, 1);
+        data.shift_weeks(-1, monday);
+        assert_eq!(data.week_start, ymd(2020, 12, 21));
+    }
+
+    #[test]
+    fn get_month_pads_to_whole_weeks() {
+        let data = WorkSleepData::new(ymd(2021, 1, 1));
+        let slots = |year, month| {
+            let days = data.get_month(year, month);
+            let leading = days.iter().take_while(|day| day.is_none()).count();
+            let filled = days.iter().filter(|day| day.is_some()).count();
+            (days.len(), leading, filled)
+        };
+        // February 2021 starts on a Monday and fills exactly four weeks.
+        assert_eq!(slots(2021, 2), (28, 0, 28));
+        assert_eq!(slots(2020, 2), (35, 5, 29));
+        assert_eq!(slots(2021, 4), (35, 3, 30));
+        assert_eq!(slots(2021, 8), (42, 6, 31));
+        assert_eq!(slots(2021, 12), (35, 2, 31));
+    }
+
+    fn record_day(
+        data: &mut WorkSleepData,
+        date: NaiveDate,
+        work: i64,
+        bedtime: Option<(u32, u32)>,
+    ) {
+        let ws = data.get_mut_or_create(&date);
+        ws.actual_work_count = work;
+        ws.actual_bedtime = bedtime.map(|(h, m)| Bedtime {
+            time: hm(h, m),
+            next_day: false,
+        });
+    }
+
+    #[test]
+    fn streaks_are_broken_by_missing_dates() {
+        let start = ymd(2021, 5, 1);
+        let mut data = WorkSleepData::new(start);
+        for offset in [0, 1, 2, 4, 5] {
+            record_day(&mut data, start + Duration::days(offset), 6, Some((23, 0)));
+        }
+        let streaks = data.streaks(start + Duration::days(5), &WorkSleepGoals::default());
+        assert_eq!(
+            streaks,
+            Streaks {
+                current: 2,
+                longest: 3
+            }
+        );
+        assert_eq!(
+            data.streaks(start + Duration::days(3), &WorkSleepGoals::default())
+                .current,
+            3
+        );
+    }
+
+    #[test]
+    fn streaks_require_a_bedtime_near_the_target() {
+        let start = ymd(2021, 5, 1);
+        let mut data = WorkSleepData::new(start);
+        record_day(&mut data, start, 6, Some((23, 0)));
+        record_day(&mut data, start + Duration::days(1), 6, None);
+        record_day(&mut data, start + Duration::days(2), 6, Some((1, 0)));
+        let streaks = data.streaks(start + Duration::days(2), &WorkSleepGoals::default());
+        assert_eq!(
+            streaks,
+            Streaks {
+                current: 0,
+                longest: 1
+            }
+        );
+    }
+
+    #[test]
+    fn streaks_starting_at_the_first_recorded_date() {
+        let start = ymd(2021, 5, 1);
+        let mut data = WorkSleepData::new(start);
+        for offset in 0..4 {
+            record_day(&mut data, start + Duration::days(offset), 7, Some((23, 10)));
+        }
+        let streaks = data.streaks(start + Duration::days(3), &WorkSleepGoals::default());
+        assert_eq!(
+            streaks,
+            Streaks {
+                current: 4,
+                longest: 4
+            }
+        );
+        // A current date that hasn't been recorded yet doesn't break the streak.
+        assert_eq!(
+            data.streaks(start + Duration::days(4), &WorkSleepGoals::default())
+                .current,
+            4
+        );
+    }
+
+    #[test]
+    fn bedtime_countdown_runs_past_midnight_for_a_same_day_target() {
+        let date = ymd(2021, 5, 3);
+        let target = Bedtime {
+            time: hm(23, 30),
+            next_day: false,
+        };
+        let countdown = |day: NaiveDate, h, m, s| {
+            bedtime_countdown(day.and_hms_opt(h, m, s).unwrap(), &target, date)
+        };
+        assert_eq!(
+            countdown(date, 21, 16, 0),
+            Some(BedtimeCountdown::Left(134))
+        );
+        assert_eq!(countdown(date, 23, 29, 30), Some(BedtimeCountdown::Left(1)));
+        assert_eq!(countdown(date, 23, 30, 0), Some(BedtimeCountdown::Past(0)));
+        let next = date.succ_opt().unwrap();
+        assert_eq!(countdown(next, 0, 7, 59), Some(BedtimeCountdown::Past(37)));
+    }
+
+    #[test]
+    fn bedtime_countdown_for_a_target_after_midnight_is_past_on_the_next_day() {
+        let date = ymd(2021, 5, 3);
+        let target = Bedtime {
+            time: hm(0, 30),
+            next_day: true,
+        };
+        let next = date.succ_opt().unwrap();
+        let countdown = |day: NaiveDate, h, m| {
+            bedtime_countdown(day.and_hms_opt(h, m, 0).unwrap(), &target, date)
+        };
+        // Half past midnight on `date` itself is a day early.
+        assert_eq!(countdown(date, 0, 40), Some(BedtimeCountdown::Left(1430)));
+        assert_eq!(countdown(date, 23, 50), Some(BedtimeCountdown::Left(40)));
+        assert_eq!(countdown(next, 0, 0), Some(BedtimeCountdown::Left(30)));
+        assert_eq!(countdown(next, 0, 45), Some(BedtimeCountdown::Past(15)));
+    }
+
+    #[test]
+    fn hours_are_left_out_under_an_hour() {
+        assert_eq!(format_hours_minutes(134), "2h 14m");
+        assert_eq!(format_hours_minutes(60), "1h 0m");
+        assert_eq!(format_hours_minutes(37), "37m");
+    }
+
+    #[test]
+    fn bedtime_reminder_due_within_lead_time() {
+        let date = ymd(2021, 5, 3);
+        let target = Bedtime {
+            time: hm(23, 0),
+            next_day: false,
+        };
+        let at = |h, m| date.and_hms_opt(h, m, 0).unwrap();
+        assert!(!bedtime_reminder_due(at(22, 29), date, &target, 30));
+        assert!(bedtime_reminder_due(at(22, 30), date, &target, 30));
+        assert!(bedtime_reminder_due(at(22, 59), date, &target, 30));
+        assert!(!bedtime_reminder_due(at(23, 0), date, &target, 30));
+    }
+
+    #[test]
+    fn bedtime_reminders_are_due_once_per_date_without_a_bedtime() {
+        let today = ymd(2021, 5, 5);
+        let mut data = Data::new(today);
+        data.default_work_sleep_goals.target_bedtime = Bedtime::from_time_of_day(hm(0, 30));
+        data.bedtime_reminder_lead_minutes = 30;
+        let evening = today.and_time(hm(23, 0));
+        assert_eq!(data.bedtime_reminder_date(evening, today), None);
+
+        let before_bedtime = ymd(2021, 5, 6).and_time(hm(0, 10));
+        let tomorrow = ymd(2021, 5, 6);
+        assert_eq!(
+            data.bedtime_reminder_date(before_bedtime, tomorrow),
+            Some(today)
+        );
+        data.last_bedtime_reminder = Some(today);
+        assert_eq!(data.bedtime_reminder_date(before_bedtime, tomorrow), None);
+        data.last_bedtime_reminder = None;
+        data.day_mut(today).actual_bedtime = Some(Bedtime::from_time_of_day(hm(23, 50)));
+        assert_eq!(data.bedtime_reminder_date(before_bedtime, tomorrow), None);
+    }
+
+    #[test]
+    fn bedtime_reminder_due_for_a_target_after_midnight() {
+        let date = ymd(2021, 5, 3);
+        let target = Bedtime {
+            time: hm(0, 15),
+            next_day: true,
+        };
+        assert!(bedtime_reminder_due(
+            date.and_hms_opt(23, 50, 0).unwrap(),
+            date,
+            &target,
+            30
+        ));
+        assert!(bedtime_reminder_due(
+            date.succ_opt().unwrap().and_hms_opt(0, 5, 0).unwrap(),
+            date,
+            &target,
+            30
+        ));
+        assert!(!bedtime_reminder_due(
+            date.and_hms_opt(0, 5, 0).unwrap(),
+            date,
+            &target,
+            30
+        ));
+    }
+
+    #[test]
+    fn imported_bedtimes_keep_recorded_ones_unless_overwriting() {
+        let today = ymd(2021, 5, 5);
+        let mut data = Data::new(today);
+        data.work_sleep_data.archive_before(ymd(2021, 5, 1));
+        let recorded = Bedtime::from_time_of_day(hm(22, 0));
+        data.day_mut(ymd(2021, 5, 3)).actual_bedtime = Some(recorded.clone());
+        let late = Bedtime::from_time_of_day(hm(23, 30));
+        let bedtimes = vec![
+            (ymd(2021, 4, 30), late.clone()),
+            (ymd(2021, 5, 3), late.clone()),
+            (ymd(2021, 5, 4), late.clone()),
+            (ymd(2021, 5, 7), late.clone()),
+        ];
+        assert_eq!(data.import_bedtimes(bedtimes.clone(), false, today), (1, 3));
+        let days = data.work_sleep_data.days();
+        assert_eq!(days[&ymd(2021, 5, 3)].actual_bedtime, Some(recorded));
+        assert_eq!(days[&ymd(2021, 5, 4)].actual_bedtime, Some(late.clone()));
+        assert_eq!(days.len(), 2);
+
+        assert_eq!(data.import_bedtimes(bedtimes, true, today), (2, 2));
+        let days = data.work_sleep_data.days();
+        assert_eq!(days[&ymd(2021, 5, 3)].actual_bedtime, Some(late));
+    }
+
+    #[test]
+    fn week_report_marks_days_without_data() {
+        let mut data = WorkSleepData::new(ymd(2021, 5, 3));
+        let ws = data.get_mut_or_create(&ymd(2021, 5, 4));
+        ws.actual_work_count = 3;
+        ws.actual_bedtime = Some(Bedtime {
+            time: hm(0, 30),
+            next_day: true,
+        });
+        let goals = WorkSleepGoals::default();
+        let report = week_report(
+            &data.get_current_week(),
+            &data.week_summary(&goals),
+            &goals,
+            DateFormat::WeekdayFirst,
+            ClockFormat::TwelveHour,
+            Lang::English,
+        );
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[2], "| Mon May 3 | — | — | — |");
+        assert_eq!(lines[3], "| Tue May 4 | 3/6 | 12:30 AM (next day) | 39 |");
+        assert_eq!(lines[9], "| **Week** | 3/6 | 12:30 AM (next day) | 39 |");
+
+        let report = week_report(
+            &data.get_current_week(),
+            &data.week_summary(&goals),
+            &goals,
+            DateFormat::WeekdayFirst,
+            ClockFormat::TwentyFourHour,
+            Lang::Spanish,
+        );
+        assert!(report.contains("| 00:30 (día siguiente) |"));
+        assert!(!report.contains("next day"));
+    }
+
+    #[test]
+    fn period_category_is_omitted_when_unset() {
+        let json = serde_json::to_string(&period("read")).unwrap();
+        assert!(!json.contains("category"));
+        let categorized = Period {
+            category: Some("thesis".to_owned()),
+            ..period("write")
+        };
+        let json = serde_json::to_string(&categorized).unwrap();
+        let parsed: Period = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.category.as_deref(), Some("thesis"));
+    }
+
+    #[test]
+    fn effective_date_respects_the_day_cutoff() {
+        let date = ymd(2021, 5, 3);
+        let at = |h, m| date.and_time(hm(h, m));
+        assert_eq!(effective_date(at(0, 30), 0), date);
+        assert_eq!(effective_date(at(0, 30), 4), ymd(2021, 5, 2));
+        assert_eq!(effective_date(at(3, 59), 4), ymd(2021, 5, 2));
+        assert_eq!(effective_date(at(4, 0), 4), date);
+        assert_eq!(effective_date(at(23, 0), 4), date);
+    }
+
+    #[test]
+    fn bedtime_now_is_on_the_next_day_until_the_cutoff() {
+        let bedtime = |h, m, next_day| Bedtime {
+            time: hm(h, m),
+            next_day,
+        };
+        let at = |d, h, m| ymd(2021, 5, d).and_hms_opt(h, m, 42).unwrap();
+        assert_eq!(
+            bedtime_now(at(3, 23, 59), 4),
+            Some((ymd(2021, 5, 3), bedtime(23, 59, false)))
+        );
+        assert_eq!(
+            bedtime_now(at(4, 0, 0), 4),
+            Some((ymd(2021, 5, 3), bedtime(0, 0, true)))
+        );
+        assert_eq!(
+            bedtime_now(at(4, 3, 59), 4),
+            Some((ymd(2021, 5, 3), bedtime(3, 59, true)))
+        );
+        assert_eq!(
+            bedtime_now(at(4, 4, 0), 4),
+            Some((ymd(2021, 5, 4), bedtime(4, 0, false)))
+        );
+        // Without a cutoff, midnight starts the next day.
+        assert_eq!(
+            bedtime_now(at(4, 0, 0), 0),
+            Some((ymd(2021, 5, 4), bedtime(0, 0, false)))
+        );
+    }
+
+    #[test]
+    fn date_stepping_stops_at_the_supported_range() {
+        let last = chrono::naive::MAX_DATE;
+        assert_eq!(shift_days(last, 7), last);
+        let data = WorkSleepData::new(shift_days(last, -2));
+        assert_eq!(data.get_current_week().len(), 3);
+    }
+
+    #[test]
+    fn add_months_across_years() {
+        let date = ymd(2021, 12, 25);
+        assert_eq!(add_months(date, 0), Some(ymd(2021, 12, 1)));
+        assert_eq!(add_months(date, 1), Some(ymd(2022, 1, 1)));
+        assert_eq!(add_months(date, -12), Some(ymd(2020, 12, 1)));
+        assert_eq!(add_months(date, i32::MAX), None);
+    }
+
+    #[test]
+    fn recurring_task_weekday_mask() {
+        let mut task = RecurringTask {
+            id: Uuid::new_v4(),
+            name: "laundry".to_owned(),
+            quantity: 1,
+            weekdays: None,
+        };
+        let monday = ymd(2021, 5, 3);
+        assert!(task.is_due(monday));
+        let mut weekdays = [false; 7];
+        weekdays[6] = true;
+        task.weekdays = Some(weekdays);
+        assert!(!task.is_due(monday));
+        assert!(task.is_due(monday + Duration::days(6)));
+    }
+
+    #[test]
+    fn format_countdown_pads_minutes_and_seconds() {
+        assert_eq!(format_countdown(25 * 60), "25:00");
+        assert_eq!(format_countdown(65), "01:05");
+        assert_eq!(format_countdown(0), "00:00");
+    }
+
+    #[test]
+    fn finish_estimates_run_past_midnight_and_the_target_bedtime() {
+        let date = ymd(2021, 5, 3);
+        let now = date.and_hms_opt(21, 30, 0).unwrap();
+        let target = Bedtime {
+            time: hm(23, 0),
+            next_day: false,
+        };
+        let finish = estimate_finish(3, 25, now);
+        assert_eq!(finish, date.and_hms_opt(22, 45, 0).unwrap());
+        assert_eq!(bedtime_overshoot(date, &target, finish), None);
+
+        let finish = estimate_finish(9, 25, now);
+        assert_eq!(finish, ymd(2021, 5, 4).and_hms_opt(1, 15, 0).unwrap());
+        assert_eq!(bedtime_overshoot(date, &target, finish), Some(135));
+        assert_eq!(estimate_finish(0, 25, now), now);
+
+        let after_midnight = Bedtime {
+            time: hm(0, 30),
+            next_day: true,
+        };
+        assert_eq!(bedtime_overshoot(date, &after_midnight, finish), Some(45));
+    }
+
+    #[test]
+    fn average_period_minutes_uses_the_latest_timed_periods() {
+        let mut data = WorkSleepData::new(ymd(2021, 5, 1));
+        assert_eq!(data.average_period_minutes(3), None);
+        for (day, minutes) in [(1, Some(60)), (2, None), (2, Some(20)), (3, Some(30))] {
+            data.get_mut_or_create(&ymd(2021, 5, day))
+                .completed
+                .push(CompletedPeriod {
+                    id: Uuid::new_v4(),
+                    name: "read".to_owned(),
+                    completed_at: hm(9, 0),
+                    duration_minutes: minutes,
+                    category: None,
+                    note: String::new(),
+                });
+        }
+        assert_eq!(data.average_period_minutes(2), Some(25));
+        assert_eq!(data.average_period_minutes(10), Some(36));
+    }
+
+    #[test]
+    fn note_preview_cuts_long_or_multiline_notes() {
+        assert_eq!(note_preview("short", 10), "short");
+        assert_eq!(note_preview("exactly 10", 10), "exactly 10");
+        assert_eq!(note_preview("rather long note", 10), "rather lon…");
+        assert_eq!(note_preview("first\nsecond", 10), "first…");
+        assert_eq!(note_preview("ünïcödé ëvërywhërë", 7), "ünïcödé…");
+    }
+
+    #[test]
+    fn tab_title_shows_the_next_task_cut_short() {
+        assert_eq!(tab_title(None), "slorz");
+        assert_eq!(tab_title(Some("write intro")), "▶ write intro — slorz");
+        let long = "a".repeat(60);
+        assert_eq!(
+            tab_title(Some(&long)),
+            format!("▶ {}… — slorz", "a".repeat(40))
+        );
+        assert_eq!(note_preview("", 10), "");
+    }
+
+    #[test]
+    fn minutes_between_wraps_past_midnight() {
+        assert_eq!(minutes_between(hm(9, 0), hm(9, 25)), 25);
+        assert_eq!(minutes_between(hm(23, 50), hm(0, 10)), 20);
+    }
+
+    #[test]
+    fn elapsed_minutes_adds_running_time_to_tracked_time() {
+        let mut wp = period("read");
+        assert_eq!(wp.elapsed_minutes(hm(10, 0)), None);
+        wp.started_at = Some(hm(9, 0));
+        wp.stop_timer(hm(9, 20));
+        assert_eq!(wp.tracked_minutes, 20);
+        wp.started_at = Some(hm(10, 0));
+        assert_eq!(wp.elapsed_minutes(hm(10, 5)), Some(25));
+    }
+
+    #[test]
+    fn parse_quantity_accepts_positive_integers() {
+        assert_eq!(parse_quantity("3", 100, Lang::English), Ok(3));
+        assert_eq!(parse_quantity(" 12 ", 100, Lang::English), Ok(12));
+    }
+
+    #[test]
+    fn parse_quantity_rejects_invalid_values() {
+        assert!(parse_quantity("ten", 100, Lang::English).is_err());
+        assert!(parse_quantity("", 100, Lang::English).is_err());
+        assert!(parse_quantity("0", 100, Lang::English).is_err());
+        assert!(parse_quantity("-3", 100, Lang::English).is_err());
+    }
+
+    #[test]
+    fn parse_quantity_caps_at_max() {
+        assert_eq!(parse_quantity("100", 100, Lang::English), Ok(100));
+        assert_eq!(parse_quantity("1000000", 100, Lang::English), Ok(100));
+    }
+
+    #[test]
+    fn parse_weight_rounds_to_one_decimal() {
+        assert_eq!(parse_weight("2.5", Lang::English), Ok(2.5));
+        assert_eq!(parse_weight(" 1.26 ", Lang::English), Ok(1.3));
+        assert_eq!(parse_weight("3", Lang::English), Ok(3.0));
+    }
+
+    #[test]
+    fn parse_weight_clamps_into_range() {
+        assert_eq!(parse_weight("0", Lang::English), Ok(MIN_TASK_WEIGHT));
+        assert_eq!(parse_weight("-2", Lang::English), Ok(MIN_TASK_WEIGHT));
+        assert_eq!(parse_weight("0.04", Lang::English), Ok(MIN_TASK_WEIGHT));
+        assert_eq!(parse_weight("12", Lang::English), Ok(MAX_TASK_WEIGHT));
+    }
+
+    #[test]
+    fn parse_weight_rejects_invalid_values() {
+        assert!(parse_weight("heavy", Lang::English).is_err());
+        assert!(parse_weight("", Lang::English).is_err());
+        assert!(parse_weight("NaN", Lang::English).is_err());
+        assert!(parse_weight("inf", Lang::English).is_err());
+    }
+
+    #[test]
+    fn periods_saved_without_a_weight_weigh_one() {
+        let json = r#"{"id":"67e55044-10b1-426f-9247-bb680e5fe0c8","name":"read"}"#;
+        let period: Period = serde_json::from_str(json).unwrap();
+        assert_eq!(period.weight, 1.0);
+        assert!(!serde_json::to_string(&period).unwrap().contains("weight"));
+    }
+
+    fn period(name: &str) -> Period {
+        Period::new(name.to_owned())
+    }
+
+    #[test]
+    fn sorting_by_priority_keeps_the_order_within_each_priority() {
+        let mut periods: VecDeque<Period> = [
+            ("a", Priority::Low),
+            ("b", Priority::Normal),
+            ("c", Priority::High),
+            ("d", Priority::Low),
+            ("e", Priority::High),
+            ("f", Priority::Normal),
+        ]
+        .iter()
+        .map(|(name, priority)| Period {
+            priority: *priority,
+            ..period(name)
+        })
+        .collect();
+        sort_by_priority(&mut periods);
+        let names: Vec<&str> = periods.iter().map(|wp| wp.name.as_str()).collect();
+        assert_eq!(names, vec!["c", "e", "b", "f", "a", "d"]);
+    }
+
+    #[test]
+    fn periods_saved_without_a_priority_are_normal() {
+        let json = serde_json::to_string(&period("write")).unwrap();
+        assert!(!json.contains("priority"));
+        let wp: Period = serde_json::from_str(&json).unwrap();
+        assert_eq!(wp.priority, Priority::Normal);
+    }
+
+    #[test]
+    fn group_consecutive_only_merges_adjacent_names() {
+        let periods: VecDeque<Period> = ["a", "a", "b", "a", "c", "c", "c"]
+            .iter()
+            .map(|name| period(name))
+            .collect();
+        assert_eq!(group_consecutive(&periods), vec![0..2, 2..3, 3..4, 4..7]);
+        assert!(group_consecutive(&VecDeque::new()).is_empty());
+    }
+
+    #[test]
+    fn remove_and_insert_tasks_keep_group_order() {
+        let mut periods: VecDeque<Period> = ["a", "b", "b", "c"]
+            .iter()
+            .map(|name| period(name))
+            .collect();
+        let ids = vec![periods[1].id, periods[2].id];
+        let (start, tasks) = remove_tasks(&mut periods, &ids).unwrap();
+        assert_eq!(start, 1);
+        insert_tasks(&mut periods, 0, tasks);
+        let names: Vec<&str> = periods.iter().map(|wp| wp.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "b", "a", "c"]);
+        assert!(remove_tasks(&mut periods, &[Uuid::new_v4()]).is_none());
+    }
+
+    #[test]
+    fn bedtime_consistency_only_looks_at_nights_in_range() {
+        let end = ymd(2021, 3, 14);
+        let mut data = WorkSleepData::new(end);
+        let at = |h, m| Bedtime {
+            time: NaiveTime::from_hms_opt(h, m, 0).unwrap(),
+            next_day: false,
+        };
+        for (day, bedtime) in [
+            (1, at(12, 0)),
+            (12, at(22, 0)),
+            (13, at(23, 0)),
+            (14, at(22, 30)),
+        ] {
+            data.get_mut_or_create(&ymd(2021, 3, day)).actual_bedtime = Some(bedtime);
+        }
+        data.get_mut_or_create(&ymd(2021, 3, 10));
+        data.get_mut_or_create(&ymd(2021, 3, 15)).actual_bedtime = Some(at(3, 0));
+
+        let stats = data.bedtime_consistency(end, 7).unwrap();
+        assert_eq!(stats.nights, 3);
+        assert_eq!(stats.mean, at(22, 30));
+        assert_eq!(data.bedtime_consistency(end, 2), None);
+        assert_eq!(data.bedtime_consistency(end, 14).unwrap().nights, 4);
+    }
+
+    #[test]
+    fn week_summary_only_counts_the_displayed_week() {
+        let week_start = ymd(2021, 3, 1);
+        let mut data = WorkSleepData::new(week_start);
+        data.get_mut_or_create(&week_start).actual_work_count = 6;
+        data.get_mut_or_create(&(week_start + Duration::days(2)))
+            .actual_work_count = 3;
+        data.get_mut_or_create(&(week_start + Duration::days(7)))
+            .actual_work_count = 6;
+
+        let summary = data.week_summary(&WorkSleepGoals::default());
+        assert_eq!(summary.days_with_data, 2);
+        assert_eq!(summary.work_completed, 9.0);
+    }
+
+    #[test]
+    fn dates_in_fills_in_missing_days() {
+        let start = ymd(2021, 3, 1);
+        let mut data = WorkSleepData::new(start);
+        data.get_mut_or_create(&ymd(2021, 2, 28));
+        data.get_mut_or_create(&ymd(2021, 3, 2)).actual_work_count = 2;
+        let dates: Vec<_> = data
+            .dates_in(start, ymd(2021, 3, 3))
+            .map(|(date, ws)| (date.day(), ws.map(|ws| ws.actual_work_count)))
+            .collect();
+        assert_eq!(dates, vec![(1, None), (2, Some(2)), (3, None)]);
+        assert_eq!(data.dates_in(start, ymd(2021, 2, 27)).count(), 0);
+    }
+
+    #[test]
+    fn every_change_to_the_days_marks_cached_scores_stale() {
+        let date = ymd(2021, 3, 1);
+        let mut data = WorkSleepData::new(date);
+        let goals = WorkSleepGoals::default();
+        let score = |data: &WorkSleepData, goals| data.score(date, &data.days()[&date], goals);
+        data.get_mut_or_create(&date).actual_work_count = 6;
+        let full = score(&data, &goals);
+        assert_eq!(full, data.days()[&date].calc_score(&goals));
+
+        data.get_mut(&date).unwrap().actual_work_count = 0;
+        let none = score(&data, &goals);
+        assert_ne!(none, full);
+        assert_eq!(none, data.days()[&date].calc_score(&goals));
+        data.days_mut().get_mut(&date).unwrap().actual_work_count = 6;
+        assert_eq!(score(&data, &goals), full);
+        data.get_mut_or_create(&date).actual_work_count = 0;
+        assert_eq!(score(&data, &goals), none);
+
+        let other_goals = WorkSleepGoals {
+            target_work_count: 1,
+            ..goals.clone()
+        };
+        let expected = data.days()[&date].calc_score(&other_goals);
+        assert_eq!(score(&data, &other_goals), expected);
+
+        let revision = data.scores.revision;
+        data.archive_before(date);
+        assert!(data.scores.revision > revision);
+    }
+
+    #[test]
+    fn summarize_range_skips_missing_days() {
+        let start = ymd(2021, 3, 1);
+        let mut data = WorkSleepData::new(start);
+        let goals = WorkSleepGoals::default();
+        let late = goals.target_bedtime.plus_minutes(30).unwrap();
+        let early = goals.target_bedtime.plus_minutes(-10).unwrap();
+        data.get_mut_or_create(&start).actual_bedtime = Some(late);
+        data.get_mut_or_create(&ymd(2021, 3, 4)).actual_work_count = 4;
+        data.get_mut_or_create(&ymd(2021, 3, 7)).actual_bedtime = Some(early);
+        data.get_mut_or_create(&ymd(2021, 3, 8)).actual_work_count = 6;
+
+        let summary = data.summarize_range(start, 7, &goals);
+        assert_eq!(summary.days_with_data, 3);
+        assert_eq!(summary.work_completed, 4.0);
+        assert_eq!(summary.average_bedtime_deviation, Some(10.0));
+        assert_eq!(
+            data.summarize_range(ymd(2021, 2, 22), 7, &goals),
+            RangeSummary::default()
+        );
+    }
+}
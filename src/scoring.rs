@@ -0,0 +1,1322 @@
+// Work/sleep scoring. This module is kept free of `seed` and `web_sys`
+// so that it can be unit tested natively.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::i18n::{Lang, TextKey};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+// Longer nights are taken for a wrong wake time rather than for that much sleep.
+pub const MAX_SLEEP_MINUTES: i64 = 16 * 60;
+pub const MOOD_EMOJIS: [&str; 5] = ["😫", "🙁", "😐", "🙂", "😄"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bedtime {
+    pub time: NaiveTime,
+    pub next_day: bool,
+}
+
+impl Bedtime {
+    // Minutes since the start of the current date, so bedtimes after midnight sort last.
+    pub fn minutes_since_midnight(&self) -> i64 {
+        let minutes = (self.time.num_seconds_from_midnight() / 60) as i64;
+        if self.next_day {
+            minutes + MINUTES_PER_DAY
+        } else {
+            minutes
+        }
+    }
+
+    // A bedtime at `now` for the night of `date`, or `None` unless `now` falls on
+    // `date` or the day after.
+    pub fn at(date: NaiveDate, now: NaiveDateTime) -> Option<Self> {
+        let midnight = date.and_hms_opt(0, 0, 0)?;
+        Self::from_minutes_since_midnight((now - midnight).num_minutes())
+    }
+
+    // A bedtime typed as a time of day. Times before noon are taken for the morning after.
+    pub fn from_time_of_day(time: NaiveTime) -> Self {
+        Self {
+            time,
+            next_day: time.hour() < 12,
+        }
+    }
+
+    pub fn plus_minutes(&self, minutes: i64) -> Option<Self> {
+        Self::from_minutes_since_midnight(self.minutes_since_midnight() + minutes)
+    }
+
+    // The inverse of `minutes_since_midnight`, or `None` if `minutes` is out of range.
+    fn from_minutes_since_midnight(minutes: i64) -> Option<Self> {
+        if !(0..2 * MINUTES_PER_DAY).contains(&minutes) {
+            return None;
+        }
+        Some(Self {
+            time: NaiveTime::from_num_seconds_from_midnight_opt(
+                (minutes % MINUTES_PER_DAY * 60) as u32,
+                0,
+            )?,
+            next_day: minutes >= MINUTES_PER_DAY,
+        })
+    }
+
+    // Minutes between two bedtimes on a 24-hour clock, so a bedtime that straddles
+    // midnight without `next_day` set is still compared to the nearest target.
+    pub fn abs_diff(&self, other: &Self) -> i64 {
+        let one_day = Duration::days(1);
+        let zero = Duration::zero();
+        let minutes = (other.time - self.time + if other.next_day { one_day } else { zero }
+            - if self.next_day { one_day } else { zero })
+        .num_minutes()
+        .abs()
+            % MINUTES_PER_DAY;
+        minutes.min(MINUTES_PER_DAY - minutes)
+    }
+
+    // Like `abs_diff`, but positive when `self` is later than `target` and negative when
+    // it is earlier.
+    pub fn signed_diff(&self, target: &Self) -> i64 {
+        let minutes = (self.minutes_since_midnight() - target.minutes_since_midnight())
+            .rem_euclid(MINUTES_PER_DAY);
+        if minutes > MINUTES_PER_DAY / 2 {
+            minutes - MINUTES_PER_DAY
+        } else {
+            minutes
+        }
+    }
+
+    // Minutes asleep from this bedtime until `wake_time`, both relative to the same date,
+    // or `None` unless that's positive and at most `MAX_SLEEP_MINUTES`.
+    pub fn sleep_minutes_until(&self, wake_time: &Self) -> Option<i64> {
+        let minutes = wake_time.minutes_since_midnight() - self.minutes_since_midnight();
+        Some(minutes).filter(|minutes| (1..=MAX_SLEEP_MINUTES).contains(minutes))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkSleepGoals {
+    pub work_sleep_balance: i64,
+    pub target_work_count: i64,
+    pub target_bedtime: Bedtime,
+    pub bedtime_pts_halflife: i64,
+    #[serde(default)]
+    pub bedtime_penalty_mode: BedtimePenaltyMode,
+    // Whether work beyond the target stops earning points. Goals saved before this
+    // option existed keep the uncapped behavior.
+    #[serde(default)]
+    pub cap_work_score: bool,
+}
+
+// Which bedtimes off the target lose sleep points.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BedtimePenaltyMode {
+    // Early and late bedtimes are penalized alike.
+    #[default]
+    Symmetric,
+    // Any bedtime at or before the target earns full sleep points.
+    LateOnly,
+}
+
+impl WorkSleepGoals {
+    // Minutes of deviation from the target bedtime that count against the sleep points.
+    pub fn bedtime_penalty_minutes(&self, bedtime: &Bedtime) -> i64 {
+        match self.bedtime_penalty_mode {
+            BedtimePenaltyMode::Symmetric => bedtime.abs_diff(&self.target_bedtime),
+            BedtimePenaltyMode::LateOnly => bedtime.signed_diff(&self.target_bedtime).max(0),
+        }
+    }
+
+    // Clamps every value into its valid range: the balance into 0..=100,
+    // and counts and half-lives to at least zero.
+    pub fn clamped(&self) -> Self {
+        Self {
+            work_sleep_balance: self.work_sleep_balance.clamp(0, 100),
+            target_work_count: self.target_work_count.max(0),
+            target_bedtime: self.target_bedtime.clone(),
+            bedtime_pts_halflife: self.bedtime_pts_halflife.max(0),
+            bedtime_penalty_mode: self.bedtime_penalty_mode,
+            cap_work_score: self.cap_work_score,
+        }
+    }
+}
+
+impl Default for WorkSleepGoals {
+    fn default() -> Self {
+        Self {
+            work_sleep_balance: 70,
+            target_work_count: 6,
+            target_bedtime: Bedtime {
+                time: NaiveTime::from_hms_opt(23, 0, 0).expect("23:00 is a valid time"),
+                next_day: false,
+            },
+            bedtime_pts_halflife: 30,
+            bedtime_penalty_mode: BedtimePenaltyMode::Symmetric,
+            cap_work_score: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkSleep {
+    // Goals set for this day alone. `None` follows the defaults in effect wherever the
+    // day is scored, so editing the defaults also rescores past days without their own.
+    #[serde(default, rename = "custom_goals")]
+    pub goals: Option<WorkSleepGoals>,
+    // Older versions copied the defaults into every day, marking hand-edited ones.
+    #[serde(default, rename = "goals", skip_serializing)]
+    legacy_goals: Option<WorkSleepGoals>,
+    #[serde(default, rename = "goals_overridden", skip_serializing)]
+    legacy_goals_overridden: bool,
+    pub actual_work_count: i64,
+    // What the weights of the finished periods add to `actual_work_count` beyond one
+    // each. Zero while every period weighs one, as all did before weights existed.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub extra_work_weight: f64,
+    pub actual_bedtime: Option<Bedtime>,
+    #[serde(default)]
+    pub completed: Vec<CompletedPeriod>,
+    // Whether the work count of a past day was corrected by hand.
+    #[serde(default)]
+    pub work_count_edited: bool,
+    // Whether reaching the work target was already celebrated, so that undoing and
+    // redoing the last task doesn't celebrate again.
+    #[serde(default)]
+    pub target_celebrated: bool,
+    // When this date's night ended, relative to this date like `actual_bedtime`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wake_time: Option<Bedtime>,
+    // How the day felt from 1 to 5, for comparing with the score. It doesn't change it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mood: Option<u8>,
+    // Whether `goals` were copied from the weekend goals when the day was created.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub weekend_preset: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompletedPeriod {
+    pub id: Uuid,
+    pub name: String,
+    pub completed_at: NaiveTime,
+    #[serde(default)]
+    pub duration_minutes: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+impl Default for WorkSleep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorkSleep {
+    pub fn new() -> Self {
+        Self {
+            goals: None,
+            legacy_goals: None,
+            legacy_goals_overridden: false,
+            actual_work_count: 0,
+            extra_work_weight: 0.0,
+            actual_bedtime: None,
+            completed: Vec::new(),
+            work_count_edited: false,
+            target_celebrated: false,
+            wake_time: None,
+            mood: None,
+            weekend_preset: false,
+        }
+    }
+
+    // Turns goals copied in by an older version into custom goals, unless they were
+    // never edited and still match `defaults`. Either way the day keeps its score.
+    pub fn migrate_legacy_goals(&mut self, defaults: &WorkSleepGoals) {
+        if let Some(goals) = self.legacy_goals.take() {
+            if self.legacy_goals_overridden || goals != *defaults {
+                self.goals = Some(goals);
+            }
+        }
+        self.legacy_goals_overridden = false;
+    }
+
+    pub fn effective_goals<'a>(&'a self, defaults: &'a WorkSleepGoals) -> &'a WorkSleepGoals {
+        self.goals.as_ref().unwrap_or(defaults)
+    }
+
+    pub fn sleep_minutes(&self) -> Option<i64> {
+        self.actual_bedtime
+            .as_ref()?
+            .sleep_minutes_until(self.wake_time.as_ref()?)
+    }
+
+    // Whether the recorded bedtime is within one half-life of the target.
+    pub fn bedtime_near_target(&self, defaults: &WorkSleepGoals) -> bool {
+        let goals = self.effective_goals(defaults).clamped();
+        self.actual_bedtime.as_ref().is_some_and(|bedtime| {
+            goals.bedtime_penalty_minutes(bedtime) <= goals.bedtime_pts_halflife
+        })
+    }
+
+    // The work periods counted towards the target, each by its weight.
+    pub fn weighted_work_count(&self) -> f64 {
+        (self.actual_work_count.max(0) as f64 + self.extra_work_weight).max(0.0)
+    }
+
+    // Whether both the work target and the bedtime target were hit.
+    pub fn met_targets(&self, defaults: &WorkSleepGoals) -> bool {
+        let target = self.effective_goals(defaults).clamped().target_work_count;
+        self.weighted_work_count() >= target as f64 && self.bedtime_near_target(defaults)
+    }
+
+    pub fn tracked_minutes(&self) -> i64 {
+        self.completed
+            .iter()
+            .filter_map(|cp| cp.duration_minutes)
+            .sum()
+    }
+
+    // Completed and still planned work periods as fractions of the work target. The
+    // completed fraction goes past 1 once the target is beaten. As in scoring, a
+    // target of zero counts as met, so the day is shown as fully done.
+    pub fn work_progress(&self, defaults: &WorkSleepGoals, planned: usize) -> WorkProgress {
+        let target = self.effective_goals(defaults).clamped().target_work_count;
+        if target == 0 {
+            return WorkProgress {
+                completed: 1.0,
+                planned: 0.0,
+            };
+        }
+        WorkProgress {
+            completed: self.weighted_work_count() / target as f64,
+            planned: planned as f64 / target as f64,
+        }
+    }
+
+    // The score the day would get if `bedtime` were recorded, whatever is recorded now.
+    pub fn score_with_bedtime(&self, defaults: &WorkSleepGoals, bedtime: Bedtime) -> i64 {
+        let mut ws = self.clone();
+        ws.actual_bedtime = Some(bedtime);
+        ws.calc_score(defaults)
+    }
+
+    // Minutes the bedtime was after (positive) or before (negative) the target.
+    pub fn bedtime_deviation(&self, defaults: &WorkSleepGoals) -> Option<i64> {
+        let target = &self.effective_goals(defaults).clamped().target_bedtime;
+        self.actual_bedtime
+            .as_ref()
+            .map(|actual_bedtime| actual_bedtime.signed_diff(target))
+    }
+
+    pub fn calc_score(&self, defaults: &WorkSleepGoals) -> i64 {
+        self.score_breakdown(defaults).total()
+    }
+
+    // A target of zero work periods counts as met, so it earns the full work points.
+    // A half-life of zero decays instantly: only a bedtime exactly on target earns sleep points.
+    pub fn score_breakdown(&self, defaults: &WorkSleepGoals) -> ScoreBreakdown {
+        let goals = self.effective_goals(defaults).clamped();
+        let work_points = goals.work_sleep_balance;
+        let sleep_points = 100 - goals.work_sleep_balance;
+        let work_score = if goals.target_work_count == 0 {
+            work_points as f64
+        } else {
+            let work_score =
+                self.weighted_work_count() * work_points as f64 / goals.target_work_count as f64;
+            if goals.cap_work_score {
+                work_score.min(work_points as f64)
+            } else {
+                work_score
+            }
+        };
+        let bedtime_deviation = self.bedtime_deviation(defaults);
+        let penalty_minutes = self
+            .actual_bedtime
+            .as_ref()
+            .map(|actual_bedtime| goals.bedtime_penalty_minutes(actual_bedtime));
+        let halflife = goals.bedtime_pts_halflife;
+        let decay = penalty_minutes.map(|deviation| bedtime_decay(deviation, halflife));
+        let sleep_score = penalty_minutes.map_or(0.0, |deviation| {
+            self::sleep_points(deviation, goals.work_sleep_balance, halflife)
+        });
+        // Round the total rather than each part, and give the sleep part whatever is left,
+        // so the parts always add up to the score.
+        let total = (work_score + sleep_score).round() as i64;
+        let work_score = work_score.round() as i64;
+        ScoreBreakdown {
+            work_points,
+            sleep_points,
+            work_score,
+            sleep_score: total - work_score,
+            bedtime_deviation,
+            decay,
+        }
+    }
+
+    // The score's formula in words and with the day's numbers, worked out the same way as
+    // `score_breakdown`.
+    pub fn show_score_calc(&self, defaults: &WorkSleepGoals, lang: Lang) -> Option<String> {
+        let goals = self.effective_goals(defaults).clamped();
+        let balance = goals.work_sleep_balance;
+        let (formula, work) = if goals.target_work_count == 0 {
+            (TextKey::ScoreFormula, balance.to_string())
+        } else {
+            let work = format!(
+                "{}*{}/{}",
+                balance,
+                format_work_count(self.weighted_work_count()),
+                goals.target_work_count
+            );
+            if goals.cap_work_score {
+                let capped = format!("min({}, {})", work, balance);
+                (TextKey::ScoreFormulaCapped, capped)
+            } else {
+                (TextKey::ScoreFormula, work)
+            }
+        };
+        self.actual_bedtime.as_ref().map(|actual_bedtime| {
+            format!(
+                "<p>{}</p><p>= {}+{}*(1/2)^({}/{})</p><p>= {}</p>",
+                lang.tr(formula),
+                work,
+                100 - balance,
+                goals.bedtime_penalty_minutes(actual_bedtime),
+                goals.bedtime_pts_halflife,
+                self.calc_score(defaults)
+            )
+        })
+    }
+}
+
+// A weighted work count to one decimal, leaving whole counts as they are.
+pub fn format_work_count(count: f64) -> String {
+    let tenths = (count * 10.0).round();
+    if tenths % 10.0 == 0.0 {
+        format!("{}", tenths / 10.0)
+    } else {
+        format!("{:.1}", tenths / 10.0)
+    }
+}
+
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+
+// The unrounded sleep score for a bedtime `deviation` minutes off target, out of the
+// points that `balance` leaves for sleep. The goals preview plots this too, so that it
+// always matches the real score.
+pub fn sleep_points(deviation: i64, balance: i64, halflife: i64) -> f64 {
+    (100 - balance) as f64 * bedtime_decay(deviation, halflife)
+}
+
+// The share of the sleep points kept: halved every `halflife` minutes of deviation.
+fn bedtime_decay(deviation: i64, halflife: i64) -> f64 {
+    if halflife == 0 {
+        if deviation == 0 {
+            1.0
+        } else {
+            0.0
+        }
+    } else {
+        (0.5f64).powf(deviation as f64 / halflife as f64)
+    }
+}
+
+// The mean of `bedtimes` on a 24-hour clock, so that e.g. 23:30 and 00:30 average to
+// midnight rather than noon. The result is placed on whichever side of midnight is
+// closest to the recorded dates. Returns `None` if there are no bedtimes or they
+// cancel out exactly (e.g. noon and midnight).
+pub fn mean_bedtime(bedtimes: &[Bedtime]) -> Option<Bedtime> {
+    if bedtimes.is_empty() {
+        return None;
+    }
+    let to_radians = std::f64::consts::TAU / MINUTES_PER_DAY as f64;
+    let (sin, cos) = bedtimes.iter().fold((0.0, 0.0), |(sin, cos), bedtime| {
+        let angle = bedtime.minutes_since_midnight() as f64 * to_radians;
+        (sin + angle.sin(), cos + angle.cos())
+    });
+    if sin.hypot(cos) < 1e-9 {
+        return None;
+    }
+    let clock_minutes = ((sin.atan2(cos) / to_radians).round() as i64).rem_euclid(MINUTES_PER_DAY);
+    let linear_mean = bedtimes
+        .iter()
+        .map(Bedtime::minutes_since_midnight)
+        .sum::<i64>() as f64
+        / bedtimes.len() as f64;
+    let minutes = [clock_minutes, clock_minutes + MINUTES_PER_DAY]
+        .iter()
+        .copied()
+        .min_by(|a, b| {
+            let distance = |m: i64| (m as f64 - linear_mean).abs();
+            distance(*a).total_cmp(&distance(*b))
+        })
+        .unwrap_or(clock_minutes);
+    Bedtime::from_minutes_since_midnight(minutes)
+}
+
+// Bedtimes need at least this many nights to say how consistent they are.
+pub const MIN_CONSISTENCY_NIGHTS: usize = 3;
+
+#[derive(Debug, PartialEq)]
+pub struct ConsistencyStats {
+    pub mean: Bedtime,
+    // The standard deviation of the distances from `mean` on a 24-hour clock, so that
+    // 23:50 and 00:10 are 20 minutes apart.
+    pub stddev_minutes: f64,
+    pub nights: usize,
+}
+
+impl ConsistencyStats {
+    // `None` for fewer than `MIN_CONSISTENCY_NIGHTS` bedtimes or ones without a mean.
+    pub fn of(bedtimes: &[Bedtime]) -> Option<Self> {
+        if bedtimes.len() < MIN_CONSISTENCY_NIGHTS {
+            return None;
+        }
+        let mean = mean_bedtime(bedtimes)?;
+        let squares = bedtimes
+            .iter()
+            .map(|bedtime| (bedtime.signed_diff(&mean) as f64).powi(2))
+            .sum::<f64>();
+        Some(Self {
+            mean,
+            stddev_minutes: (squares / bedtimes.len() as f64).sqrt(),
+            nights: bedtimes.len(),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ScoreBreakdown {
+    // The most points each part can earn at the target.
+    pub work_points: i64,
+    pub sleep_points: i64,
+    pub work_score: i64,
+    pub sleep_score: i64,
+    // Minutes the bedtime was after (positive) or before (negative) the target.
+    pub bedtime_deviation: Option<i64>,
+    // The fraction of sleep points kept after the half-life decay.
+    pub decay: Option<f64>,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> i64 {
+        self.work_score + self.sleep_score
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WorkProgress {
+    pub completed: f64,
+    pub planned: f64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct WeekSummary {
+    pub days_with_data: i64,
+    pub total_score: i64,
+    // Weighted, like the work count each day is scored on.
+    pub work_completed: f64,
+    pub work_target: i64,
+    pub average_bedtime: Option<Bedtime>,
+    pub earliest_bedtime: Option<Bedtime>,
+    pub latest_bedtime: Option<Bedtime>,
+    pub nights_with_bedtime: i64,
+    // Nights whose bedtime was within one half-life of that day's target.
+    pub nights_near_target: i64,
+    // Over the nights with both a bedtime and a wake time.
+    pub average_sleep_minutes: Option<i64>,
+    // Over the days with a mood.
+    pub average_mood: Option<f64>,
+    // Completed periods per category; periods without a category aren't counted.
+    pub completed_by_category: BTreeMap<String, i64>,
+}
+
+impl WeekSummary {
+    // Takes each day along with its score for `defaults`, the goals for days without
+    // their own.
+    pub fn from_days<'a>(
+        days: impl IntoIterator<Item = (&'a WorkSleep, i64)>,
+        defaults: &WorkSleepGoals,
+    ) -> Self {
+        let mut summary = Self::default();
+        let mut bedtimes = Vec::new();
+        let mut sleep_minutes = Vec::new();
+        let mut moods = Vec::new();
+        for (ws, score) in days {
+            sleep_minutes.extend(ws.sleep_minutes());
+            moods.extend(ws.mood);
+            summary.days_with_data += 1;
+            summary.total_score += score;
+            summary.work_completed += ws.weighted_work_count();
+            summary.work_target += ws.effective_goals(defaults).target_work_count;
+            for category in ws.completed.iter().filter_map(|cp| cp.category.as_ref()) {
+                *summary
+                    .completed_by_category
+                    .entry(category.clone())
+                    .or_insert(0) += 1;
+            }
+            if let Some(bedtime) = &ws.actual_bedtime {
+                summary.nights_with_bedtime += 1;
+                if ws.bedtime_near_target(defaults) {
+                    summary.nights_near_target += 1;
+                }
+                bedtimes.push(bedtime.clone());
+            }
+        }
+        summary.average_bedtime = mean_bedtime(&bedtimes);
+        if !sleep_minutes.is_empty() {
+            summary.average_sleep_minutes =
+                Some(sleep_minutes.iter().sum::<i64>() / sleep_minutes.len() as i64);
+        }
+        if !moods.is_empty() {
+            summary.average_mood =
+                Some(moods.iter().map(|&mood| f64::from(mood)).sum::<f64>() / moods.len() as f64);
+        }
+        summary.earliest_bedtime = bedtimes
+            .iter()
+            .min_by_key(|bedtime| bedtime.minutes_since_midnight())
+            .cloned();
+        summary.latest_bedtime = bedtimes
+            .into_iter()
+            .max_by_key(|bedtime| bedtime.minutes_since_midnight());
+        summary
+    }
+
+    // Averages only over the days that have data.
+    pub fn average_score(&self) -> Option<f64> {
+        if self.days_with_data == 0 {
+            None
+        } else {
+            Some(self.total_score as f64 / self.days_with_data as f64)
+        }
+    }
+}
+
+// A week of days compacted into the numbers its summary showed, once the days themselves
+// are dropped.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeekArchive {
+    pub start: NaiveDate,
+    pub total_score: i64,
+    pub avg_score: Option<f64>,
+    // Archives made before tasks had weights hold a whole number here.
+    pub work_done: f64,
+    pub nights_with_bedtime: i64,
+}
+
+impl WeekArchive {
+    // Takes the summary of the week's days, so the archive keeps what the live week showed.
+    pub fn new(start: NaiveDate, summary: &WeekSummary) -> Self {
+        Self {
+            start,
+            total_score: summary.total_score,
+            avg_score: summary.average_score(),
+            work_done: summary.work_completed,
+            nights_with_bedtime: summary.nights_with_bedtime,
+        }
+    }
+
+    pub fn end(&self) -> NaiveDate {
+        self.start
+            .checked_add_signed(Duration::days(6))
+            .unwrap_or(chrono::naive::MAX_DATE)
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end()
+    }
+}
+
+// What one range of days is compared on with another.
+#[derive(Debug, Default, PartialEq)]
+pub struct RangeSummary {
+    pub days_with_data: i64,
+    pub total_score: i64,
+    pub work_completed: f64,
+    // Minutes after (positive) or before (negative) the target, over the nights with a
+    // bedtime.
+    pub average_bedtime_deviation: Option<f64>,
+}
+
+impl RangeSummary {
+    // Takes each day along with its score for `defaults`.
+    pub fn from_days<'a>(
+        days: impl IntoIterator<Item = (&'a WorkSleep, i64)>,
+        defaults: &WorkSleepGoals,
+    ) -> Self {
+        let mut summary = Self::default();
+        let mut deviations = Vec::new();
+        for (ws, score) in days {
+            summary.days_with_data += 1;
+            summary.total_score += score;
+            summary.work_completed += ws.weighted_work_count();
+            deviations.extend(ws.bedtime_deviation(defaults));
+        }
+        if !deviations.is_empty() {
+            summary.average_bedtime_deviation =
+                Some(deviations.iter().sum::<i64>() as f64 / deviations.len() as f64);
+        }
+        summary
+    }
+
+    pub fn average_score(&self) -> Option<f64> {
+        if self.days_with_data == 0 {
+            None
+        } else {
+            Some(self.total_score as f64 / self.days_with_data as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summarize<'a>(days: impl IntoIterator<Item = &'a WorkSleep>) -> WeekSummary {
+        let days: Vec<_> = days
+            .into_iter()
+            .map(|ws| (ws, ws.calc_score(&defaults())))
+            .collect();
+        WeekSummary::from_days(days, &defaults())
+    }
+
+    fn bedtime(h: u32, m: u32, next_day: bool) -> Bedtime {
+        Bedtime {
+            time: NaiveTime::from_hms_opt(h, m, 0).unwrap(),
+            next_day,
+        }
+    }
+
+    fn defaults() -> WorkSleepGoals {
+        WorkSleepGoals::default()
+    }
+
+    // A day with its own goals, so that it is scored the same whatever the defaults.
+    fn work_sleep(
+        work_sleep_balance: i64,
+        target_work_count: i64,
+        actual_work_count: i64,
+        actual_bedtime: Option<Bedtime>,
+    ) -> WorkSleep {
+        let mut ws = WorkSleep::new();
+        ws.goals = Some(WorkSleepGoals {
+            work_sleep_balance,
+            target_work_count,
+            ..defaults()
+        });
+        ws.actual_work_count = actual_work_count;
+        ws.actual_bedtime = actual_bedtime;
+        ws
+    }
+
+    #[test]
+    fn weights_count_towards_the_work_target() {
+        let mut ws = work_sleep(70, 6, 2, None);
+        ws.extra_work_weight = 1.5;
+        assert_eq!(ws.weighted_work_count(), 3.5);
+        assert_eq!(ws.calc_score(&defaults()), 41);
+        ws.extra_work_weight = 1.0;
+        assert_eq!(ws.calc_score(&defaults()), 35);
+        assert_eq!(
+            ws.calc_score(&defaults()),
+            work_sleep(70, 6, 3, None).calc_score(&defaults())
+        );
+    }
+
+    #[test]
+    fn summaries_add_up_the_weighted_counts() {
+        let mut heavy = work_sleep(70, 6, 2, None);
+        heavy.extra_work_weight = 1.5;
+        let light = work_sleep(70, 6, 3, None);
+        let summary = summarize(vec![&heavy, &light]);
+        assert_eq!(summary.work_completed, 6.5);
+        let days = vec![(&heavy, 0), (&light, 0)];
+        assert_eq!(
+            RangeSummary::from_days(days, &defaults()).work_completed,
+            6.5
+        );
+
+        let json = r#"{"start":"2024-03-04","total_score":300,"avg_score":60.0,"work_done":9,"nights_with_bedtime":5}"#;
+        let archive: WeekArchive = serde_json::from_str(json).unwrap();
+        assert_eq!(archive.work_done, 9.0);
+    }
+
+    #[test]
+    fn work_counts_are_formatted_to_one_decimal() {
+        assert_eq!(format_work_count(4.0), "4");
+        assert_eq!(format_work_count(3.5), "3.5");
+        assert_eq!(format_work_count(0.1 + 0.2), "0.3");
+        assert_eq!(format_work_count(2.96), "3");
+    }
+
+    #[test]
+    fn work_progress_is_relative_to_the_target() {
+        let progress = work_sleep(70, 4, 1, None).work_progress(&defaults(), 2);
+        assert_eq!(
+            progress,
+            WorkProgress {
+                completed: 0.25,
+                planned: 0.5
+            }
+        );
+        let over = work_sleep(70, 4, 6, None).work_progress(&defaults(), 0);
+        assert_eq!(over.completed, 1.5);
+    }
+
+    #[test]
+    fn work_progress_with_a_zero_target_is_complete() {
+        let done = WorkProgress {
+            completed: 1.0,
+            planned: 0.0,
+        };
+        assert_eq!(
+            work_sleep(70, 0, 0, None).work_progress(&defaults(), 3),
+            done
+        );
+        assert_eq!(
+            work_sleep(70, 0, 5, None).work_progress(&defaults(), 0),
+            done
+        );
+    }
+
+    #[test]
+    fn bedtime_at_counts_past_midnight_as_the_next_day() {
+        let date = NaiveDate::from_ymd_opt(2021, 5, 3).unwrap();
+        let at = |day: u32, h: u32, m: u32| {
+            let now = NaiveDate::from_ymd_opt(2021, 5, day)
+                .unwrap()
+                .and_hms_opt(h, m, 0);
+            Bedtime::at(date, now.unwrap())
+        };
+        assert_eq!(at(3, 23, 15), Some(bedtime(23, 15, false)));
+        assert_eq!(at(4, 0, 45), Some(bedtime(0, 45, true)));
+        assert_eq!(at(2, 23, 0), None);
+        assert_eq!(at(5, 0, 0), None);
+        assert_eq!(
+            bedtime(23, 45, false).plus_minutes(30),
+            Some(bedtime(0, 15, true))
+        );
+    }
+
+    #[test]
+    fn score_with_bedtime_leaves_the_recorded_bedtime_alone() {
+        let ws = work_sleep(50, 0, 0, Some(bedtime(1, 0, true)));
+        let on_target = defaults().target_bedtime;
+        assert_eq!(ws.score_with_bedtime(&defaults(), on_target), 100);
+        assert_eq!(ws.actual_bedtime, Some(bedtime(1, 0, true)));
+        assert!(ws.calc_score(&defaults()) < 100);
+    }
+
+    #[test]
+    fn abs_diff_straddling_midnight() {
+        let target = bedtime(23, 30, false);
+        assert_eq!(target.abs_diff(&bedtime(0, 15, false)), 45);
+        assert_eq!(target.abs_diff(&bedtime(0, 15, true)), 45);
+        assert_eq!(bedtime(0, 15, false).abs_diff(&target), 45);
+        assert_eq!(bedtime(0, 15, true).abs_diff(&target), 45);
+    }
+
+    // Every whole-minute bedtime, on both sides of midnight. Bedtimes are entered to the
+    // minute, so checking all of them covers every case `abs_diff` can see.
+    fn all_bedtimes() -> impl Iterator<Item = Bedtime> {
+        (0..2 * MINUTES_PER_DAY)
+            .map(|minutes| Bedtime::from_minutes_since_midnight(minutes).unwrap())
+    }
+
+    // The clock distance between `a` and `b` from their minutes since the same midnight.
+    fn reference_abs_diff(a: &Bedtime, b: &Bedtime) -> i64 {
+        let minutes =
+            (a.minutes_since_midnight() - b.minutes_since_midnight()).abs() % MINUTES_PER_DAY;
+        minutes.min(MINUTES_PER_DAY - minutes)
+    }
+
+    #[test]
+    fn abs_diff_sweep_over_every_pair_of_bedtimes() {
+        let bedtimes: Vec<Bedtime> = all_bedtimes().collect();
+        for a in &bedtimes {
+            for b in &bedtimes {
+                let diff = a.abs_diff(b);
+                assert_eq!(diff, b.abs_diff(a), "{:?} {:?}", a, b);
+                assert!((0..=MINUTES_PER_DAY / 2).contains(&diff), "{:?} {:?}", a, b);
+                assert_eq!(diff, reference_abs_diff(a, b), "{:?} {:?}", a, b);
+                assert_eq!(a.signed_diff(b).abs(), diff, "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn abs_diff_ignores_the_next_day_flag_at_the_same_clock_time() {
+        for a in all_bedtimes() {
+            let flipped = Bedtime {
+                next_day: !a.next_day,
+                ..a.clone()
+            };
+            assert_eq!(a.abs_diff(&flipped), 0);
+        }
+    }
+
+    #[test]
+    fn abs_diff_identical_times_across_flag_boundary() {
+        assert_eq!(bedtime(0, 0, false).abs_diff(&bedtime(0, 0, true)), 0);
+        assert_eq!(bedtime(23, 0, true).abs_diff(&bedtime(23, 0, false)), 0);
+        assert_eq!(bedtime(23, 0, false).abs_diff(&bedtime(23, 0, false)), 0);
+    }
+
+    #[test]
+    fn calc_score_work_above_target() {
+        let mut ws = work_sleep(70, 6, 12, None);
+        ws.goals.as_mut().unwrap().cap_work_score = false;
+        assert_eq!(ws.calc_score(&defaults()), 140);
+    }
+
+    #[test]
+    fn the_shown_calculation_caps_work_like_the_score() {
+        let on_target = Some(bedtime(23, 0, false));
+        let ws = work_sleep(70, 6, 12, on_target.clone());
+        let calc = ws.show_score_calc(&defaults(), Lang::English).unwrap();
+        assert!(calc.contains("<p>= min(70*12/6, 70)+30*(1/2)^(0/"));
+        assert!(calc.ends_with("<p>= 100</p>"));
+
+        let mut ws = work_sleep(70, 6, 12, on_target);
+        ws.goals.as_mut().unwrap().cap_work_score = false;
+        let calc = ws.show_score_calc(&defaults(), Lang::English).unwrap();
+        assert!(calc.contains("<p>= 70*12/6+30*(1/2)^(0/"));
+        assert!(calc.ends_with("<p>= 170</p>"));
+    }
+
+    #[test]
+    fn calc_score_caps_work_at_target() {
+        assert_eq!(work_sleep(70, 6, 5, None).calc_score(&defaults()), 58);
+        assert_eq!(work_sleep(70, 6, 6, None).calc_score(&defaults()), 70);
+        assert_eq!(work_sleep(70, 6, 12, None).calc_score(&defaults()), 70);
+        let mut uncapped = work_sleep(70, 6, 5, None);
+        uncapped.goals.as_mut().unwrap().cap_work_score = false;
+        assert_eq!(uncapped.calc_score(&defaults()), 58);
+        uncapped.actual_work_count = 6;
+        assert_eq!(uncapped.calc_score(&defaults()), 70);
+    }
+
+    #[test]
+    fn goals_saved_without_the_cap_option_stay_uncapped() {
+        let json = r#"{"work_sleep_balance":70,"target_work_count":6,"target_bedtime":{"time":"23:00:00","next_day":false},"bedtime_pts_halflife":30}"#;
+        let goals: WorkSleepGoals = serde_json::from_str(json).unwrap();
+        assert!(!goals.cap_work_score);
+        assert_eq!(goals.bedtime_penalty_mode, BedtimePenaltyMode::Symmetric);
+    }
+
+    #[test]
+    fn calc_score_balance_extremes() {
+        let on_target = Some(bedtime(23, 0, false));
+        assert_eq!(
+            work_sleep(0, 6, 6, on_target.clone()).calc_score(&defaults()),
+            100
+        );
+        assert_eq!(work_sleep(0, 6, 6, None).calc_score(&defaults()), 0);
+        assert_eq!(
+            work_sleep(100, 6, 6, on_target).calc_score(&defaults()),
+            100
+        );
+        assert_eq!(work_sleep(100, 6, 3, None).calc_score(&defaults()), 50);
+    }
+
+    #[test]
+    fn calc_score_missing_bedtime_gives_no_sleep_points() {
+        assert_eq!(work_sleep(70, 6, 6, None).calc_score(&defaults()), 70);
+    }
+
+    #[test]
+    fn calc_score_halflife_of_one_minute() {
+        let mut ws = work_sleep(70, 6, 0, Some(bedtime(23, 1, false)));
+        ws.goals.as_mut().unwrap().bedtime_pts_halflife = 1;
+        assert_eq!(ws.calc_score(&defaults()), 15);
+        ws.actual_bedtime = Some(bedtime(23, 10, false));
+        assert_eq!(ws.calc_score(&defaults()), 0);
+    }
+
+    #[test]
+    fn sleep_points_match_the_sleep_score() {
+        for minutes in [0, 15, 30, 60, 95] {
+            let late = defaults().target_bedtime.plus_minutes(minutes).unwrap();
+            let ws = work_sleep(40, 6, 0, Some(late));
+            let halflife = defaults().bedtime_pts_halflife;
+            assert_eq!(
+                ws.score_breakdown(&defaults()).sleep_score,
+                sleep_points(minutes, 40, halflife).round() as i64
+            );
+        }
+        assert_eq!(sleep_points(30, 70, 30), 15.0);
+        assert_eq!(sleep_points(0, 70, 0), 30.0);
+        assert_eq!(sleep_points(1, 70, 0), 0.0);
+    }
+
+    #[test]
+    fn calc_score_target_work_count_edges() {
+        assert_eq!(work_sleep(70, 1, 1, None).calc_score(&defaults()), 70);
+        assert_eq!(work_sleep(70, 1, 0, None).calc_score(&defaults()), 0);
+        assert_eq!(work_sleep(70, 20, 1, None).calc_score(&defaults()), 4);
+    }
+
+    #[test]
+    fn calc_score_zero_target_counts_as_met() {
+        assert_eq!(work_sleep(70, 0, 0, None).calc_score(&defaults()), 70);
+        assert_eq!(work_sleep(70, 0, 3, None).calc_score(&defaults()), 70);
+    }
+
+    #[test]
+    fn calc_score_zero_halflife_decays_instantly() {
+        let mut ws = work_sleep(70, 6, 0, Some(bedtime(23, 0, false)));
+        ws.goals.as_mut().unwrap().bedtime_pts_halflife = 0;
+        assert_eq!(ws.calc_score(&defaults()), 30);
+        ws.actual_bedtime = Some(bedtime(23, 1, false));
+        assert_eq!(ws.calc_score(&defaults()), 0);
+    }
+
+    #[test]
+    fn calc_score_clamps_negative_and_out_of_range_goals() {
+        assert_eq!(work_sleep(-10, 6, 6, None).calc_score(&defaults()), 0);
+        assert_eq!(work_sleep(150, 6, 6, None).calc_score(&defaults()), 100);
+        assert_eq!(work_sleep(70, -2, 1, None).calc_score(&defaults()), 70);
+        let mut ws = work_sleep(70, 6, 0, Some(bedtime(23, 0, false)));
+        ws.goals.as_mut().unwrap().bedtime_pts_halflife = -5;
+        assert_eq!(ws.calc_score(&defaults()), 30);
+    }
+
+    #[test]
+    fn score_breakdown_splits_work_and_sleep() {
+        let breakdown =
+            work_sleep(70, 6, 5, Some(bedtime(23, 30, false))).score_breakdown(&defaults());
+        assert_eq!(
+            breakdown,
+            ScoreBreakdown {
+                work_points: 70,
+                sleep_points: 30,
+                work_score: 58,
+                sleep_score: 15,
+                bedtime_deviation: Some(30),
+                decay: Some(0.5),
+            }
+        );
+        let early = work_sleep(70, 6, 0, Some(bedtime(22, 0, false))).score_breakdown(&defaults());
+        assert_eq!(early.bedtime_deviation, Some(-60));
+        let missing = work_sleep(70, 6, 6, None).score_breakdown(&defaults());
+        assert_eq!((missing.sleep_score, missing.decay), (0, None));
+    }
+
+    #[test]
+    fn score_breakdown_sums_to_calc_score() {
+        for balance in [0, 33, 70, 100] {
+            for count in 0..8 {
+                for minute in (0..60).step_by(7) {
+                    let ws = work_sleep(balance, 7, count, Some(bedtime(23, minute, false)));
+                    assert_eq!(
+                        ws.score_breakdown(&defaults()).total(),
+                        ws.calc_score(&defaults())
+                    );
+                    assert!(ws.score_breakdown(&defaults()).sleep_score >= 0);
+                }
+            }
+        }
+    }
+
+    fn with_penalty_mode(mode: BedtimePenaltyMode, h: u32, m: u32) -> WorkSleep {
+        let mut ws = work_sleep(70, 6, 0, Some(bedtime(h, m, false)));
+        ws.goals.as_mut().unwrap().bedtime_penalty_mode = mode;
+        ws
+    }
+
+    #[test]
+    fn symmetric_penalty_mode_penalizes_early_and_late_alike() {
+        let mode = BedtimePenaltyMode::Symmetric;
+        assert_eq!(with_penalty_mode(mode, 22, 30).calc_score(&defaults()), 15);
+        assert_eq!(with_penalty_mode(mode, 23, 0).calc_score(&defaults()), 30);
+        assert_eq!(with_penalty_mode(mode, 23, 30).calc_score(&defaults()), 15);
+    }
+
+    #[test]
+    fn late_only_penalty_mode_gives_full_points_for_early_bedtimes() {
+        let mode = BedtimePenaltyMode::LateOnly;
+        assert_eq!(with_penalty_mode(mode, 21, 0).calc_score(&defaults()), 30);
+        assert_eq!(with_penalty_mode(mode, 22, 30).calc_score(&defaults()), 30);
+        assert_eq!(with_penalty_mode(mode, 23, 0).calc_score(&defaults()), 30);
+        assert_eq!(with_penalty_mode(mode, 23, 30).calc_score(&defaults()), 15);
+        assert!(with_penalty_mode(mode, 21, 0).bedtime_near_target(&defaults()));
+    }
+
+    #[test]
+    fn signed_diff_across_midnight() {
+        let target = bedtime(23, 30, false);
+        assert_eq!(bedtime(0, 15, true).signed_diff(&target), 45);
+        assert_eq!(bedtime(0, 15, false).signed_diff(&target), 45);
+        assert_eq!(bedtime(22, 45, false).signed_diff(&target), -45);
+    }
+
+    #[test]
+    fn clamped_goals_stay_in_range() {
+        let goals = WorkSleepGoals {
+            work_sleep_balance: 120,
+            target_work_count: -1,
+            bedtime_pts_halflife: -30,
+            ..WorkSleepGoals::default()
+        }
+        .clamped();
+        assert_eq!(goals.work_sleep_balance, 100);
+        assert_eq!(goals.target_work_count, 0);
+        assert_eq!(goals.bedtime_pts_halflife, 0);
+    }
+
+    #[test]
+    fn week_summary_averages_days_with_data() {
+        let days = vec![work_sleep(70, 6, 6, None), work_sleep(70, 6, 3, None)];
+        let summary = summarize(&days);
+        assert_eq!(
+            summary,
+            WeekSummary {
+                days_with_data: 2,
+                total_score: 105,
+                work_completed: 9.0,
+                work_target: 12,
+                ..WeekSummary::default()
+            }
+        );
+        assert_eq!(summary.average_score(), Some(52.5));
+        assert_eq!(WeekSummary::default().average_score(), None);
+    }
+
+    #[test]
+    fn week_summary_counts_completed_periods_by_category() {
+        let completed = |category: Option<&str>| CompletedPeriod {
+            note: String::new(),
+            id: Uuid::new_v4(),
+            name: "task".to_owned(),
+            completed_at: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            duration_minutes: None,
+            category: category.map(str::to_owned),
+        };
+        let mut monday = work_sleep(70, 6, 3, None);
+        monday.completed = vec![
+            completed(Some("thesis")),
+            completed(Some("email")),
+            completed(None),
+        ];
+        let mut tuesday = work_sleep(70, 6, 1, None);
+        tuesday.completed = vec![completed(Some("thesis"))];
+        let summary = summarize(&[monday, tuesday]);
+        let expected: BTreeMap<String, i64> =
+            vec![("email".to_owned(), 1), ("thesis".to_owned(), 2)]
+                .into_iter()
+                .collect();
+        assert_eq!(summary.completed_by_category, expected);
+    }
+
+    #[test]
+    fn mean_bedtime_across_midnight() {
+        assert_eq!(
+            mean_bedtime(&[bedtime(23, 30, false), bedtime(0, 30, true)]),
+            Some(bedtime(0, 0, true))
+        );
+        assert_eq!(
+            mean_bedtime(&[bedtime(23, 0, false), bedtime(23, 40, false)]),
+            Some(bedtime(23, 20, false))
+        );
+        assert_eq!(
+            mean_bedtime(&[bedtime(1, 0, true), bedtime(2, 0, true)]),
+            Some(bedtime(1, 30, true))
+        );
+    }
+
+    #[test]
+    fn mean_bedtime_ignores_a_missing_next_day_flag() {
+        assert_eq!(
+            mean_bedtime(&[bedtime(23, 30, false), bedtime(0, 30, false)]),
+            Some(bedtime(0, 0, false))
+        );
+    }
+
+    #[test]
+    fn consistency_measures_distances_across_midnight() {
+        let stats = ConsistencyStats::of(&[
+            bedtime(23, 50, false),
+            bedtime(0, 0, true),
+            bedtime(0, 10, true),
+        ])
+        .unwrap();
+        assert_eq!(stats.mean, bedtime(0, 0, true));
+        assert!((stats.stddev_minutes - (200.0f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert_eq!(stats.nights, 3);
+    }
+
+    #[test]
+    fn consistency_of_identical_bedtimes_is_perfect() {
+        // A bedtime on the wrong side of midnight is still the same time of night.
+        let stats = ConsistencyStats::of(&[
+            bedtime(0, 30, true),
+            bedtime(0, 30, false),
+            bedtime(0, 30, true),
+        ])
+        .unwrap();
+        assert_eq!(stats.stddev_minutes, 0.0);
+    }
+
+    #[test]
+    fn consistency_needs_three_nights() {
+        assert_eq!(
+            ConsistencyStats::of(&[bedtime(23, 0, false), bedtime(23, 0, false)]),
+            None
+        );
+        let spread = ConsistencyStats::of(&[
+            bedtime(22, 0, false),
+            bedtime(23, 0, false),
+            bedtime(0, 0, true),
+        ])
+        .unwrap();
+        assert!((spread.stddev_minutes - (2400.0f64).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mean_bedtime_of_nothing_or_opposite_times() {
+        assert_eq!(mean_bedtime(&[]), None);
+        assert_eq!(
+            mean_bedtime(&[bedtime(12, 0, false), bedtime(0, 0, false)]),
+            None
+        );
+    }
+
+    #[test]
+    fn week_summary_bedtime_stats() {
+        let days = vec![
+            work_sleep(70, 6, 0, Some(bedtime(22, 30, false))),
+            work_sleep(70, 6, 0, Some(bedtime(1, 0, true))),
+            work_sleep(70, 6, 0, Some(bedtime(23, 20, false))),
+            work_sleep(70, 6, 0, None),
+        ];
+        let summary = summarize(&days);
+        assert_eq!(summary.earliest_bedtime, Some(bedtime(22, 30, false)));
+        assert_eq!(summary.latest_bedtime, Some(bedtime(1, 0, true)));
+        assert_eq!(summary.average_bedtime, Some(bedtime(23, 36, false)));
+        assert_eq!(summary.nights_near_target, 2);
+        assert_eq!(summary.nights_with_bedtime, 3);
+    }
+
+    #[test]
+    fn week_archives_keep_the_summary_numbers() {
+        let days = vec![
+            work_sleep(70, 6, 6, Some(bedtime(22, 30, false))),
+            work_sleep(70, 6, 3, None),
+        ];
+        let summary = summarize(&days);
+        let start = NaiveDate::from_ymd_opt(2021, 3, 1).unwrap();
+        let archive = WeekArchive::new(start, &summary);
+        assert_eq!(archive.total_score, summary.total_score);
+        assert_eq!(archive.avg_score, summary.average_score());
+        assert_eq!(archive.work_done, 9.0);
+        assert_eq!(archive.nights_with_bedtime, 1);
+        assert!(archive.contains(NaiveDate::from_ymd_opt(2021, 3, 7).unwrap()));
+        assert!(!archive.contains(NaiveDate::from_ymd_opt(2021, 3, 8).unwrap()));
+    }
+
+    #[test]
+    fn sleep_minutes_span_midnight() {
+        let morning = bedtime(7, 0, true);
+        assert_eq!(
+            bedtime(23, 0, false).sleep_minutes_until(&morning),
+            Some(480)
+        );
+        assert_eq!(
+            bedtime(1, 30, true).sleep_minutes_until(&morning),
+            Some(330)
+        );
+        // Waking before going to bed, or after an impossibly long night.
+        assert_eq!(bedtime(8, 0, true).sleep_minutes_until(&morning), None);
+        assert_eq!(bedtime(7, 0, true).sleep_minutes_until(&morning), None);
+        assert_eq!(bedtime(9, 0, false).sleep_minutes_until(&morning), None);
+    }
+
+    #[test]
+    fn week_summary_averages_sleep_over_nights_with_a_wake_time() {
+        let mut days = vec![
+            work_sleep(70, 6, 0, Some(bedtime(23, 0, false))),
+            work_sleep(70, 6, 0, Some(bedtime(0, 0, true))),
+            work_sleep(70, 6, 0, Some(bedtime(22, 0, false))),
+        ];
+        days[0].wake_time = Some(bedtime(7, 0, true));
+        days[1].wake_time = Some(bedtime(7, 0, true));
+        let summary = summarize(&days);
+        assert_eq!(summary.average_sleep_minutes, Some(450));
+        assert_eq!(days[2].sleep_minutes(), None);
+    }
+
+    #[test]
+    fn week_summary_averages_moods_without_scoring_them() {
+        let mut days = vec![
+            work_sleep(70, 6, 3, None),
+            work_sleep(70, 6, 3, None),
+            work_sleep(70, 6, 3, None),
+        ];
+        let score = days[0].calc_score(&defaults());
+        days[0].mood = Some(2);
+        days[1].mood = Some(5);
+        let summary = summarize(&days);
+        assert_eq!(summary.average_mood, Some(3.5));
+        assert_eq!(days[0].calc_score(&defaults()), score);
+    }
+
+    #[test]
+    fn days_without_custom_goals_follow_the_defaults() {
+        let mut ws = WorkSleep::new();
+        ws.actual_work_count = 3;
+        let mut goals = defaults();
+        assert_eq!(ws.calc_score(&goals), 35);
+        goals.target_work_count = 3;
+        assert_eq!(ws.calc_score(&goals), 70);
+    }
+
+    #[test]
+    fn custom_goals_ignore_the_defaults() {
+        let ws = work_sleep(70, 3, 3, None);
+        let mut goals = defaults();
+        goals.target_work_count = 12;
+        goals.work_sleep_balance = 10;
+        assert_eq!(ws.calc_score(&goals), 70);
+        assert_eq!(ws.effective_goals(&goals).target_work_count, 3);
+    }
+
+    #[test]
+    fn legacy_goals_keep_their_scores() {
+        let legacy = |goals: &str, overridden: bool| -> WorkSleep {
+            let json = format!(
+                r#"{{"goals":{},"goals_overridden":{},"actual_work_count":3,"actual_bedtime":null}}"#,
+                goals, overridden
+            );
+            serde_json::from_str(&json).unwrap()
+        };
+        let default_json = serde_json::to_string(&defaults()).unwrap();
+        let mut goals = defaults();
+        goals.target_work_count = 3;
+        let edited_json = serde_json::to_string(&goals).unwrap();
+
+        let mut copied = legacy(&default_json, false);
+        copied.migrate_legacy_goals(&defaults());
+        assert_eq!(copied.goals, None);
+
+        let mut stale = legacy(&edited_json, false);
+        stale.migrate_legacy_goals(&defaults());
+        assert_eq!(stale.goals, Some(goals.clone()));
+        assert_eq!(stale.calc_score(&defaults()), 70);
+
+        let mut overridden = legacy(&default_json, true);
+        overridden.migrate_legacy_goals(&defaults());
+        assert_eq!(overridden.goals, Some(defaults()));
+
+        let saved = serde_json::to_string(&stale).unwrap();
+        assert!(!saved.contains("goals_overridden"));
+        let mut reloaded: WorkSleep = serde_json::from_str(&saved).unwrap();
+        reloaded.migrate_legacy_goals(&defaults());
+        assert_eq!(reloaded.goals, Some(goals));
+    }
+}
@@ -0,0 +1,192 @@
+// Reading the sleep sessions that sleep trackers export as CSV. Like `scoring`, this
+// module is kept free of `seed` and `web_sys` so that it can be unit tested natively.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+use crate::scoring::{Bedtime, MAX_SLEEP_MINUTES};
+
+// Sessions starting before this hour belong to the night of the day before.
+const NIGHT_START_HOUR: u32 = 12;
+const TIMESTAMP_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S", "%H:%M"];
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SleepCsv {
+    // One bedtime per night, oldest first.
+    pub bedtimes: Vec<(NaiveDate, Bedtime)>,
+    // Sessions left out for a longer one on the same night, such as naps.
+    pub duplicates: usize,
+    pub failed: usize,
+}
+
+// Reads rows of `date,sleep start,sleep end`, skipping a header row. Starts and ends are
+// local timestamps or times of day on the row's date. When a night has several
+// sessions, the longest one gives its bedtime.
+pub fn parse_sleep_csv(csv: &str) -> SleepCsv {
+    let mut nights: BTreeMap<NaiveDate, (Bedtime, Duration)> = BTreeMap::new();
+    let mut result = SleepCsv::default();
+    for (i, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match parse_session(line) {
+            Some((night, bedtime, length)) => match nights.entry(night) {
+                Entry::Vacant(entry) => {
+                    entry.insert((bedtime, length));
+                }
+                Entry::Occupied(mut entry) => {
+                    result.duplicates += 1;
+                    if length > entry.get().1 {
+                        entry.insert((bedtime, length));
+                    }
+                }
+            },
+            None if i == 0 && !line.contains(|c: char| c.is_ascii_digit()) => {}
+            None => result.failed += 1,
+        }
+    }
+    result.bedtimes = nights
+        .into_iter()
+        .map(|(night, (bedtime, _))| (night, bedtime))
+        .collect();
+    result
+}
+
+// The night a session belongs to, its bedtime on that night and how long it lasted.
+fn parse_session(line: &str) -> Option<(NaiveDate, Bedtime, Duration)> {
+    let fields: Vec<&str> = line
+        .split(',')
+        .map(|field| field.trim().trim_matches('"'))
+        .collect();
+    let (date, start, end) = match fields[..] {
+        [date, start, end, ..] => (date, start, end),
+        _ => return None,
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let (start, _) = parse_moment(start, date)?;
+    let (mut end, end_is_time_only) = parse_moment(end, date)?;
+    // A bare end time before the start is on the next morning.
+    if end_is_time_only && end <= start {
+        end += Duration::days(1);
+    }
+    let length = end - start;
+    if length <= Duration::zero() || length > Duration::minutes(MAX_SLEEP_MINUTES) {
+        return None;
+    }
+    let next_day = start.hour() < NIGHT_START_HOUR;
+    let night = if next_day {
+        start.date().pred_opt()?
+    } else {
+        start.date()
+    };
+    let time = NaiveTime::from_hms_opt(start.hour(), start.minute(), 0)?;
+    Some((night, Bedtime { time, next_day }, length))
+}
+
+// A timestamp, or a time of day on `date` along with `true`.
+fn parse_moment(s: &str, date: NaiveDate) -> Option<(NaiveDateTime, bool)> {
+    if let Some(moment) = TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    {
+        return Some((moment, false));
+    }
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+        .map(|time| (date.and_time(time), true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn bedtime(h: u32, m: u32, next_day: bool) -> Bedtime {
+        Bedtime {
+            time: NaiveTime::from_hms_opt(h, m, 0).unwrap(),
+            next_day,
+        }
+    }
+
+    #[test]
+    fn reads_timezone_less_timestamps() {
+        let parsed = parse_sleep_csv(
+            "date,sleep start,sleep end\n\
+             2024-03-04,2024-03-04 23:15:42,2024-03-05 07:00:00\n\
+             2024-03-05,\"2024-03-05T22:40\",\"2024-03-06T06:30\"\n",
+        );
+        assert_eq!(
+            parsed,
+            SleepCsv {
+                bedtimes: vec![
+                    (ymd(2024, 3, 4), bedtime(23, 15, false)),
+                    (ymd(2024, 3, 5), bedtime(22, 40, false)),
+                ],
+                duplicates: 0,
+                failed: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn sessions_starting_after_midnight_belong_to_the_night_before() {
+        let parsed = parse_sleep_csv(
+            "2024-03-05,2024-03-05 00:45,2024-03-05 08:00\n\
+             2024-03-06,01:30,09:00\n\
+             2024-03-06,23:30,06:45\n",
+        );
+        assert_eq!(
+            parsed.bedtimes,
+            vec![
+                (ymd(2024, 3, 4), bedtime(0, 45, true)),
+                (ymd(2024, 3, 5), bedtime(1, 30, true)),
+                (ymd(2024, 3, 6), bedtime(23, 30, false)),
+            ]
+        );
+        assert_eq!(parsed.failed, 0);
+    }
+
+    #[test]
+    fn the_longest_session_of_a_night_wins() {
+        let parsed = parse_sleep_csv(
+            "2024-03-04,2024-03-04 15:00,2024-03-04 15:40\n\
+             2024-03-04,2024-03-04 23:00,2024-03-05 06:00\n\
+             2024-03-05,2024-03-05 03:00,2024-03-05 05:00\n",
+        );
+        assert_eq!(
+            parsed.bedtimes,
+            vec![(ymd(2024, 3, 4), bedtime(23, 0, false))]
+        );
+        assert_eq!(parsed.duplicates, 2);
+    }
+
+    #[test]
+    fn counts_rows_that_fail_to_parse() {
+        let parsed = parse_sleep_csv(
+            "2024-03-04,2024-03-04 23:00\n\
+             not a date,23:00,07:00\n\
+             2024-03-04,2024-03-05 07:00,2024-03-04 23:00\n\
+             2024-03-04,2024-03-04 08:00,2024-03-06 08:00\n\
+             \n\
+             2024-03-04,23:00,07:00\n",
+        );
+        assert_eq!(parsed.failed, 4);
+        assert_eq!(
+            parsed.bedtimes,
+            vec![(ymd(2024, 3, 4), bedtime(23, 0, false))]
+        );
+    }
+}